@@ -681,6 +681,7 @@ impl<'e, E: ExecutionState<'e>> analysis::Analyzer<'e> for PlayerAiAnalyzer<'e,
 pub(crate) struct AiscriptSwitchAnalysis<Va: VirtualAddressTrait> {
     pub ai_attack_prepare: Option<Va>,
     pub ai_attack_clear: Option<Va>,
+    pub ai_request_unit: Option<Va>,
 }
 
 pub(crate) fn aiscript_switch_analysis<'e, E: ExecutionState<'e>>(
@@ -693,6 +694,7 @@ pub(crate) fn aiscript_switch_analysis<'e, E: ExecutionState<'e>>(
     let mut result = AiscriptSwitchAnalysis {
         ai_attack_prepare: None,
         ai_attack_clear: None,
+        ai_request_unit: None,
     };
 
     let mut analyzer = AttackPrepareAnalyzer::<E> {
@@ -719,9 +721,56 @@ pub(crate) fn aiscript_switch_analysis<'e, E: ExecutionState<'e>>(
     let mut analysis = FuncAnalysis::new(binary, ctx, attack_clear);
     analysis.analyze(&mut analyzer);
 
+    // Opcode 0x6 `build`; it adds a request for `count` units of `unit_id`
+    // at `priority` to the script's town, which ai_spend_money later consumes.
+    if let Some(build) = crate::switch::simple_switch_branch(binary, aiscript_switch_table, 0x6) {
+        let mut analysis = FuncAnalysis::new(binary, ctx, build);
+        let mut analyzer = FindAiRequestUnit::<E> {
+            result: None,
+            call_limit: 4,
+        };
+        analysis.analyze(&mut analyzer);
+        result.ai_request_unit = analyzer.result;
+    }
+
     result
 }
 
+struct FindAiRequestUnit<'e, E: ExecutionState<'e>> {
+    result: Option<E::VirtualAddress>,
+    call_limit: u8,
+}
+
+impl<'e, E: ExecutionState<'e>> analysis::Analyzer<'e> for FindAiRequestUnit<'e, E> {
+    type State = analysis::DefaultState;
+    type Exec = E;
+    fn operation(&mut self, ctrl: &mut Control<'e, '_, '_, Self>, op: &Operation<'e>) {
+        if let Operation::Call(dest) = *op {
+            // Find first call that gets `script.town` as one of the arguments
+            // (town, priority, unit_id, count); the branch checks that town isn't null
+            // before that, and the null branch just skips the request.
+            let town_off = E::struct_layouts().ai_script_town();
+            let has_town_arg = (0..4)
+                .any(|i| {
+                    let arg = ctrl.resolve_arg(i);
+                    ctrl.if_mem_word_offset(arg, town_off).is_some()
+                });
+            if has_town_arg {
+                let result = ctrl.resolve_va(dest);
+                if single_result_assign(result, &mut self.result) {
+                    ctrl.end_analysis();
+                }
+                return;
+            }
+            if self.call_limit == 0 {
+                ctrl.end_analysis();
+                return;
+            }
+            self.call_limit -= 1;
+        }
+    }
+}
+
 struct AttackPrepareAnalyzer<'a, 'e, E: ExecutionState<'e>> {
     result: &'a mut AiscriptSwitchAnalysis<E::VirtualAddress>,
     state: AiscriptSwitchState,
@@ -1240,6 +1289,23 @@ impl<'a, 'acx, 'e, E: ExecutionState<'e>> analysis::Analyzer<'e> for
     }
 }
 
+/// Returns the "Run AI Script" (0xf) action handler.
+///
+/// Check that it isn't shared with "Run AI Script At Location" (0x10), which would mean
+/// that the table isn't laid out as expected.
+pub(crate) fn trigger_run_ai_script<'e, E: ExecutionState<'e>>(
+    analysis: &AnalysisCtx<'e, E>,
+    actions: E::VirtualAddress,
+) -> Option<E::VirtualAddress> {
+    let binary = analysis.binary;
+    let run_ai_script = binary.read_address(actions + E::VirtualAddress::SIZE * 0xf).ok()?;
+    let run_ai_script_at = binary.read_address(actions + E::VirtualAddress::SIZE * 0x10).ok()?;
+    if run_ai_script == run_ai_script_at || run_ai_script.as_u64() == 0 {
+        return None;
+    }
+    Some(run_ai_script)
+}
+
 pub(crate) fn give_ai<'e, E: ExecutionState<'e>>(
     analysis: &AnalysisCtx<'e, E>,
     actions: E::VirtualAddress,
@@ -1797,15 +1863,23 @@ impl<'a, 'e, E: ExecutionState<'e>> scarf::Analyzer<'e> for AddMilitaryAnalyzer<
     }
 }
 
-pub(crate) fn ai_spell_cast<'e, E: ExecutionState<'e>>(
+pub(crate) struct OrderGuard<Va: VirtualAddressTrait> {
+    pub ai_spell_cast: Option<Va>,
+    pub ai_return_to_guard: Option<Va>,
+}
+
+pub(crate) fn analyze_order_guard<'e, E: ExecutionState<'e>>(
     analysis: &AnalysisCtx<'e, E>,
     order_guard: E::VirtualAddress,
-) -> Option<E::VirtualAddress> {
+) -> OrderGuard<E::VirtualAddress> {
     let ctx = analysis.ctx;
-    // Order 0xa0 (Guard) immediately calls ai_spell_cast
+    // Order 0xa0 (Guard) immediately calls ai_spell_cast, and if that didn't
+    // do anything, ai_return_to_guard is assumed to be the next call taking
+    // the unit as `this` / arg1.
 
     struct Analyzer<'exec, E: ExecutionState<'exec>> {
-        result: Option<E::VirtualAddress>,
+        result: OrderGuard<E::VirtualAddress>,
+        call_limit: u8,
     }
     impl<'exec, E: ExecutionState<'exec>> scarf::Analyzer<'exec> for Analyzer<'exec, E> {
         type State = analysis::DefaultState;
@@ -1813,17 +1887,42 @@ pub(crate) fn ai_spell_cast<'e, E: ExecutionState<'e>>(
         fn operation(&mut self, ctrl: &mut Control<'exec, '_, '_, Self>, op: &Operation<'exec>) {
             match *op {
                 Operation::Call(dest) => {
-                    if let Some(dest) = ctrl.resolve(dest).if_constant() {
-                        let arg1 = ctrl.resolve_arg(0);
+                    let dest = match ctrl.resolve(dest).if_constant() {
+                        Some(s) => E::VirtualAddress::from_u64(s),
+                        None => {
+                            if self.result.ai_spell_cast.is_none() {
+                                ctrl.end_analysis();
+                            }
+                            return;
+                        }
+                    };
+                    let ctx = ctrl.ctx();
+                    let this = ctx.register(1);
+                    let arg1 = ctrl.resolve_arg(0);
+                    if self.result.ai_spell_cast.is_none() {
                         let arg2 = ctrl.resolve_arg(1);
-                        let this = ctrl.ctx().register(1);
                         let args_ok = arg1 == this &&
                             arg2.if_constant() == Some(0);
                         if args_ok {
-                            self.result = Some(E::VirtualAddress::from_u64(dest));
+                            self.result.ai_spell_cast = Some(dest);
+                            // Assume that no spell was cast and continue
+                            ctrl.do_call_with_result(ctx.const_0());
+                        } else {
+                            ctrl.end_analysis();
+                        }
+                    } else {
+                        let this_ok = arg1 == this || ctrl.resolve_register(1) == this;
+                        if this_ok && Some(dest) != self.result.ai_spell_cast {
+                            self.result.ai_return_to_guard = Some(dest);
+                            ctrl.end_analysis();
+                            return;
+                        }
+                        if self.call_limit == 0 {
+                            ctrl.end_analysis();
+                        } else {
+                            self.call_limit -= 1;
                         }
                     }
-                    ctrl.end_analysis();
                 }
                 _ => (),
             }
@@ -1831,7 +1930,11 @@ pub(crate) fn ai_spell_cast<'e, E: ExecutionState<'e>>(
     }
 
     let mut analyzer = Analyzer::<E> {
-        result: None,
+        result: OrderGuard {
+            ai_spell_cast: None,
+            ai_return_to_guard: None,
+        },
+        call_limit: 4,
     };
     let mut analysis = FuncAnalysis::new(analysis.binary, ctx, order_guard);
     analysis.analyze(&mut analyzer);
@@ -2150,6 +2253,7 @@ pub(crate) struct AiStepRegion<Va: VirtualAddressTrait> {
     pub ai_region_abandon_if_overwhelmed: Option<Va>,
     pub ai_region_pick_attack_target: Option<Va>,
     pub change_ai_region_state: Option<Va>,
+    pub ai_manage_attack: Option<Va>,
 }
 
 pub(crate) fn analyze_ai_step_region<'e, E: ExecutionState<'e>>(
@@ -2165,6 +2269,7 @@ pub(crate) fn analyze_ai_step_region<'e, E: ExecutionState<'e>>(
         ai_region_abandon_if_overwhelmed: None,
         ai_region_pick_attack_target: None,
         change_ai_region_state: None,
+        ai_manage_attack: None,
     };
 
     let mut analysis = FuncAnalysis::new(binary, ctx, ai_step_region);
@@ -2174,6 +2279,7 @@ pub(crate) fn analyze_ai_step_region<'e, E: ExecutionState<'e>>(
         inline_depth: 0,
         state: StepAiRegionState::FindSwitch,
         call_tracker: CallTracker::with_capacity(actx, 0x1000_0000, 0x20),
+        attacking_branch: None,
     };
     analysis.analyze(&mut analyzer);
     result
@@ -2200,6 +2306,8 @@ enum StepAiRegionState {
     ChangeRegionState,
     /// Next call with a1 = region
     PickAttackTarget,
+    /// Continue from switch state 9 (Attacking), first call with a1 = region
+    ManageAttack,
 }
 
 struct StepRegionInnerAnalyzer<'a, 'acx, 'e, E: ExecutionState<'e>> {
@@ -2209,6 +2317,8 @@ struct StepRegionInnerAnalyzer<'a, 'acx, 'e, E: ExecutionState<'e>> {
     inline_depth: u8,
     state: StepAiRegionState,
     call_tracker: CallTracker<'acx, 'e, E>,
+    /// State at switch and the branch for state 9
+    attacking_branch: Option<(E, E::VirtualAddress)>,
 }
 
 impl<'a, 'acx, 'e, E: ExecutionState<'e>> analysis::Analyzer<'e> for
@@ -2220,11 +2330,13 @@ impl<'a, 'acx, 'e, E: ExecutionState<'e>> analysis::Analyzer<'e> for
         let ctx = ctrl.ctx();
         let is_jump_state = match self.state {
             StepAiRegionState::FindSwitch | StepAiRegionState::AbandonFlagCheck |
-                StepAiRegionState::PickAttackTargetUnwalkableJump => true,
+                StepAiRegionState::PickAttackTargetUnwalkableJump |
+                StepAiRegionState::ManageAttack => true,
             _ => false,
         };
         let is_call_state = !is_jump_state ||
-            self.state == StepAiRegionState::PickAttackTargetUnwalkableJump;
+            self.state == StepAiRegionState::PickAttackTargetUnwalkableJump ||
+            self.state == StepAiRegionState::ManageAttack;
         if self.state == StepAiRegionState::UpdateStrength && self.inline_depth > 0 {
             if let Operation::Move(DestOperand::Memory(ref mem), value) = *op {
                 let value = ctrl.resolve(value);
@@ -2265,7 +2377,8 @@ impl<'a, 'acx, 'e, E: ExecutionState<'e>> analysis::Analyzer<'e> for
                         }
                         StepAiRegionState::UpdateTarget | StepAiRegionState::AbandonCall |
                             StepAiRegionState::ChangeRegionState |
-                            StepAiRegionState::PickAttackTarget =>
+                            StepAiRegionState::PickAttackTarget |
+                            StepAiRegionState::ManageAttack =>
                         {
                             let a1 = ctrl.resolve_arg(0);
                             if a1 == self.region_operand {
@@ -2281,10 +2394,18 @@ impl<'a, 'acx, 'e, E: ExecutionState<'e>> analysis::Analyzer<'e> for
                                         self.state = StepAiRegionState::PickAttackTarget;
                                         self.result.change_ai_region_state = Some(dest);
                                     }
+                                } else if self.state == StepAiRegionState::PickAttackTarget {
+                                    self.result.ai_region_pick_attack_target = Some(dest);
+                                    if let Some((state, addr)) = self.attacking_branch.take() {
+                                        self.state = StepAiRegionState::ManageAttack;
+                                        ctrl.continue_with_state((state, Default::default(), addr));
+                                    } else {
+                                        ctrl.end_analysis();
+                                    }
                                 } else {
-                                    // self.state == StepAiRegionState::PickAttackTarget
+                                    // self.state == StepAiRegionState::ManageAttack
+                                    self.result.ai_manage_attack = Some(dest);
                                     ctrl.end_analysis();
-                                    self.result.ai_region_pick_attack_target = Some(dest);
                                 }
                             }
                         }
@@ -2309,6 +2430,10 @@ impl<'a, 'acx, 'e, E: ExecutionState<'e>> analysis::Analyzer<'e> for
                                 if let Some(base) = index.if_mem8_offset(5) {
                                     self.region_operand = base;
                                     let binary = ctrl.binary();
+                                    if let Some(state9) = switch.branch(binary, ctx, 0x9) {
+                                        let exec_state = ctrl.exec_state().clone();
+                                        self.attacking_branch = Some((exec_state, state9));
+                                    }
                                     if let Some(state8) = switch.branch(binary, ctx, 0x8) {
                                         self.state = StepAiRegionState::UpdateStrength;
                                         ctrl.clear_unchecked_branches();
@@ -2318,7 +2443,9 @@ impl<'a, 'acx, 'e, E: ExecutionState<'e>> analysis::Analyzer<'e> for
                             }
                         }
                     }
-                } else if self.state != StepAiRegionState::FindSwitch {
+                } else if self.state != StepAiRegionState::FindSwitch &&
+                    self.state != StepAiRegionState::ManageAttack
+                {
                     let condition = ctrl.resolve(condition);
                     if self.state == StepAiRegionState::AbandonFlagCheck {
                         if let Some((val, eq)) = condition.if_and_mask_eq_neq(0x20) {
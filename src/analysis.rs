@@ -1,4 +1,5 @@
 use std::rc::Rc;
+use std::time::{Duration, Instant};
 
 use bumpalo::Bump;
 use byteorder::{ByteOrder, LittleEndian};
@@ -19,7 +20,7 @@ use crate::dialog;
 use crate::eud::{self, EudTable};
 use crate::file;
 use crate::firegraft::{self, RequirementTables};
-use crate::game::{self, Limits};
+use crate::game::{self, Limits, UnitLimits};
 use crate::game_init;
 use crate::iscript::{self, StepIscriptHook};
 use crate::images;
@@ -39,6 +40,7 @@ use crate::storm;
 use crate::switch::{CompleteSwitch};
 use crate::text;
 use crate::units;
+use crate::util::ExecStateExt;
 use crate::vtables::{self, Vtables};
 use crate::x86_64_globals;
 use crate::x86_64_unwind;
@@ -56,6 +58,27 @@ pub struct Patch<Va: VirtualAddress> {
     pub data: Vec<u8>,
 }
 
+impl<Va: VirtualAddress> Patch<Va> {
+    /// Writes the patch to `image`, a copy of the executable's memory (not file) layout
+    /// starting at `image_base`. E.g. a single section's data and its virtual address,
+    /// or the memory of the loaded executable.
+    ///
+    /// Returns false without modifying `image` if the patch doesn't fit in it.
+    pub fn apply_to_memory_image(&self, image: &mut [u8], image_base: Va) -> bool {
+        let start = match self.address.as_u64().checked_sub(image_base.as_u64()) {
+            Some(s) => s as usize,
+            None => return false,
+        };
+        match image.get_mut(start..).and_then(|x| x.get_mut(..self.data.len())) {
+            Some(out) => {
+                out.copy_from_slice(&self.data);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
 // Just since option spam for caches is a bit hard to keep track of
 struct Cached<T: Clone>(Option<T>);
 
@@ -68,6 +91,10 @@ impl<T: Clone> Cached<T> {
         self.0.clone()
     }
 
+    pub fn get(&self) -> Option<&T> {
+        self.0.as_ref()
+    }
+
     pub fn cache(&mut self, val: &T) {
         self.0 = Some(val.clone());
     }
@@ -157,6 +184,11 @@ macro_rules! results {
                 ];
                 ITEMS.iter().copied()
             }
+
+            /// Inverse of `name()`.
+            pub fn from_name(name: &str) -> Option<$name> {
+                $name::iter().find(|x| x.name() == name)
+            }
         }
 
         impl<'e, E: ExecutionState<'e>> Analysis<'e, E> {
@@ -189,16 +221,37 @@ results! {
         StepOrder => step_order,
         PrepareDrawImage => prepare_draw_image => cache_draw_game_layer,
         DrawImage => draw_image => cache_draw_game_layer,
+        // Reserves vertices for one textured quad in vertex_buffer and fills them
+        DrawQuad => draw_quad,
+        // this = renderer, a1 texture; Method of GL (or Prism) renderer
+        BindTexture => bind_texture,
         PlaySmk => play_smk,
+        // a1/this = current_smk_context; Stops playback and clears current_smk_context
+        StopSmk => stop_smk => cache_play_smk,
         AddOverlayIscript => add_overlay_iscript,
+        // a1 = unit, a2 = first image id; creates ensnare / plague / etc overlay sized for
+        // the unit.
+        AddStatusOverlay => add_status_overlay,
+        // Branch of step_iscript_switch for iscript opcode `useweapon`, not a function.
+        IscriptUseWeaponBranch => iscript_use_weapon_branch,
         RunDialog => run_dialog => cache_run_dialog,
         GluCmpgnEventHandler => glucmpgn_event_handler => cache_run_dialog,
+        // a1 = path to .bin / .ui file; returns the loaded dialog.
+        LoadDialogBin => load_dialog_bin => cache_run_dialog,
+        // a1 = dialog, a2 = control id; returns the child control with that id.
+        FindDialogControl => find_dialog_control,
         AiUpdateAttackTarget => ai_update_attack_target,
         IsOutsideGameScreen => is_outside_game_screen,
+        // Converts mouse event position to game coordinates, using screen_x/y and zoom.
+        ScreenToGameCoords => screen_to_game_coords => cache_coord_conversion,
+        // Roughly inverse of screen_to_game_coords; converts a game coordinate to
+        // position on screen.
+        GameToScreenCoords => game_to_screen_coords,
         ChooseSnp => choose_snp => cache_choose_snp,
         GetLocales => get_locales => cache_choose_snp,
         LoadImages => load_images,
         InitGameNetwork => init_game_network,
+        InitObserver => init_observer => cache_init_observer,
         SpawnDialog => spawn_dialog => cache_spawn_dialog,
         InitStatLb => init_statlb => cache_spawn_dialog,
         InitStatRes => init_statres => cache_init_statres,
@@ -209,13 +262,21 @@ results! {
         DrawGraphicLayers => draw_graphic_layers,
         AiAttackPrepare => ai_attack_prepare => cache_aiscript_switch,
         AiAttackClear => ai_attack_clear => cache_aiscript_switch,
+        // Adds an unit / building request to AI town; called by aiscript `build` opcode.
+        AiRequestUnit => ai_request_unit => cache_aiscript_switch,
         JoinGame => join_game,
         SnetInitializeProvider => snet_initialize_provider,
         CheckDatRequirements => check_dat_requirements,
         GiveAi => give_ai,
+        // Trigger action 0xf, a1 trigger action
+        TriggerRunAiScript => trigger_run_ai_script,
         PlaySound => play_sound,
+        // a1/this = sound channel, a2/a1 volume
+        SetSoundVolume => set_sound_volume => cache_sound_volume,
         AiPrepareMovingTo => ai_prepare_moving_to,
         StepReplayCommands => step_replay_commands,
+        // Writes replay to a file; used both for manually saved replays and the automatic
+        // replay save when game ends.
         SaveReplay => save_replay,
         AiTrainMilitary => ai_train_military,
         AiAddMilitaryToRegion => ai_add_military_to_region,
@@ -246,12 +307,18 @@ results! {
         DrawTooltipLayer => draw_tooltip_layer => cache_tooltip_related,
         SelectMapEntry => select_map_entry => cache_select_map_entry,
         CreateBullet => create_bullet => cache_bullet_creation,
+        // a1 x, a2 y; Returns 0 for low, 1 for middle, 2 for high ground.
+        GetTerrainHeight => get_terrain_height => cache_terrain_height,
+        // Called on bullet creation; compares get_terrain_height of target and attacker
+        // to decide if the bullet has the high ground miss chance.
+        ComputeMissChance => compute_miss_chance => cache_terrain_height,
         OrderInitArbiter => order_init_arbiter => cache_order_issuing,
         PrepareIssueOrder => prepare_issue_order => cache_order_issuing,
         DoNextQueuedOrder => do_next_queued_order => cache_order_issuing,
         ResetUiEventHandlers => reset_ui_event_handlers => cache_ui_event_handlers,
         ClampZoom => clamp_zoom,
         DrawMinimapUnits => draw_minimap_units,
+        SetReplayVision => set_replay_vision,
         InitNetPlayer => init_net_player => cache_net_players,
         ScMain => sc_main => cache_game_init,
         MainMenuEntryHook => mainmenu_entry_hook => cache_game_init,
@@ -271,8 +338,24 @@ results! {
         RunModernDialog => run_modern_dialog => cache_menu_screens,
         MenuSwishIn => menu_swish_in => cache_glucmpgn_events,
         MenuSwishOut => menu_swish_out => cache_glucmpgn_events,
-        AiSpellCast => ai_spell_cast,
+        AiSpellCast => ai_spell_cast => cache_order_guard,
+        // Moves a guard ai unit back to its home position; called by order 0xa0 (Guard).
+        AiReturnToGuard => ai_return_to_guard => cache_order_guard,
+        // this = unit; Spends energy and creates the spell for generic spell orders
+        CastSpell => cast_spell => cache_order_spell,
+        // a1 target; Creates one hallucination of target for the player of unit casting it
+        CreateHallucination => create_hallucination => cache_order_hallucination,
+        // Order handlers of burrowing (0x74) and unburrowing (0x76) orders, this = unit
+        BurrowUnit => burrow_unit => cache_burrow_orders,
+        UnburrowUnit => unburrow_unit => cache_burrow_orders,
+        // this = worker; Adds carried resources to player's minerals/gas at a resource depot
+        ReturnCargo => return_cargo => cache_order_return_cargo,
+        // this = building, a1 = addon
+        AttachAddon => attach_addon => cache_order_place_addon,
         GiveUnit => give_unit,
+        // a1 unit_id; returns units_dat_flags[unit_id] & 1.
+        // Most code reads the flag inline, so this may not be found in every build.
+        IsBuildingFn => is_building_fn,
         SetUnitPlayer => set_unit_player,
         RemoveFromSelections => remove_from_selections => cache_set_unit_player_fns,
         RemoveFromClientSelection => remove_from_client_selection => cache_set_unit_player_fns,
@@ -304,6 +387,10 @@ results! {
         GetImagesRel => get_images_rel => cache_image_loading,
         InitRealTimeLighting => init_real_time_lighting => cache_images_loaded,
         StepActiveUnitFrame => step_active_unit_frame => cache_step_objects,
+        // this = hatchery/lair/hive
+        SpawnLarva => spawn_larva,
+        // this = unit; decrements stim/ensnare/lockdown/irradiate/stasis/plague timers
+        StepUnitStatusEffects => step_unit_status_effects => cache_step_unit_status_effects,
         StepHiddenUnitFrame => step_hidden_unit_frame => cache_step_objects,
         StepBulletFrame => step_bullet_frame => cache_step_objects,
         StepBullets => step_bullets => cache_step_objects,
@@ -321,15 +408,31 @@ results! {
         RevealUnitArea => reveal_unit_area => cache_step_objects,
         UpdateUnitVisibility => update_unit_visibility => cache_step_objects,
         UpdateCloakState => update_cloak_state => cache_step_objects,
+        // this = unit; Drains energy from cloaked unit
+        CloakEnergyDrain => cloak_energy_drain,
         StepUnitMovement => step_unit_movement => cache_step_active_unit,
+        // this = unit; Turns unit.facing_direction towards its target direction
+        // by flingy turn speed.
+        UpdateUnitFacing => update_unit_facing,
         StepUnitTimers => step_unit_timers => cache_step_hidden_unit,
+        // this = unit; Regenerates energy of spellcasters
+        UnitStepEnergy => unit_step_energy => cache_unit_step_energy,
+        // this = unit; Progresses hitpoints and remaining build time of an incomplete unit
+        StepConstruction => step_construction => cache_step_construction,
         InitMapFromPath => init_map_from_path => cache_init_map,
         // Chk section handlers for non-SC:R maps. SC:R callback table is slightly different.
         MapInitChkCallbacks => map_init_chk_callbacks => cache_init_map,
+        // STR section callback of map_init_chk_callbacks
+        LoadMapStrings => load_map_strings => cache_load_map_strings,
+        // Called from THG2 section callback for sprite (doodad overlay) entries;
+        // a1 sprite_id, a2 x, a3 y, a4 player
+        PlaceMapSprite => place_map_sprite,
         StepNetwork => step_network => cache_game_loop,
         // a1 zero?, a2 player_count, a3 void **out_player_turns, a4 u32 *out_player_turns_size,
         // a4 u32 *out_net_player_flags
         ReceiveStormTurns => receive_storm_turns => cache_step_network,
+        // a1 player; called for players whose net_player_flags say they have dropped.
+        HandlePlayerDrop => handle_player_drop => cache_player_drop,
         AiStepRegion => ai_step_region => cache_ai_step_frame,
         AiSpendMoney => ai_spend_money => cache_ai_step_frame,
         AiTargetExpansion => ai_target_expansion => cache_ai_step_frame,
@@ -344,6 +447,8 @@ results! {
         DrawGameLayer => draw_game_layer,
         RenderScreen => render_screen => cache_game_loop,
         LoadPcx => load_pcx => cache_game_loop,
+        // a1 surface, a2 path; loads pcx directly to surface pixels with load_pcx.
+        BlitPcxToSurface => blit_pcx_to_surface => cache_blit_pcx_to_surface,
         SetMusic => set_music => cache_game_loop,
         StepIscript => step_iscript,
         StepIscriptSwitch => step_iscript_switch => cache_step_iscript,
@@ -353,6 +458,8 @@ results! {
         StepAiScripts => step_ai_scripts => cache_ai_step_frame,
         StepGameLoop => step_game_loop => cache_game_loop,
         StepGameLogic => step_game_logic => cache_game_loop,
+        // Calculates checksum of current game state and stores it to sync_data.
+        ComputeSyncChecksum => compute_sync_checksum,
         ProcessEvents => process_events => cache_game_loop,
         StepBnetController => step_bnet_controller => cache_process_events,
         CreateGameMultiplayer => create_game_multiplayer => cache_select_map_entry_children,
@@ -363,6 +470,8 @@ results! {
         GetMouseY => get_mouse_y => cache_mouse_xy,
         AddPylonAura => add_pylon_aura => cache_pylon_aura,
         SinglePlayerMapEnd => single_player_map_end => cache_sp_map_end,
+        // Called from step_game_logic; ends the game once local player has won or lost.
+        CheckGameOver => check_game_over,
         SetScmainState => set_scmain_state => cache_sp_map_end_analysis,
         UnlockMission => unlock_mission => cache_sp_map_end_analysis,
         CreateFowSprite => create_fow_sprite => cache_update_unit_visibility,
@@ -373,6 +482,8 @@ results! {
         LoadReplayScenarioChk => load_replay_scenario_chk => cache_init_map_from_path,
         SfileCloseArchive => sfile_close_archive => cache_init_map_from_path,
         OpenMapMpq => open_map_mpq => cache_init_map_from_path,
+        // SFileOpenArchive; a1 path, a2 priority, a3 flags, a4 HANDLE *out
+        MountArchive => mount_archive,
         // arg 1 void *mpq_handle, arg 2 char *filename, arg 3 u8 *out_ptr arg 4 u32 *out_size,
         // arg 5 extra_out_size (0), arg 6 storm flags (0), arg 7 unk opt ptr? (0); stdcall
         ReadWholeMpqFile => read_whole_mpq_file => cache_init_map_from_path,
@@ -388,6 +499,13 @@ results! {
         GameScreenRClick => game_screen_rclick => cache_game_screen_rclick,
         UiDefaultKeyDownHandler => ui_default_key_down_handler => cache_ui_event_handlers,
         UiDefaultKeyUpHandler => ui_default_key_up_handler => cache_ui_event_handlers,
+        // Pauses if not paused, unpauses otherwise
+        TogglePause => toggle_pause,
+        // Hotkey action which cycles the screen through local player's town halls
+        // (resource depots).
+        CenterOnTownhall => center_on_townhall,
+        OpenGameMenu => open_game_menu => cache_game_menu_funcs,
+        CloseGameMenu => close_game_menu => cache_game_menu_funcs,
         UiDefaultLeftDownHandler => ui_default_left_down_handler => cache_ui_event_handlers,
         UiDefaultRightDownHandler => ui_default_right_down_handler => cache_ui_event_handlers,
         UiDefaultMiddleDownHandler => ui_default_middle_down_handler => cache_ui_event_handlers,
@@ -396,6 +514,13 @@ results! {
         UiDefaultCharHandler => ui_default_char_handler => cache_ui_event_handlers,
         UiDefaultScrollHandler => ui_default_scroll_handler => cache_ui_event_handlers,
         StartTargeting => start_targeting => cache_start_targeting,
+        // Calls button condition functions of current button set to refresh
+        // enabled / disabled state of command buttons.
+        UpdateButtonStates => update_button_states,
+        // Receives characters typed to the chat box, appending them to chat_input_buffer.
+        ChatInputHandler => chat_input_handler => cache_chat_input,
+        // Draw func of command button controls; draws cmdbtns/cmdicons frames.
+        DrawCommandButton => draw_command_button,
         FindUnitForClick => find_unit_for_click => cache_targeting_lclick,
         FindFowSpriteForClick => find_fow_sprite_for_click => cache_targeting_lclick,
         HandleTargetedClick => handle_targeted_click => cache_targeting_lclick,
@@ -421,10 +546,16 @@ results! {
         AddAiToTrainedUnit => add_ai_to_trained_unit => cache_order_train,
         CancelQueuedUnit => cancel_queued_unit => cache_order_train,
         RefreshUi => refresh_ui => cache_order_train,
+        // this = unit, a1 = ignore_blindness; units_dat_sight_range with sight upgrades
+        // applied. Used for both vision and detection.
         GetSightRange => get_sight_range => cache_order_matrix,
         GetTargetAcquisitionRange => get_target_acquisition_range => cache_order_player_guard,
+        // Called by guard orders of idle units to scan units in target acquisition range
+        // for something to attack. This is the auto-acquisition scan.
         PickAutoTarget => pick_auto_target => cache_order_player_guard,
         AttackUnit => attack_unit => cache_order_player_guard,
+        // this = unit, a1 = weapon_id; returns weapons_dat_max_range[weapon_id] with
+        // upgrade / bunker range bonuses applied. That is, the effective weapon range.
         GetAttackRange => get_attack_range => cache_order_arbiter_cloak,
         FindUnitBordersRect => find_unit_borders_rect => cache_order_arbiter_cloak,
         PickRandomTarget => pick_random_target => cache_order_tower,
@@ -449,8 +580,15 @@ results! {
         LookupSoundId => lookup_sound_id => cache_do_missile_damage,
         PlaySoundAtUnit => play_sound_at_unit => cache_do_missile_damage,
         KillUnit => kill_unit => cache_do_missile_damage,
+        // Address of the call in order 0x0 (Die) which starts unit's death animation.
+        // The unit is deleted later, once the animation's iscript has ended.
+        IscriptDeathCall => iscript_death_call,
+        // Not in vanilla; only found if a mod has added unit ranks
+        UpdateUnitRank => update_unit_rank => cache_unit_rank,
         UnitMaxEnergy => unit_max_energy => cache_do_missile_damage,
         SplashLurker => splash_lurker => cache_do_missile_damage,
+        // Applies splash damage to units in the weapon's area, found with
+        // `for_each_unit_in_area`. (Lurker splash is SplashLurker)
         SplashFull => splash_full => cache_do_missile_damage,
         ForEachUnitInArea => for_each_unit_in_area => cache_do_missile_damage,
         HallucinationHit => hallucination_hit => cache_hit_unit,
@@ -480,6 +618,11 @@ results! {
         SetCurrentCursorType => set_current_cursor_type => cache_select_mouse_up,
         // select_units(amount, ptr_arr, bool, bool)
         SelectUnits => select_units => cache_select_mouse_up,
+        // Called from select_mouse_up when mouse was dragged; selects units in the box
+        // spanning from select_start_x/y to mouse up position.
+        FinalizeDragSelect => finalize_drag_select,
+        // this = unit; Plays unit's "what" response when it gets selected
+        PlayUnitSound => play_unit_sound,
         UnitCanBeInfested => unit_can_be_infested => cache_order_infest,
         UnitDetachAddon => unit_detach_addon => cache_order_infest,
         UnitCanRally => unit_can_rally => cache_order_infest,
@@ -526,6 +669,8 @@ results! {
         AiRegionAbandonIfOverwhelmed => ai_region_abandon_if_overwhelmed => cache_ai_step_region,
         // a1 region; Pick target for attack force; can be far away
         AiRegionPickAttackTarget => ai_region_pick_attack_target => cache_ai_step_region,
+        // a1 region; Steps region that is attacking
+        AiManageAttack => ai_manage_attack => cache_ai_step_region,
         // a1 ctrl, a2 id, a3 time, a4 func * (64bit) / a4_8 func_by_value (32bit)
         CtrlSetTimer => ctrl_set_timer => cache_run_dialog_children,
         // a1 unit_id, a2 -1?, a3 -1?, a4 time
@@ -547,6 +692,17 @@ results! {
         SimpleFileMatchCallback => simple_file_match_callback => cache_find_file_with_crc,
         StartCloaking => start_cloaking => cache_cloak_command,
         PrepareBuildUnit => prepare_build_unit => cache_morph_command,
+        SetRallyPoint => set_rally_point => cache_rally_command,
+        // this = unit, a1 unit_id
+        EnqueueBuild => enqueue_build,
+        // can_afford(player, unit_id); checks minerals/gas/supply and shows error if not
+        CanAfford => can_afford,
+        // this = unit, a1 = tech id
+        QueueTech => queue_tech,
+        // this = unit, a1 = upgrade id
+        QueueUpgrade => queue_upgrade,
+        // this = unit; sets stim timer and updates speed
+        ApplyStim => apply_stim,
         UnitAiWorker => unit_ai_worker => cache_ai_order,
         UnitAiMilitary => unit_ai_military => cache_ai_order,
         AiTryProgressSpendingRequest => ai_try_progress_spending_request => cache_ai_order,
@@ -555,6 +711,8 @@ results! {
         // this unit, a1 radius, a2 filter_func, a3 filter_param
         FindNearestUnitAroundUnit => find_nearest_unit_around_unit => cache_ai_order,
         // this unit, a1 target, a2 check_detection
+        // Returns nonzero if unit can attack target; the general target validity check
+        // that is also used outside AI code.
         CanAttackUnit => can_attack_unit => cache_ai_order,
         // this unit, a1 target
         IsOutsideAttackRange => is_outside_attack_range => cache_ai_order,
@@ -633,11 +791,22 @@ results! {
         TeamGameTeams => team_game_teams => cache_unit_strength_etc,
         WireframDdsgrp => wirefram_ddsgrp,
         ChkInitPlayers => chk_init_players,
+        MapStringTable => map_string_table => cache_load_map_strings,
+        // game + offset; Indexed by 1-based location id - 1
+        LocationArray => location_array => cache_trigger_locations,
+        // game + offset; u32 deaths[unit_id][player]
+        DeathCounters => death_counters => cache_trigger_death_counters,
+        CurrentSmkContext => current_smk_context => cache_play_smk,
+        SoundBankHandle => sound_bank_handle => cache_sound_volume,
         OriginalChkPlayerTypes => original_chk_player_types,
         AiTransportReachabilityCachedRegion => ai_transport_reachability_cached_region,
         // u16 array [0xe4 * 0xc]
         PlayerUnitSkins => player_unit_skins => cache_draw_image,
         ReplayData => replay_data,
+        // Nonzero when commands are being recorded to replay_data.
+        IsRecordingReplay => is_recording_replay,
+        // u32[12]; incremented for every command a player sends. Not in older versions.
+        PlayerActionCounts => player_action_counts,
         VertexBuffer => vertex_buffer,
         RngSeed => rng_seed => cache_rng,
         RngEnable => rng_enable => cache_rng,
@@ -661,7 +830,12 @@ results! {
         Sprites => sprites => cache_init_sprites,
         FirstActiveUnit => first_active_unit => cache_active_hidden_units,
         FirstHiddenUnit => first_hidden_unit => cache_active_hidden_units,
+        // u32 per tile. Bits 0..8 are set for players that don't currently see the tile,
+        // bits 8..16 for players that haven't explored it; there is no separate
+        // explored tiles array.
         MapTileFlags => map_tile_flags => cache_map_tile_flags,
+        // Array which get_terrain_height reads elevation flags from
+        ElevationData => elevation_data => cache_terrain_height,
         TooltipDrawFunc => tooltip_draw_func => cache_tooltip_related,
         CurrentTooltipCtrl => current_tooltip_ctrl => cache_tooltip_related,
         GraphicLayers => graphic_layers => cache_tooltip_related,
@@ -670,6 +844,8 @@ results! {
         LastActiveBullet => last_active_bullet => cache_bullet_creation,
         FirstFreeBullet => first_free_bullet => cache_bullet_creation,
         LastFreeBullet => last_free_bullet => cache_bullet_creation,
+        // Fixed-size array that bullets are allocated from, see `Analysis::bullet_array_layout`
+        BulletArray => bullet_array,
         ActiveIscriptUnit => active_iscript_unit => cache_bullet_creation,
         UniqueCommandUser => unique_command_user => cache_selections,
         Selections => selections => cache_selections,
@@ -682,10 +858,26 @@ results! {
         NgdpEnabled => ngdp_enabled => cache_game_init,
         NgdpInstance => ngdp_instance => cache_game_init,
         LocalStormPlayerId => local_storm_player_id => cache_single_player_start,
+        // u8, nonzero if local player is observing
+        IsObserver => is_observer => cache_init_observer,
         LocalUniquePlayerId => local_unique_player_id => cache_single_player_start,
         NetPlayerToGame => net_player_to_game => cache_single_player_start,
         NetPlayerToUnique => net_player_to_unique => cache_single_player_start,
         GameData => game_data => cache_single_player_start,
+        // Mem16[game_data + 0x6d]; game type (2 = melee, 3 = ffa, 0xa = ums, etc.)
+        // from GameData's game template. Confirmed by init_game reading it.
+        GameMode => game_mode,
+        // Mem8[game_data + 0x75]; victory condition of the game template
+        // (0 = map default, 1 = melee, 2 = high score, 3 = resources, 4 = capture the flag,
+        // 5 = sudden death, 6 = slaughter, 7 = one on one).
+        // There is no table of condition functions, they are all checked in check_game_over.
+        // Confirmed by check_game_over reading it.
+        VictoryConditions => victory_conditions,
+        // Mem32[game_data + 0x84]; starting resource amount of the game template,
+        // used when game template's resource type (Mem8[game_data + 0x76]) is 1 (fixed value).
+        // Same for all players, there's no per-player handicap table.
+        // Confirmed by init_game reading it.
+        StartingResources => starting_resources,
         // Local skins? Has some linked list etc and then u16 array of 0xab first units
         Skins => skins => cache_single_player_start,
         // u16 array [0xab * 0x10] Only first 0xab units are included, so player_unit_skins
@@ -730,8 +922,15 @@ results! {
         UnitShouldRevealArea => unit_should_reveal_area => cache_step_active_unit,
         MenuScreenId => menu_screen_id => cache_game_loop,
         NetPlayerFlags => net_player_flags => cache_step_network,
+        // Bitmask, (1 << player) is set once the player has left / dropped.
+        PlayerLeftFlags => player_left_flags => cache_player_drop,
+        // `void *[storm_turn_count]`; receive_storm_turns writes each player's received
+        // turn buffer there.
         PlayerTurns => player_turns => cache_step_network,
         PlayerTurnsSize => player_turns_size => cache_step_network,
+        // Player count passed to receive_storm_turns, length of player_turns and
+        // player_turns_size arrays.
+        StormTurnCount => storm_turn_count => cache_step_network,
         NetworkReady => network_ready => cache_step_network,
         NetUserLatency => net_user_latency,
         LastBulletSpawner => last_bullet_spawner => cache_do_attack,
@@ -739,6 +938,8 @@ results! {
         CmdBtnsDdsGrp => cmdbtns_ddsgrp => cache_cmdicons,
         DatRequirementError => dat_requirement_error => cache_unit_requirements,
         CursorMarker => cursor_marker => cache_draw_game_layer,
+        // Unit pointer, set by game screen mouse move to `find_unit_for_click(mouse_xy)`
+        UnitUnderCursor => unit_under_cursor,
         MainPalette => main_palette => cache_game_loop,
         PaletteSet => palette_set => cache_game_loop,
         TfontGam => tfontgam => cache_game_loop,
@@ -780,6 +981,11 @@ results! {
         ReplayScenarioChk => replay_scenario_chk => cache_init_map_from_path,
         ReplayScenarioChkSize => replay_scenario_chk_size => cache_init_map_from_path,
         MapMpq => map_mpq => cache_init_map_from_path,
+        // Pointer to first archive in the list of mounted archives that are
+        // searched when opening files.
+        ArchiveChainHead => archive_chain_head,
+        // Global surface passed to blit_pcx_to_surface
+        PcxTargetSurface => pcx_target_surface => cache_blit_pcx_to_surface,
         MapHistory => map_history => cache_init_map_from_path,
         GameScreenLClickCallback => game_screen_lclick_callback => cache_ui_event_handlers,
         GameScreenRClickCallback => game_screen_rclick_callback => cache_ui_event_handlers,
@@ -818,7 +1024,7 @@ results! {
         MapWidthPixels => map_width_pixels => cache_step_moving_bullet_frame,
         MapHeightPixels => map_height_pixels => cache_step_moving_bullet_frame,
         ReplayHeader => replay_header => cache_step_replay_commands,
-        // What locale is used when reading from mpq files
+        // What locale is used when reading from mpq files; the active locale id (LCID).
         MpqLocale => mpq_locale => cache_read_mpq_file,
         // HashTable u32 -> Console *
         UiConsoles => ui_consoles => cache_init_ingame_ui,
@@ -880,7 +1086,9 @@ results! {
         // (Not sure if more than one is still loaded at once) in one point.
         TilesetData => tileset_data => cache_init_terrain,
         TileDefaultFlags => tile_default_flags => cache_init_terrain,
+        // tileset.cv5
         TilesetCv5 => tileset_cv5 => cache_init_terrain,
+        // tileset.vx4ex; SC:R's vx4 with 32-bit minitile references.
         TilesetVx4Ex => tileset_vx4ex => cache_init_terrain,
         // tileset.vr4
         MinitileGraphics => minitile_graphics => cache_init_terrain,
@@ -899,9 +1107,51 @@ results! {
         SnetLocalPlayerList => snet_local_player_list => cache_snet_recv_packets,
         SnetPlayerList => snet_player_list => cache_snet_recv_packets,
         CursorScaleFactor => cursor_scale_factor,
+        // Text currently typed in the chat box; u8 or u16 array depending on version.
+        ChatInputBuffer => chat_input_buffer => cache_chat_input,
     }
 }
 
+/// Switches that `Analysis::switch_cases` can list cases of.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum SwitchKind {
+    /// Game command id switch in `process_commands`.
+    ProcessCommands,
+    /// Lobby command id switch in `process_lobby_commands`.
+    ProcessLobbyCommands,
+    /// Iscript opcode switch in `step_iscript`.
+    StepIscript,
+    /// Message type switch in bnet controller's message handler.
+    BnetMessage,
+    /// Aiscript opcode switch.
+    AiScript,
+}
+
+/// Result of `Analysis::explain` / `Analysis::explain_operand`.
+#[derive(Copy, Clone, Debug)]
+pub enum FailureReason {
+    /// The analysis has a result.
+    Found,
+    /// A result that the analysis depends on was not found.
+    /// Explaining that analysis may give more details.
+    MissingAddress(AddressAnalysis),
+    MissingOperand(OperandAnalysis),
+    /// Dependencies were found, but the analysis itself didn't find anything.
+    ///
+    /// Dependencies that aren't `AddressAnalysis` / `OperandAnalysis` results,
+    /// such as dat tables, strings, or switch tables, are not tracked and
+    /// their failures also end up here.
+    NoMatch,
+}
+
+#[derive(Default)]
+struct AnalysisTimings {
+    results: Vec<(&'static str, Duration)>,
+    /// Time spent in nested analyses of the analysis that is currently running,
+    /// subtracted from its own time.
+    child_time: Duration,
+}
+
 pub struct AnalysisCache<'e, E: ExecutionState<'e>> {
     binary: &'e BinaryFile<E::VirtualAddress>,
     text: &'e BinarySection<E::VirtualAddress>,
@@ -912,6 +1162,11 @@ pub struct AnalysisCache<'e, E: ExecutionState<'e>> {
     vtables: Cached<Rc<Vtables<'e, E::VirtualAddress>>>,
     firegraft_addresses: Cached<Rc<FiregraftAddresses<E::VirtualAddress>>>,
     aiscript_hook: Option<AiScriptHook<'e, E::VirtualAddress>>,
+    /// Some when `Analysis::explain` is running; results that were requested
+    /// and not found, in order.
+    missing_trace: Option<Vec<FailureReason>>,
+    /// Some after `Analysis::enable_timing`.
+    timings: Option<AnalysisTimings>,
     // 0 = Not calculated, 1 = Not found
     address_results: [E::VirtualAddress; AddressAnalysis::COUNT],
     // None = Not calculated, Custom(1234578) = Not found
@@ -933,12 +1188,28 @@ pub struct AnalysisCache<'e, E: ExecutionState<'e>> {
     renderer_vtables: Cached<Rc<Vec<E::VirtualAddress>>>,
     snp_definitions: Cached<Option<SnpDefinitions<'e>>>,
     sprite_struct_size: u16,
+    bullet_struct_size: u16,
     net_player_size: u16,
     skins_size: u16,
     anim_struct_size: u16,
     bnet_message_vtable_type: u16,
     create_game_dialog_vtbl_on_multiplayer_create: u16,
     join_param_variant_type_offset: u16,
+    unit_rally_offset: u16,
+    unit_build_queue_offset: u16,
+    ai_region_size: u16,
+    ai_region_state_offset: u16,
+    unit_energy_offset: u16,
+    unit_remaining_build_time_offset: u16,
+    chk_section_handler_count: u16,
+    location_struct_size: u16,
+    death_counters_row_size: u16,
+    max_selection_count: u16,
+    selection_array_stride: u16,
+    unit_rank_offset: u16,
+    unit_addon_offset: u16,
+    unit_status_timers: units::UnitStatusTimers,
+    flingy_speed_offsets: Cached<Option<units::FlingySpeedOffsets>>,
     limits: Cached<Rc<Limits<'e, E::VirtualAddress>>>,
     prism_shaders: Cached<PrismShaders<E::VirtualAddress>>,
     dat_patches: Cached<Option<Rc<DatPatches<'e, E::VirtualAddress>>>>,
@@ -1140,6 +1411,8 @@ impl<'e, E: ExecutionState<'e>> Analysis<'e, E> {
                 vtables: Default::default(),
                 firegraft_addresses: Default::default(),
                 aiscript_hook: Default::default(),
+                missing_trace: None,
+                timings: None,
                 address_results:
                     [E::VirtualAddress::from_u64(0); AddressAnalysis::COUNT],
                 operand_results: [None; OperandAnalysis::COUNT],
@@ -1158,12 +1431,28 @@ impl<'e, E: ExecutionState<'e>> Analysis<'e, E> {
                 renderer_vtables: Default::default(),
                 snp_definitions: Default::default(),
                 sprite_struct_size: 0,
+                bullet_struct_size: 0,
                 net_player_size: 0,
                 skins_size: 0,
                 anim_struct_size: 0,
                 bnet_message_vtable_type: 0,
                 create_game_dialog_vtbl_on_multiplayer_create: 0,
                 join_param_variant_type_offset: u16::MAX,
+                unit_rally_offset: 0,
+                unit_build_queue_offset: u16::MAX,
+                ai_region_size: 0,
+                ai_region_state_offset: u16::MAX,
+                unit_energy_offset: 0,
+                unit_remaining_build_time_offset: 0,
+                chk_section_handler_count: u16::MAX,
+                location_struct_size: 0,
+                death_counters_row_size: 0,
+                max_selection_count: 0,
+                selection_array_stride: 0,
+                unit_rank_offset: 0,
+                unit_addon_offset: 0,
+                unit_status_timers: Default::default(),
+                flingy_speed_offsets: Default::default(),
                 limits: Default::default(),
                 prism_shaders: Default::default(),
                 dat_patches: Default::default(),
@@ -1303,6 +1592,15 @@ impl<'e, E: ExecutionState<'e>> Analysis<'e, E> {
         self.enter(AnalysisCache::renderer_vtables)
     }
 
+    /// Returns functions of a vtable (Intended for ones returned by `renderer_vtables`),
+    /// indexed by method index. Stops at the first pointer outside code section.
+    pub fn renderer_vtable_methods(
+        &mut self,
+        vtable: E::VirtualAddress,
+    ) -> Vec<E::VirtualAddress> {
+        renderer::vtable_methods(self.shareable.binary, vtable)
+    }
+
     pub fn vtables(&mut self) -> Vec<E::VirtualAddress> {
         self.enter(AnalysisCache::all_vtables)
     }
@@ -1311,6 +1609,95 @@ impl<'e, E: ExecutionState<'e>> Analysis<'e, E> {
         self.enter(|x, s| x.vtables_for_class(name, s))
     }
 
+    /// Same as `address_analysis(AddressAnalysis::from_name(name)?)`.
+    pub fn address_by_name(&mut self, name: &str) -> Option<E::VirtualAddress> {
+        self.address_analysis(AddressAnalysis::from_name(name)?)
+    }
+
+    /// Same as `operand_analysis(OperandAnalysis::from_name(name)?)`.
+    pub fn operand_by_name(&mut self, name: &str) -> Option<Operand<'e>> {
+        self.operand_analysis(OperandAnalysis::from_name(name)?)
+    }
+
+    /// Runs only the given analyses (and whatever they depend on), returning results
+    /// in same order as the inputs.
+    ///
+    /// Equivalent to calling `address_analysis` / `operand_analysis` for each input;
+    /// as results are cached, shared dependencies are only analyzed once.
+    pub fn resolve_subset(
+        &mut self,
+        addrs: &[AddressAnalysis],
+        ops: &[OperandAnalysis],
+    ) -> (Vec<Option<E::VirtualAddress>>, Vec<Option<Operand<'e>>>) {
+        let addrs = addrs.iter().map(|&x| self.address_analysis(x)).collect();
+        let ops = ops.iter().map(|&x| self.operand_analysis(x)).collect();
+        (addrs, ops)
+    }
+
+    /// Starts recording time spent in each analysis for `timings()`.
+    pub fn enable_timing(&mut self) {
+        if self.cache.timings.is_none() {
+            self.cache.timings = Some(AnalysisTimings::default());
+        }
+    }
+
+    /// Time spent in each analysis that was run after `enable_timing`, in order they
+    /// finished.
+    ///
+    /// Analyses that find multiple results at once are named by their first result.
+    /// Time spent in dependencies is not included in the dependent analysis' time;
+    /// a dependency that was not cached yet gets its own entry.
+    pub fn timings(&self) -> Vec<(&'static str, Duration)> {
+        self.cache.timings.as_ref().map(|x| x.results.clone()).unwrap_or_default()
+    }
+
+    /// Explains why `addr` was not found.
+    ///
+    /// Reruns the analysis while keeping track of the results it requests, and returns the
+    /// first one that was not found. Dependencies aren't rerun, so their cached results
+    /// are used.
+    pub fn explain(&mut self, addr: AddressAnalysis) -> FailureReason {
+        if self.address_analysis(addr).is_some() {
+            return FailureReason::Found;
+        }
+        self.cache.address_results[addr as usize] = E::VirtualAddress::from_u64(0);
+        self.cache.missing_trace = Some(Vec::new());
+        let result = self.address_analysis(addr);
+        let trace = self.cache.missing_trace.take().unwrap_or_default();
+        if result.is_some() {
+            return FailureReason::Found;
+        }
+        let is_self = |x: &FailureReason| match *x {
+            FailureReason::MissingAddress(a) => a as usize == addr as usize,
+            _ => false,
+        };
+        trace.into_iter().find(|x| !is_self(x)).unwrap_or(FailureReason::NoMatch)
+    }
+
+    /// Same as `explain`, but for `OperandAnalysis`.
+    pub fn explain_operand(&mut self, op: OperandAnalysis) -> FailureReason {
+        if self.operand_analysis(op).is_some() {
+            return FailureReason::Found;
+        }
+        self.cache.operand_results[op as usize] = None;
+        self.cache.missing_trace = Some(Vec::new());
+        let result = self.operand_analysis(op);
+        let trace = self.cache.missing_trace.take().unwrap_or_default();
+        if result.is_some() {
+            return FailureReason::Found;
+        }
+        let is_self = |x: &FailureReason| match *x {
+            FailureReason::MissingOperand(o) => o as usize == op as usize,
+            _ => false,
+        };
+        trace.into_iter().find(|x| !is_self(x)).unwrap_or(FailureReason::NoMatch)
+    }
+
+    /// Returns addresses of instructions that reference `global`, sorted by address.
+    pub fn global_references(&mut self, global: E::VirtualAddress) -> Vec<E::VirtualAddress> {
+        self.enter(|x, s| x.global_references(global, s))
+    }
+
     pub fn skins_size(&mut self) -> Option<u32> {
         self.player_skins()
             .map(|_| self.cache.skins_size as u32)
@@ -1381,6 +1768,27 @@ impl<'e, E: ExecutionState<'e>> Analysis<'e, E> {
         self.enter(AnalysisCache::add_overlay_iscript)
     }
 
+    /// Address of the `useweapon` opcode case inside step_iscript. Not a function entry.
+    pub fn iscript_use_weapon_branch(&mut self) -> Option<E::VirtualAddress> {
+        self.enter(AnalysisCache::iscript_use_weapon_branch)
+    }
+
+    /// Address of the call instruction in order 0x0 (Die) that starts the death animation.
+    ///
+    /// This is the point where the unit is still fully valid; `delete_unit` is only called
+    /// on a later frame, after the death animation's iscript has ended.
+    pub fn iscript_death_call(&mut self) -> Option<E::VirtualAddress> {
+        self.enter(AnalysisCache::iscript_death_call)
+    }
+
+    pub fn update_unit_facing(&mut self) -> Option<E::VirtualAddress> {
+        self.enter(AnalysisCache::update_unit_facing)
+    }
+
+    pub fn add_status_overlay(&mut self) -> Option<E::VirtualAddress> {
+        self.enter(AnalysisCache::add_status_overlay)
+    }
+
     pub fn draw_cursor_marker(&mut self) -> Option<Operand<'e>> {
         self.enter(AnalysisCache::draw_cursor_marker)
     }
@@ -1446,6 +1854,17 @@ impl<'e, E: ExecutionState<'e>> Analysis<'e, E> {
         self.enter(AnalysisCache::fonts)
     }
 
+    /// Returns `fonts` and amount of font sizes in it.
+    pub fn font_array_layout(&mut self) -> Option<(Operand<'e>, u32)> {
+        self.fonts().map(|fonts| (fonts, text::FONT_NAMES.len() as u32))
+    }
+
+    /// Bullet array and size of a single bullet.
+    pub fn bullet_array_layout(&mut self) -> Option<(Operand<'e>, u32)> {
+        self.enter(AnalysisCache::bullet_array)
+            .map(|x| (x, self.cache.bullet_struct_size.into()))
+    }
+
     pub fn sprite_array(&mut self) -> Option<(Operand<'e>, u32)> {
         self.sprites()
             .map(|x| (x, self.cache.sprite_struct_size.into()))
@@ -1455,6 +1874,14 @@ impl<'e, E: ExecutionState<'e>> Analysis<'e, E> {
         self.enter(AnalysisCache::limits)
     }
 
+    /// Limit values that the game passes to `set_limits`.
+    /// Patch sites for the limits are in `limits().arrays`.
+    pub fn unit_limits(&mut self) -> Option<UnitLimits> {
+        let limits = self.limits();
+        limits.set_limits?;
+        Some(UnitLimits::from_values(&limits.values))
+    }
+
     /// Memory allocation function that at least TTF code uses.
     ///
     /// (Should be Win32 HeapAlloc with a specific heap)
@@ -1517,6 +1944,15 @@ impl<'e, E: ExecutionState<'e>> Analysis<'e, E> {
         self.enter(AnalysisCache::allocator)
     }
 
+    /// Allocator object that `smem_alloc` and `smem_free` forward to.
+    ///
+    /// Memory allocated with `allocator.vtable.alloc(size, align)` can be freed by the game
+    /// with `smem_free`. None if `smem_alloc` wasn't found as a separate function, in which
+    /// case `allocator` is still the one used by the limit arrays.
+    pub fn smem_alloc_heap(&mut self) -> Option<Operand<'e>> {
+        self.enter(AnalysisCache::smem_alloc_heap)
+    }
+
     pub fn status_screen_mode(&mut self) -> Option<Operand<'e>> {
         self.enter(AnalysisCache::status_screen_mode)
     }
@@ -1558,6 +1994,32 @@ impl<'e, E: ExecutionState<'e>> Analysis<'e, E> {
         self.enter(AnalysisCache::chk_init_players)
     }
 
+    pub fn location_struct_size(&mut self) -> Option<u32> {
+        self.location_array().map(|_| self.cache.location_struct_size as u32)
+    }
+
+    /// Size of one unit id's row in `death_counters`.
+    pub fn death_counters_row_size(&mut self) -> Option<u32> {
+        self.death_counters().map(|_| self.cache.death_counters_row_size as u32)
+    }
+
+    /// Amount of units a single player can have selected at once.
+    pub fn max_selection_count(&mut self) -> Option<u32> {
+        self.selections().map(|_| self.cache.max_selection_count as u32)
+    }
+
+    /// Size of one player's row in `selections`.
+    pub fn selection_array_stride(&mut self) -> Option<u32> {
+        self.selections().map(|_| self.cache.selection_array_stride as u32)
+    }
+
+    /// Amount of entries in `map_init_chk_callbacks`, not including the terminating entry.
+    ///
+    /// The entries are `u32 section_id, fnptr, u32 flags`, terminated by section_id 0.
+    pub fn chk_section_handler_count(&mut self) -> Option<u16> {
+        self.enter(AnalysisCache::chk_section_handler_count)
+    }
+
     pub fn original_chk_player_types(&mut self) -> Option<Operand<'e>> {
         self.enter(AnalysisCache::original_chk_player_types)
     }
@@ -1566,10 +2028,26 @@ impl<'e, E: ExecutionState<'e>> Analysis<'e, E> {
         self.enter(AnalysisCache::give_ai)
     }
 
+    pub fn trigger_run_ai_script(&mut self) -> Option<E::VirtualAddress> {
+        self.enter(AnalysisCache::trigger_run_ai_script)
+    }
+
     pub fn play_sound(&mut self) -> Option<E::VirtualAddress> {
         self.enter(AnalysisCache::play_sound)
     }
 
+    pub fn play_unit_sound(&mut self) -> Option<E::VirtualAddress> {
+        self.enter(AnalysisCache::play_unit_sound)
+    }
+
+    pub fn toggle_pause(&mut self) -> Option<E::VirtualAddress> {
+        self.enter(AnalysisCache::toggle_pause)
+    }
+
+    pub fn center_on_townhall(&mut self) -> Option<E::VirtualAddress> {
+        self.enter(AnalysisCache::center_on_townhall)
+    }
+
     pub fn do_missile_damage(&mut self) -> Option<E::VirtualAddress> {
         self.enter(AnalysisCache::do_missile_damage)
     }
@@ -1599,6 +2077,10 @@ impl<'e, E: ExecutionState<'e>> Analysis<'e, E> {
         self.enter(AnalysisCache::draw_minimap_units)
     }
 
+    pub fn set_replay_vision(&mut self) -> Option<E::VirtualAddress> {
+        self.enter(AnalysisCache::set_replay_vision)
+    }
+
     pub fn step_replay_commands(&mut self) -> Option<E::VirtualAddress> {
         self.enter(AnalysisCache::step_replay_commands)
     }
@@ -1607,6 +2089,17 @@ impl<'e, E: ExecutionState<'e>> Analysis<'e, E> {
         self.enter(AnalysisCache::replay_data)
     }
 
+    pub fn is_recording_replay(&mut self) -> Option<Operand<'e>> {
+        self.enter(AnalysisCache::is_recording_replay)
+    }
+
+    /// `u32[12]` array counting commands each player has sent, used for APM.
+    ///
+    /// Returns `None` on versions that don't track this.
+    pub fn player_action_counts(&mut self) -> Option<Operand<'e>> {
+        self.enter(AnalysisCache::player_action_counts)
+    }
+
     pub fn ai_train_military(&mut self) -> Option<E::VirtualAddress> {
         self.enter(AnalysisCache::ai_train_military)
     }
@@ -1620,16 +2113,38 @@ impl<'e, E: ExecutionState<'e>> Analysis<'e, E> {
         self.enter(AnalysisCache::vertex_buffer)
     }
 
+    pub fn draw_quad(&mut self) -> Option<E::VirtualAddress> {
+        self.enter(AnalysisCache::draw_quad)
+    }
+
+    pub fn bind_texture(&mut self) -> Option<E::VirtualAddress> {
+        self.enter(AnalysisCache::bind_texture)
+    }
+
     pub fn crt_fastfail(&mut self) -> Rc<Vec<E::VirtualAddress>> {
         self.enter(AnalysisCache::crt_fastfail)
     }
 
-    pub fn clamp_zoom(&mut self) -> Option<E::VirtualAddress> {
-        self.enter(AnalysisCache::clamp_zoom)
+    pub fn game_to_screen_coords(&mut self) -> Option<E::VirtualAddress> {
+        self.enter(AnalysisCache::game_to_screen_coords)
+    }
+
+    pub fn unit_under_cursor(&mut self) -> Option<Operand<'e>> {
+        self.enter(AnalysisCache::unit_under_cursor)
+    }
+
+    pub fn finalize_drag_select(&mut self) -> Option<E::VirtualAddress> {
+        self.enter(AnalysisCache::finalize_drag_select)
+    }
+
+    /// Start corner of the drag selection box, `(select_start_x, select_start_y)`.
+    /// The other corner is current mouse position.
+    pub fn selection_box(&mut self) -> Option<(Operand<'e>, Operand<'e>)> {
+        Some((self.select_start_x()?, self.select_start_y()?))
     }
 
-    pub fn ai_spell_cast(&mut self) -> Option<E::VirtualAddress> {
-        self.enter(AnalysisCache::ai_spell_cast)
+    pub fn clamp_zoom(&mut self) -> Option<E::VirtualAddress> {
+        self.enter(AnalysisCache::clamp_zoom)
     }
 
     pub fn give_unit(&mut self) -> Option<E::VirtualAddress> {
@@ -1640,6 +2155,124 @@ impl<'e, E: ExecutionState<'e>> Analysis<'e, E> {
         self.enter(AnalysisCache::set_unit_player)
     }
 
+    /// Size of a single region in `ai_regions[player]` array, as indexed by the
+    /// `change_ai_region_state` call that `ai_regions` was found from.
+    pub fn ai_region_struct_size(&mut self) -> Option<u32> {
+        self.ai_regions()?;
+        Some(self.cache.ai_region_size as u32).filter(|&x| x != 0)
+    }
+
+    pub fn ai_region_state_offset(&mut self) -> Option<u16> {
+        self.enter(AnalysisCache::ai_region_state_offset)
+    }
+
+    pub fn enqueue_build(&mut self) -> Option<E::VirtualAddress> {
+        self.enter(AnalysisCache::enqueue_build)
+    }
+
+    pub fn unit_build_queue_offset(&mut self) -> Option<u16> {
+        self.enter(AnalysisCache::unit_build_queue_offset)
+    }
+
+    pub fn cloak_energy_drain(&mut self) -> Option<E::VirtualAddress> {
+        self.enter(AnalysisCache::cloak_energy_drain)
+    }
+
+    pub fn spawn_larva(&mut self) -> Option<E::VirtualAddress> {
+        self.enter(AnalysisCache::spawn_larva)
+    }
+
+    pub fn can_afford(&mut self) -> Option<E::VirtualAddress> {
+        self.enter(AnalysisCache::can_afford)
+    }
+
+    pub fn queue_tech(&mut self) -> Option<E::VirtualAddress> {
+        self.enter(AnalysisCache::queue_tech)
+    }
+
+    pub fn queue_upgrade(&mut self) -> Option<E::VirtualAddress> {
+        self.enter(AnalysisCache::queue_upgrade)
+    }
+
+    pub fn check_game_over(&mut self) -> Option<E::VirtualAddress> {
+        self.enter(AnalysisCache::check_game_over)
+    }
+
+    pub fn compute_sync_checksum(&mut self) -> Option<E::VirtualAddress> {
+        self.enter(AnalysisCache::compute_sync_checksum)
+    }
+
+    pub fn is_building_fn(&mut self) -> Option<E::VirtualAddress> {
+        self.enter(AnalysisCache::is_building_fn)
+    }
+
+    pub fn game_mode(&mut self) -> Option<Operand<'e>> {
+        self.enter(AnalysisCache::game_mode)
+    }
+
+    pub fn victory_conditions(&mut self) -> Option<Operand<'e>> {
+        self.enter(AnalysisCache::victory_conditions)
+    }
+
+    pub fn starting_resources(&mut self) -> Option<Operand<'e>> {
+        self.enter(AnalysisCache::starting_resources)
+    }
+
+    /// Offsets of lobby settings in `game_data`.
+    ///
+    /// `game_data` is found from `single_player_start` copying the entire struct, so
+    /// the field offsets come from the GameData layout in `struct_layouts`, which is
+    /// same in all versions. This returns `None` only if `game_data` wasn't found.
+    pub fn game_data_offsets(&mut self) -> Option<game_init::GameDataOffsets> {
+        self.game_data()?;
+        let layouts = E::struct_layouts();
+        Some(game_init::GameDataOffsets {
+            game_name: layouts.game_data_game_name() as u16,
+            active_human_players: layouts.game_data_active_human_players() as u16,
+            max_players: layouts.game_data_max_players() as u16,
+            game_speed: layouts.game_data_game_speed() as u16,
+            host_name: layouts.game_data_host_name() as u16,
+            map_title: layouts.game_data_map_title() as u16,
+            game_type: layouts.game_data_game_template() as u16,
+        })
+    }
+
+    pub fn place_map_sprite(&mut self) -> Option<E::VirtualAddress> {
+        self.enter(AnalysisCache::place_map_sprite)
+    }
+
+    pub fn mount_archive(&mut self) -> Option<E::VirtualAddress> {
+        self.enter(AnalysisCache::mount_archive)
+    }
+
+    /// Returns `(tileset_cv5, tileset_vx4ex, minitile_graphics)`, that is, loaded
+    /// cv5, vx4 and vr4 data of current tileset.
+    ///
+    /// Not named `tileset_data` since that is the per-tileset buffer array.
+    pub fn tileset_cv5_vx4_vr4(&mut self) -> Option<(Operand<'e>, Operand<'e>, Operand<'e>)> {
+        Some((self.tileset_cv5()?, self.tileset_vx4ex()?, self.minitile_graphics()?))
+    }
+
+    pub fn archive_chain_head(&mut self) -> Option<Operand<'e>> {
+        self.enter(AnalysisCache::archive_chain_head)
+    }
+
+    pub fn update_button_states(&mut self) -> Option<E::VirtualAddress> {
+        self.enter(AnalysisCache::update_button_states)
+    }
+
+    pub fn draw_command_button(&mut self) -> Option<E::VirtualAddress> {
+        self.enter(AnalysisCache::draw_command_button)
+    }
+
+    pub fn find_dialog_control(&mut self) -> Option<E::VirtualAddress> {
+        self.enter(AnalysisCache::find_dialog_control)
+    }
+
+    pub fn apply_stim(&mut self) -> Option<E::VirtualAddress> {
+        self.enter(AnalysisCache::apply_stim)
+    }
+
     pub fn start_udp_server(&mut self) -> Option<E::VirtualAddress> {
         self.enter(AnalysisCache::start_udp_server)
     }
@@ -1648,6 +2281,88 @@ impl<'e, E: ExecutionState<'e>> Analysis<'e, E> {
         self.base_anim_set().map(|_| self.cache.anim_struct_size)
     }
 
+    pub fn unit_energy_offset(&mut self) -> Option<u16> {
+        self.unit_step_energy().map(|_| self.cache.unit_energy_offset)
+    }
+
+    pub fn unit_remaining_build_time_offset(&mut self) -> Option<u16> {
+        self.step_construction().map(|_| self.cache.unit_remaining_build_time_offset)
+    }
+
+    /// Offset of `building.addon` in unit struct.
+    pub fn unit_addon_offset(&mut self) -> Option<u16> {
+        self.attach_addon().map(|_| self.cache.unit_addon_offset)
+    }
+
+    /// Offsets of the status effect timers that `step_unit_status_effects` decrements;
+    /// remaining durations of ensnare / lockdown / maelstrom etc.
+    pub fn unit_status_timer_offsets(&mut self) -> Option<units::UnitStatusTimers> {
+        self.step_unit_status_effects().map(|_| self.cache.unit_status_timers)
+    }
+
+    /// Offset of the rank byte in unit struct, if the executable has been modded to have one.
+    pub fn unit_rank_offset(&mut self) -> Option<u16> {
+        self.update_unit_rank().map(|_| self.cache.unit_rank_offset)
+    }
+
+    /// Offsets of the flingy speed fields which `unit_update_speed` writes from
+    /// the buffed speed / acceleration / turn speed values.
+    pub fn flingy_speed_offsets(&mut self) -> Option<units::FlingySpeedOffsets> {
+        self.enter(AnalysisCache::flingy_speed_offsets)
+    }
+
+    // Struct layout constants.
+    //
+    // Unlike the offsets above, these are not analyzed from code; the fields are at same
+    // offset in all executables with same word size, and the values are just taken from
+    // `struct_layouts`. They are exposed so that users don't have to duplicate the layouts.
+
+    /// Offset of `unit.flags`, the u32 status flags (completed, grounded / air, burrowed,
+    /// in transport, cloaked, etc.)
+    pub fn unit_status_flags_offset(&self) -> u16 {
+        E::struct_layouts().unit_flags() as u16
+    }
+
+    /// Offsets of `unit.order_target` position and unit, which `prepare_issue_order` /
+    /// `do_next_queued_order` write and `do_attack` reads.
+    pub fn unit_order_target_offsets(&self) -> units::OrderTargetOffsets {
+        let layouts = E::struct_layouts();
+        let pos = layouts.unit_order_target_pos() as u16;
+        units::OrderTargetOffsets {
+            target_unit: layouts.unit_target() as u16,
+            target_x: pos,
+            target_y: pos + 2,
+        }
+    }
+
+    /// Offset of `bullet.target` unit pointer.
+    pub fn bullet_target_offset(&self) -> u16 {
+        E::struct_layouts().bullet_target() as u16
+    }
+
+    /// Offset of `bullet.weapon_id` (u8).
+    pub fn bullet_weapon_id_offset(&self) -> u16 {
+        E::struct_layouts().bullet_weapon_id() as u16
+    }
+
+    /// Offset of `unit.facing_direction` (u8), which `update_unit_facing` turns.
+    pub fn unit_facing_offset(&self) -> u16 {
+        E::struct_layouts().flingy_facing_direction() as u16
+    }
+
+    /// Size of a single guard ai struct.
+    ///
+    /// `first_guard_ai` is an array of per-player list heads (2 words each),
+    /// the guard ais themselves are allocated from a fixed array.
+    pub fn guard_ai_size(&self) -> u32 {
+        E::struct_layouts().guard_ai_size() as u32
+    }
+
+    /// Offset of unit.rally_pos; rally target unit follows it.
+    pub fn unit_rally_offset(&mut self) -> Option<u16> {
+        self.set_rally_point().map(|_| self.cache.unit_rally_offset)
+    }
+
     pub fn draw_game_layer(&mut self) -> Option<E::VirtualAddress> {
         self.enter(AnalysisCache::draw_game_layer)
     }
@@ -1662,6 +2377,58 @@ impl<'e, E: ExecutionState<'e>> Analysis<'e, E> {
         Some(self.cache.bnet_message_switch?.as_operand(self.shareable.ctx))
     }
 
+    pub fn process_commands_switch_op(&mut self) -> Option<Operand<'e>> {
+        let switch = self.enter(|x, s| x.process_commands_switch(s))?;
+        Some(switch.as_operand(self.shareable.ctx))
+    }
+
+    /// Returns `(case, branch address)` for each case of a switch.
+    ///
+    /// Cases that jump to the switch's default branch are included, as they can't be
+    /// told apart from other cases. If the switch wasn't found, returns an empty `Vec`.
+    pub fn switch_cases(&mut self, switch: SwitchKind) -> Vec<(u32, E::VirtualAddress)> {
+        // Iscript and aiscript switches are just plain tables without bounds check
+        // (Or the bounds check isn't found), use the vanilla opcode counts for them.
+        let complete = match switch {
+            SwitchKind::ProcessCommands => self.enter(|x, s| x.process_commands_switch(s)),
+            SwitchKind::ProcessLobbyCommands => {
+                self.enter(|x, s| x.process_lobby_commands_switch(s))
+            }
+            SwitchKind::BnetMessage => {
+                self.bnet_controller();
+                self.cache.bnet_message_switch
+            }
+            SwitchKind::StepIscript | SwitchKind::AiScript => {
+                let (table, count) = match switch {
+                    SwitchKind::StepIscript => (self.step_iscript_switch(), 0x45),
+                    _ => (self.enter(|x, s| x.aiscript_switch_table(s)), 0x71),
+                };
+                let table = match table {
+                    Some(s) => s,
+                    None => return Vec::new(),
+                };
+                let binary = self.shareable.binary;
+                return (0..count)
+                    .filter_map(|i| {
+                        Some((i, crate::switch::simple_switch_branch(binary, table, i)?))
+                    })
+                    .collect();
+            }
+        };
+        let complete = match complete {
+            Some(s) => s,
+            None => return Vec::new(),
+        };
+        let binary = self.shareable.binary;
+        let ctx = self.shareable.ctx;
+        let (low, high) = complete.limits();
+        // Limit to something reasonable in case the upper bound wasn't known
+        let high = high.min(low.saturating_add(0x1000));
+        (low..=high)
+            .filter_map(|i| Some((i, complete.branch(binary, ctx, i)?)))
+            .collect()
+    }
+
     pub fn join_param_variant_type_offset(&mut self) -> Option<usize> {
         self.enter(AnalysisCache::join_param_variant_type_offset)
     }
@@ -1679,6 +2446,49 @@ impl<'e, E: ExecutionState<'e>> Analysis<'e, E> {
         self.enter(AnalysisCache::cursor_scale_factor)
     }
 
+    /// Returns a deterministic textual form of `op`, meant for comparing or storing
+    /// results across runs and executables.
+    ///
+    /// Unlike `Debug`, the format is kept stable; see `util::write_operand_signature`
+    /// for details.
+    pub fn operand_signature(&self, op: Operand<'e>) -> String {
+        let mut out = String::new();
+        crate::util::write_operand_signature(&mut out, op, E::WORD_SIZE);
+        out
+    }
+
+    /// Returns up to `max_bytes` of code starting from `addr`, e.g. for building
+    /// hook trampolines.
+    ///
+    /// Returns `None` if `addr` is not in the code section; the result may be shorter
+    /// than `max_bytes` if the section ends before that.
+    pub fn function_prologue(
+        &mut self,
+        addr: E::VirtualAddress,
+        max_bytes: usize,
+    ) -> Option<Vec<u8>> {
+        let text = self.shareable.binary_sections.text;
+        if addr < text.virtual_address {
+            return None;
+        }
+        let relative = addr.as_u64().wrapping_sub(text.virtual_address.as_u64()) as usize;
+        let bytes = text.data.get(relative..)?;
+        if bytes.is_empty() {
+            return None;
+        }
+        Some(bytes[..bytes.len().min(max_bytes)].to_vec())
+    }
+
+    /// Iterates through the cached dat patches without copying them
+    /// like `dat_patches_debug_data` does.
+    pub fn dat_patches_iter(
+        &mut self,
+    ) -> Option<impl Iterator<Item = &DatPatch<'e, E::VirtualAddress>>> {
+        self.dat_patches()?;
+        let patches = self.cache.dat_patches.get()?.as_ref()?;
+        Some(patches.patches.iter())
+    }
+
     /// Mainly for tests/dump
     pub fn dat_patches_debug_data(
         &mut self,
@@ -1924,15 +2734,17 @@ impl<'e, E: ExecutionState<'e>> AnalysisCache<'e, E> {
         let result = self.address_results[addr as usize];
         if result != E::VirtualAddress::from_u64(0) {
             if result == E::VirtualAddress::from_u64(1) {
+                self.trace_missing(FailureReason::MissingAddress(addr));
                 return None;
             } else {
                 return Some(result);
             }
         }
         self.address_results[addr as usize] = E::VirtualAddress::from_u64(1);
-        let result = cb(self);
-        if let Some(result) = result {
-            self.address_results[addr as usize] = result;
+        let result = self.timed(addr.name(), cb);
+        match result {
+            Some(result) => self.address_results[addr as usize] = result,
+            None => self.trace_missing(FailureReason::MissingAddress(addr)),
         }
         result
     }
@@ -1942,19 +2754,45 @@ impl<'e, E: ExecutionState<'e>> AnalysisCache<'e, E> {
     {
         if let Some(result) = self.operand_results[op as usize] {
             if result == self.operand_not_found {
+                self.trace_missing(FailureReason::MissingOperand(op));
                 return None;
             } else {
                 return Some(result);
             }
         }
         self.operand_results[op as usize] = Some(self.operand_not_found);
+        let result = self.timed(op.name(), cb);
+        match result {
+            Some(_) => self.operand_results[op as usize] = result,
+            None => self.trace_missing(FailureReason::MissingOperand(op)),
+        }
+        result
+    }
+
+    fn timed<F, R>(&mut self, name: &'static str, cb: F) -> R
+    where F: FnOnce(&mut Self) -> R
+    {
+        let outer_child_time = match self.timings {
+            Some(ref mut timings) => std::mem::take(&mut timings.child_time),
+            None => return cb(self),
+        };
+        let start = Instant::now();
         let result = cb(self);
-        if result.is_some() {
-            self.operand_results[op as usize] = result;
+        let elapsed = start.elapsed();
+        if let Some(ref mut timings) = self.timings {
+            let own_time = elapsed.saturating_sub(timings.child_time);
+            timings.results.push((name, own_time));
+            timings.child_time = outer_child_time + elapsed;
         }
         result
     }
 
+    fn trace_missing(&mut self, reason: FailureReason) {
+        if let Some(ref mut trace) = self.missing_trace {
+            trace.push(reason);
+        }
+    }
+
     fn cache_many<F, const ADDR_COUNT: usize, const OPERAND_COUNT: usize>(
         &mut self,
         addresses: &[AddressAnalysis; ADDR_COUNT],
@@ -1970,7 +2808,10 @@ impl<'e, E: ExecutionState<'e>> AnalysisCache<'e, E> {
         for &op in operands {
             self.operand_results[op as usize] = Some(self.operand_not_found);
         }
-        let result = func(self);
+        let name = addresses.first().map(|x| x.name())
+            .or_else(|| operands.first().map(|x| x.name()))
+            .unwrap_or("");
+        let result = self.timed(name, func);
         if let Some(ref res) = result {
             for i in 0..ADDR_COUNT {
                 if let Some(addr) = res.0[i] {
@@ -1995,8 +2836,12 @@ impl<'e, E: ExecutionState<'e>> AnalysisCache<'e, E> {
         if self.address_results[addr as usize] == E::VirtualAddress::from_u64(0) {
             cache_fn(self);
         }
-        Some(self.address_results[addr as usize])
-            .filter(|&addr| addr != E::VirtualAddress::from_u64(1))
+        let result = Some(self.address_results[addr as usize])
+            .filter(|&addr| addr != E::VirtualAddress::from_u64(1));
+        if result.is_none() {
+            self.trace_missing(FailureReason::MissingAddress(addr));
+        }
+        result
     }
 
     fn cache_many_op<F>(&mut self, op: OperandAnalysis, cache_fn: F) -> Option<Operand<'e>>
@@ -2005,8 +2850,12 @@ impl<'e, E: ExecutionState<'e>> AnalysisCache<'e, E> {
         if self.operand_results[op as usize].is_none() {
             cache_fn(self);
         }
-        self.operand_results[op as usize]
-            .filter(|&op| op != self.operand_not_found)
+        let result = self.operand_results[op as usize]
+            .filter(|&op| op != self.operand_not_found);
+        if result.is_none() {
+            self.trace_missing(FailureReason::MissingOperand(op));
+        }
+        result
     }
 
     pub fn firegraft_addresses(
@@ -2108,15 +2957,26 @@ impl<'e, E: ExecutionState<'e>> AnalysisCache<'e, E> {
         self.cache_many(&[GetRegion, ChangeAiRegionState], &[OperandAnalysis::AiRegions], |s| {
             let aiscript_hook = s.aiscript_hook(actx);
             let result = pathing::regions(actx, aiscript_hook.as_ref()?);
+            s.ai_region_size = result.ai_region_size as u16;
             Some(([result.get_region, result.change_ai_region_state], [result.ai_regions]))
         })
     }
 
-    #[cfg(feature = "test_assertions")]
     fn change_ai_region_state(&mut self, actx: &AnalysisCtx<'e, E>) -> Option<E::VirtualAddress> {
         self.cache_many_addr(AddressAnalysis::ChangeAiRegionState, |s| s.cache_regions(actx))
     }
 
+    fn ai_region_state_offset(&mut self, actx: &AnalysisCtx<'e, E>) -> Option<u16> {
+        if self.ai_region_state_offset == u16::MAX {
+            self.ai_region_state_offset = 0xfffe;
+            let change_ai_region_state = self.change_ai_region_state(actx)?;
+            if let Some(result) = pathing::ai_region_state_offset(actx, change_ai_region_state) {
+                self.ai_region_state_offset = result;
+            }
+        }
+        Some(self.ai_region_state_offset).filter(|&x| x < 0xfffe)
+    }
+
     fn get_region(&mut self, actx: &AnalysisCtx<'e, E>) -> Option<E::VirtualAddress> {
         self.cache_many_addr(AddressAnalysis::GetRegion, |s| s.cache_regions(actx))
     }
@@ -2224,6 +3084,8 @@ impl<'e, E: ExecutionState<'e>> AnalysisCache<'e, E> {
         self.cache_many(&[], &[UniqueCommandUser, Selections], |s| {
             let switch = s.process_commands_switch(actx)?;
             let result = commands::selections(actx, &switch);
+            s.max_selection_count = result.max_selection_count as u16;
+            s.selection_array_stride = result.selection_array_stride as u16;
             Some(([], [result.unique_command_user, result.selections]))
         })
     }
@@ -2342,6 +3204,21 @@ impl<'e, E: ExecutionState<'e>> AnalysisCache<'e, E> {
         result
     }
 
+    fn global_references(
+        &mut self,
+        global: E::VirtualAddress,
+        actx: &AnalysisCtx<'e, E>,
+    ) -> Vec<E::VirtualAddress> {
+        let functions = self.function_finder();
+        let mut result = functions.find_functions_using_global(actx, global)
+            .iter()
+            .map(|x| x.use_address)
+            .collect::<Vec<_>>();
+        result.sort_unstable();
+        result.dedup();
+        result
+    }
+
     fn cache_single_player_start(&mut self, actx: &AnalysisCtx<'e, E>) {
         use AddressAnalysis::*;
         use OperandAnalysis::*;
@@ -2369,23 +3246,69 @@ impl<'e, E: ExecutionState<'e>> AnalysisCache<'e, E> {
         )
     }
 
-    fn local_storm_player_id(&mut self, actx: &AnalysisCtx<'e, E>) -> Option<Operand<'e>> {
-        self.cache_many_op(
-            OperandAnalysis::LocalStormPlayerId,
-            |s| s.cache_single_player_start(actx),
-        )
+    fn game_data(&mut self, actx: &AnalysisCtx<'e, E>) -> Option<Operand<'e>> {
+        self.cache_many_op(OperandAnalysis::GameData, |s| s.cache_single_player_start(actx))
     }
 
-    fn local_player_id(&mut self, actx: &AnalysisCtx<'e, E>) -> Option<Operand<'e>> {
-        self.cache_single_operand(OperandAnalysis::LocalPlayerId, |s| {
-            players::local_player_id(actx, s.game_screen_rclick(actx)?)
+    fn game_mode(&mut self, actx: &AnalysisCtx<'e, E>) -> Option<Operand<'e>> {
+        self.cache_single_operand(OperandAnalysis::GameMode, |s| {
+            let game_data = s.game_data(actx)?;
+            let init_game = s.init_game(actx)?;
+            let offset = E::struct_layouts().game_data_game_template();
+            let size = MemAccessSize::Mem16;
+            if game_init::reads_game_data_field(actx, init_game, game_data, offset, size) {
+                Some(actx.ctx.mem16(game_data, offset))
+            } else {
+                None
+            }
         })
     }
 
-    fn cache_game_screen_rclick(&mut self, actx: &AnalysisCtx<'e, E>) {
-        use AddressAnalysis::*;
-        self.cache_many(&[GameScreenRClick], &[OperandAnalysis::ClientSelection], |s| {
-            let units_dat = s.dat_virtual_address(DatType::Units, actx)?;
+    fn victory_conditions(&mut self, actx: &AnalysisCtx<'e, E>) -> Option<Operand<'e>> {
+        self.cache_single_operand(OperandAnalysis::VictoryConditions, |s| {
+            let game_data = s.game_data(actx)?;
+            let check_game_over = s.check_game_over(actx)?;
+            let offset = E::struct_layouts().game_data_victory_condition();
+            let size = MemAccessSize::Mem8;
+            if game_init::reads_game_data_field(actx, check_game_over, game_data, offset, size) {
+                Some(actx.ctx.mem8(game_data, offset))
+            } else {
+                None
+            }
+        })
+    }
+
+    fn starting_resources(&mut self, actx: &AnalysisCtx<'e, E>) -> Option<Operand<'e>> {
+        self.cache_single_operand(OperandAnalysis::StartingResources, |s| {
+            let game_data = s.game_data(actx)?;
+            let init_game = s.init_game(actx)?;
+            let offset = E::struct_layouts().game_data_resource_amount();
+            let size = MemAccessSize::Mem32;
+            if game_init::reads_game_data_field(actx, init_game, game_data, offset, size) {
+                Some(actx.ctx.mem32(game_data, offset))
+            } else {
+                None
+            }
+        })
+    }
+
+    fn local_storm_player_id(&mut self, actx: &AnalysisCtx<'e, E>) -> Option<Operand<'e>> {
+        self.cache_many_op(
+            OperandAnalysis::LocalStormPlayerId,
+            |s| s.cache_single_player_start(actx),
+        )
+    }
+
+    fn local_player_id(&mut self, actx: &AnalysisCtx<'e, E>) -> Option<Operand<'e>> {
+        self.cache_single_operand(OperandAnalysis::LocalPlayerId, |s| {
+            players::local_player_id(actx, s.game_screen_rclick(actx)?)
+        })
+    }
+
+    fn cache_game_screen_rclick(&mut self, actx: &AnalysisCtx<'e, E>) {
+        use AddressAnalysis::*;
+        self.cache_many(&[GameScreenRClick], &[OperandAnalysis::ClientSelection], |s| {
+            let units_dat = s.dat_virtual_address(DatType::Units, actx)?;
             let functions = s.function_finder();
             let result = clientside::game_screen_rclick(actx, units_dat, &functions);
             Some(([result.game_screen_rclick], [result.client_selection]))
@@ -2445,13 +3368,30 @@ impl<'e, E: ExecutionState<'e>> AnalysisCache<'e, E> {
         use AddressAnalysis::*;
         use OperandAnalysis::*;
         self.cache_many(&[ReceiveStormTurns, ProcessCommands, ProcessLobbyCommands], &[
-            NetPlayerFlags, PlayerTurns, PlayerTurnsSize, NetworkReady, StormCommandUser,
+            NetPlayerFlags, PlayerTurns, PlayerTurnsSize, StormTurnCount, NetworkReady,
+            StormCommandUser,
         ], |s| {
             let step_network = s.step_network(actx)?;
             let result = commands::analyze_step_network(actx, step_network);
             Some(([result.receive_storm_turns, result.process_commands,
                 result.process_lobby_commands], [result.net_player_flags, result.player_turns,
-                result.player_turns_size, result.network_ready, result.storm_command_user]))
+                result.player_turns_size, result.storm_turn_count, result.network_ready,
+                result.storm_command_user]))
+        })
+    }
+
+    fn storm_command_user(&mut self, actx: &AnalysisCtx<'e, E>) -> Option<Operand<'e>> {
+        self.cache_many_op(OperandAnalysis::StormCommandUser, |s| s.cache_step_network(actx))
+    }
+
+    fn cache_player_drop(&mut self, actx: &AnalysisCtx<'e, E>) {
+        use AddressAnalysis::*;
+        use OperandAnalysis::*;
+        self.cache_many(&[HandlePlayerDrop], &[PlayerLeftFlags], |s| {
+            let step_network = s.step_network(actx)?;
+            let net_player_flags = s.net_player_flags(actx)?;
+            let result = commands::handle_player_drop(actx, step_network, net_player_flags);
+            Some(([result.handle_player_drop], [result.player_left_flags]))
         })
     }
 
@@ -2492,6 +3432,18 @@ impl<'e, E: ExecutionState<'e>> AnalysisCache<'e, E> {
         })
     }
 
+    fn cache_init_observer(&mut self, actx: &AnalysisCtx<'e, E>) {
+        use AddressAnalysis::*;
+        use OperandAnalysis::*;
+        self.cache_many(&[InitObserver], &[IsObserver], |s| {
+            let init_game_network = s.init_game_network(actx)?;
+            let local_storm_player_id = s.local_storm_player_id(actx)?;
+            let result =
+                game_init::init_observer(actx, init_game_network, local_storm_player_id);
+            Some(([result.init_observer], [result.is_observer]))
+        })
+    }
+
     fn snp_definitions(&mut self, actx: &AnalysisCtx<'e, E>) -> Option<SnpDefinitions<'e>> {
         if let Some(cached) = self.snp_definitions.cached() {
             return cached;
@@ -2631,12 +3583,38 @@ impl<'e, E: ExecutionState<'e>> AnalysisCache<'e, E> {
         })
     }
 
+    fn add_status_overlay(&mut self, actx: &AnalysisCtx<'e, E>) -> Option<E::VirtualAddress> {
+        self.cache_single_address(AddressAnalysis::AddStatusOverlay, |s| {
+            let add_overlay_iscript = s.add_overlay_iscript(actx)?;
+            iscript::add_status_overlay(actx, add_overlay_iscript, &s.function_finder())
+        })
+    }
+
+    fn iscript_use_weapon_branch(
+        &mut self,
+        actx: &AnalysisCtx<'e, E>,
+    ) -> Option<E::VirtualAddress> {
+        self.cache_single_address(AddressAnalysis::IscriptUseWeaponBranch, |s| {
+            iscript::iscript_use_weapon_branch(actx, s.step_iscript_switch(actx)?)
+        })
+    }
+
     fn draw_cursor_marker(&mut self, actx: &AnalysisCtx<'e, E>) -> Option<Operand<'e>> {
         self.cache_single_operand(OperandAnalysis::DrawCursorMarker, |s| {
             iscript::draw_cursor_marker(actx, s.step_iscript_switch(actx)?)
         })
     }
 
+    fn cache_play_smk(&mut self, actx: &AnalysisCtx<'e, E>) {
+        use AddressAnalysis::*;
+        use OperandAnalysis::*;
+        self.cache_many(&[StopSmk], &[CurrentSmkContext], |s| {
+            let play_smk = s.play_smk(actx)?;
+            let result = game_init::analyze_play_smk(actx, play_smk);
+            Some(([result.stop_smk], [result.current_smk_context]))
+        })
+    }
+
     fn play_smk(&mut self, actx: &AnalysisCtx<'e, E>) -> Option<E::VirtualAddress> {
         self.cache_single_address(AddressAnalysis::PlaySmk, |s| {
             game_init::play_smk(actx, &s.function_finder())
@@ -2690,6 +3668,22 @@ impl<'e, E: ExecutionState<'e>> AnalysisCache<'e, E> {
         self.cache_many_op(OperandAnalysis::IsPaused, |s| s.cache_misc_clientside(actx))
     }
 
+    fn toggle_pause(&mut self, actx: &AnalysisCtx<'e, E>) -> Option<E::VirtualAddress> {
+        self.cache_single_address(AddressAnalysis::TogglePause, |s| {
+            let key_down_handler = s.ui_default_key_down_handler(actx)?;
+            let is_paused = s.is_paused(actx)?;
+            clientside::toggle_pause(actx, key_down_handler, is_paused)
+        })
+    }
+
+    fn center_on_townhall(&mut self, actx: &AnalysisCtx<'e, E>) -> Option<E::VirtualAddress> {
+        self.cache_single_address(AddressAnalysis::CenterOnTownhall, |s| {
+            let key_down_handler = s.ui_default_key_down_handler(actx)?;
+            let units_dat = s.dat_virtual_address(DatType::Units, actx)?;
+            clientside::center_on_townhall(actx, key_down_handler, units_dat)
+        })
+    }
+
     fn is_placing_building(&mut self, actx: &AnalysisCtx<'e, E>) -> Option<Operand<'e>> {
         self.cache_many_op(OperandAnalysis::IsPlacingBuilding, |s| s.cache_misc_clientside(actx))
     }
@@ -2900,10 +3894,42 @@ impl<'e, E: ExecutionState<'e>> AnalysisCache<'e, E> {
         self.cache_many_op(OperandAnalysis::ActiveIscriptUnit, |s| s.cache_bullet_creation(actx))
     }
 
+    fn create_bullet(&mut self, actx: &AnalysisCtx<'e, E>) -> Option<E::VirtualAddress> {
+        self.cache_many_addr(AddressAnalysis::CreateBullet, |s| s.cache_bullet_creation(actx))
+    }
+
+    fn cache_terrain_height(&mut self, actx: &AnalysisCtx<'e, E>) {
+        self.cache_many(
+            &[AddressAnalysis::GetTerrainHeight, AddressAnalysis::ComputeMissChance],
+            &[OperandAnalysis::ElevationData],
+            |s| {
+                let create_bullet = s.create_bullet(actx)?;
+                let result = bullets::terrain_height(actx, create_bullet);
+                Some((
+                    [result.get_terrain_height, result.compute_miss_chance],
+                    [result.elevation_data],
+                ))
+            },
+        )
+    }
+
     fn first_active_bullet(&mut self, actx: &AnalysisCtx<'e, E>) -> Option<Operand<'e>> {
         self.cache_many_op(OperandAnalysis::FirstActiveBullet, |s| s.cache_bullet_creation(actx))
     }
 
+    fn first_free_bullet(&mut self, actx: &AnalysisCtx<'e, E>) -> Option<Operand<'e>> {
+        self.cache_many_op(OperandAnalysis::FirstFreeBullet, |s| s.cache_bullet_creation(actx))
+    }
+
+    fn bullet_array(&mut self, actx: &AnalysisCtx<'e, E>) -> Option<Operand<'e>> {
+        self.cache_single_operand(OperandAnalysis::BulletArray, |s| {
+            let first_free = s.first_free_bullet(actx)?;
+            let (array, size) = bullets::bullet_array(actx, first_free, &s.function_finder())?;
+            s.bullet_struct_size = size as u16;
+            Some(array)
+        })
+    }
+
     fn cache_net_players(&mut self, actx: &AnalysisCtx<'e, E>) {
         self.cache_many(&[AddressAnalysis::InitNetPlayer], &[OperandAnalysis::NetPlayers], |s| {
             let switch = s.process_lobby_commands_switch(actx)?;
@@ -2921,9 +3947,9 @@ impl<'e, E: ExecutionState<'e>> AnalysisCache<'e, E> {
 
     fn cache_run_dialog(&mut self, actx: &AnalysisCtx<'e, E>) {
         use AddressAnalysis::*;
-        self.cache_many(&[RunDialog, GluCmpgnEventHandler], &[], |s| {
+        self.cache_many(&[RunDialog, GluCmpgnEventHandler, LoadDialogBin], &[], |s| {
             let result = dialog::run_dialog(actx, &s.function_finder());
-            Some(([result.run_dialog, result.event_handler], []))
+            Some(([result.run_dialog, result.event_handler, result.load_dialog], []))
         })
     }
 
@@ -2935,6 +3961,13 @@ impl<'e, E: ExecutionState<'e>> AnalysisCache<'e, E> {
         self.cache_many_addr(AddressAnalysis::GluCmpgnEventHandler, |s| s.cache_run_dialog(actx))
     }
 
+    fn find_dialog_control(&mut self, actx: &AnalysisCtx<'e, E>) -> Option<E::VirtualAddress> {
+        self.cache_single_address(AddressAnalysis::FindDialogControl, |s| {
+            let event_handler = s.glucmpgn_event_handler(actx)?;
+            dialog::find_dialog_control(actx, event_handler)
+        })
+    }
+
     fn ai_update_attack_target(
         &mut self,
         actx: &AnalysisCtx<'e, E>,
@@ -2954,7 +3987,7 @@ impl<'e, E: ExecutionState<'e>> AnalysisCache<'e, E> {
 
     fn cache_coord_conversion(&mut self, actx: &AnalysisCtx<'e, E>) {
         use OperandAnalysis::*;
-        self.cache_many(&[], &[ScreenX, ScreenY, Zoom], |s| {
+        self.cache_many(&[ScreenToGameCoords], &[ScreenX, ScreenY, Zoom], |s| {
             let game_screen_rclick = s.game_screen_rclick(actx)?;
             let is_outside_game_screen = s.is_outside_game_screen(actx)?;
             let result = clientside::game_coord_conversion(
@@ -2962,7 +3995,26 @@ impl<'e, E: ExecutionState<'e>> AnalysisCache<'e, E> {
                 game_screen_rclick,
                 is_outside_game_screen
             );
-            Some(([], [result.screen_x, result.screen_y, result.scale]))
+            Some((
+                [result.screen_to_game_coords],
+                [result.screen_x, result.screen_y, result.scale],
+            ))
+        })
+    }
+
+    fn screen_x(&mut self, actx: &AnalysisCtx<'e, E>) -> Option<Operand<'e>> {
+        self.cache_many_op(OperandAnalysis::ScreenX, |s| s.cache_coord_conversion(actx))
+    }
+
+    fn zoom(&mut self, actx: &AnalysisCtx<'e, E>) -> Option<Operand<'e>> {
+        self.cache_many_op(OperandAnalysis::Zoom, |s| s.cache_coord_conversion(actx))
+    }
+
+    fn game_to_screen_coords(&mut self, actx: &AnalysisCtx<'e, E>) -> Option<E::VirtualAddress> {
+        self.cache_single_address(AddressAnalysis::GameToScreenCoords, |s| {
+            let screen_x = s.screen_x(actx)?;
+            let zoom = s.zoom(actx)?;
+            clientside::game_to_screen_coords(actx, screen_x, zoom, &s.function_finder())
         })
     }
 
@@ -3029,6 +4081,10 @@ impl<'e, E: ExecutionState<'e>> AnalysisCache<'e, E> {
         })
     }
 
+    fn create_unit(&mut self, actx: &AnalysisCtx<'e, E>) -> Option<E::VirtualAddress> {
+        self.cache_many_addr(AddressAnalysis::CreateUnit, |s| s.cache_unit_creation(actx))
+    }
+
     fn finish_unit_pre(&mut self, actx: &AnalysisCtx<'e, E>) -> Option<E::VirtualAddress> {
         self.cache_many_addr(AddressAnalysis::FinishUnitPre, |s| s.cache_unit_creation(actx))
     }
@@ -3125,6 +4181,7 @@ impl<'e, E: ExecutionState<'e>> AnalysisCache<'e, E> {
                 smem_alloc: None,
                 smem_free: None,
                 allocator: None,
+                values: [None; 7],
             }
         });
         let result = Rc::new(result);
@@ -3216,11 +4273,11 @@ impl<'e, E: ExecutionState<'e>> AnalysisCache<'e, E> {
     fn cache_aiscript_switch(&mut self, actx: &AnalysisCtx<'e, E>) {
         use AddressAnalysis::*;
         self.cache_many(
-            &[AiAttackPrepare, AiAttackClear], &[],
+            &[AiAttackPrepare, AiAttackClear, AiRequestUnit], &[],
             |s| {
                 let aiscript_switch = s.aiscript_switch_table(actx)?;
                 let r = ai::aiscript_switch_analysis(actx, aiscript_switch);
-                Some(([r.ai_attack_prepare, r.ai_attack_clear], []))
+                Some(([r.ai_attack_prepare, r.ai_attack_clear, r.ai_request_unit], []))
             },
         )
     }
@@ -3336,6 +4393,12 @@ impl<'e, E: ExecutionState<'e>> AnalysisCache<'e, E> {
         self.limits(actx).allocator
     }
 
+    fn smem_alloc_heap(&mut self, actx: &AnalysisCtx<'e, E>) -> Option<Operand<'e>> {
+        let limits = self.limits(actx);
+        limits.smem_alloc?;
+        limits.allocator
+    }
+
     fn cache_cmdicons(&mut self, actx: &AnalysisCtx<'e, E>) {
         use OperandAnalysis::*;
         self.cache_many(&[], &[CmdIconsDdsGrp, CmdBtnsDdsGrp], |s| {
@@ -3346,6 +4409,14 @@ impl<'e, E: ExecutionState<'e>> AnalysisCache<'e, E> {
         })
     }
 
+    fn draw_command_button(&mut self, actx: &AnalysisCtx<'e, E>) -> Option<E::VirtualAddress> {
+        self.cache_single_address(AddressAnalysis::DrawCommandButton, |s| {
+            let firegraft = s.firegraft_addresses(actx);
+            let &status_arr = firegraft.unit_status_funcs.get(0)?;
+            dialog::draw_command_button(actx, status_arr)
+        })
+    }
+
     fn cache_mouse_xy(&mut self, actx: &AnalysisCtx<'e, E>) {
         use AddressAnalysis::*;
         use OperandAnalysis::*;
@@ -3528,6 +4599,28 @@ impl<'e, E: ExecutionState<'e>> AnalysisCache<'e, E> {
         result
     }
 
+    fn cache_trigger_locations(&mut self, actx: &AnalysisCtx<'e, E>) {
+        use OperandAnalysis::*;
+        self.cache_many(&[], &[LocationArray], |s| {
+            let conditions = s.trigger_conditions(actx)?;
+            let game = s.game(actx)?;
+            let result = map::trigger_locations(actx, conditions, game);
+            s.location_struct_size = result.stride as u16;
+            Some(([], [result.array]))
+        })
+    }
+
+    fn cache_trigger_death_counters(&mut self, actx: &AnalysisCtx<'e, E>) {
+        use OperandAnalysis::*;
+        self.cache_many(&[], &[DeathCounters], |s| {
+            let conditions = s.trigger_conditions(actx)?;
+            let game = s.game(actx)?;
+            let result = map::trigger_death_counters(actx, conditions, game);
+            s.death_counters_row_size = result.stride as u16;
+            Some(([], [result.array]))
+        })
+    }
+
     pub fn trigger_completed_units_cache(
         &mut self,
         actx: &AnalysisCtx<'e, E>,
@@ -3555,6 +4648,27 @@ impl<'e, E: ExecutionState<'e>> AnalysisCache<'e, E> {
         )
     }
 
+    fn cache_load_map_strings(&mut self, actx: &AnalysisCtx<'e, E>) {
+        use AddressAnalysis::*;
+        use OperandAnalysis::*;
+        self.cache_many(&[LoadMapStrings], &[MapStringTable], |s| {
+            let chk_callbacks = s.map_init_chk_callbacks(actx)?;
+            let result = game_init::load_map_strings(actx, chk_callbacks);
+            Some(([result.load_map_strings], [result.map_string_table]))
+        })
+    }
+
+    fn chk_section_handler_count(&mut self, actx: &AnalysisCtx<'e, E>) -> Option<u16> {
+        if self.chk_section_handler_count == u16::MAX {
+            self.chk_section_handler_count = 0xfffe;
+            let chk_callbacks = self.map_init_chk_callbacks(actx)?;
+            if let Some(result) = game_init::chk_section_handler_count(actx, chk_callbacks) {
+                self.chk_section_handler_count = result;
+            }
+        }
+        Some(self.chk_section_handler_count).filter(|&x| x < 0xfffe)
+    }
+
     fn chk_init_players(&mut self, actx: &AnalysisCtx<'e, E>) -> Option<Operand<'e>> {
         self.cache_single_operand(OperandAnalysis::ChkInitPlayers, |s| {
             let chk_callbacks = s.map_init_chk_callbacks(actx)?;
@@ -3562,6 +4676,20 @@ impl<'e, E: ExecutionState<'e>> AnalysisCache<'e, E> {
         })
     }
 
+    fn is_building_fn(&mut self, actx: &AnalysisCtx<'e, E>) -> Option<E::VirtualAddress> {
+        self.cache_single_address(AddressAnalysis::IsBuildingFn, |s| {
+            let units_dat = s.dat_virtual_address(DatType::Units, actx)?;
+            units::is_building_fn(actx, units_dat, &s.function_finder())
+        })
+    }
+
+    fn place_map_sprite(&mut self, actx: &AnalysisCtx<'e, E>) -> Option<E::VirtualAddress> {
+        self.cache_single_address(AddressAnalysis::PlaceMapSprite, |s| {
+            let chk_callbacks = s.map_init_chk_callbacks(actx)?;
+            game_init::place_map_sprite(actx, chk_callbacks)
+        })
+    }
+
     fn original_chk_player_types(&mut self, actx: &AnalysisCtx<'e, E>) -> Option<Operand<'e>> {
         self.cache_single_operand(OperandAnalysis::OriginalChkPlayerTypes, |s| {
             let init_players = s.chk_init_players(actx)?;
@@ -3577,6 +4705,21 @@ impl<'e, E: ExecutionState<'e>> AnalysisCache<'e, E> {
         })
     }
 
+    fn trigger_run_ai_script(&mut self, actx: &AnalysisCtx<'e, E>) -> Option<E::VirtualAddress> {
+        self.cache_single_address(AddressAnalysis::TriggerRunAiScript, |s| {
+            let actions = s.trigger_actions(actx)?;
+            ai::trigger_run_ai_script(actx, actions)
+        })
+    }
+
+    fn play_unit_sound(&mut self, actx: &AnalysisCtx<'e, E>) -> Option<E::VirtualAddress> {
+        self.cache_single_address(AddressAnalysis::PlayUnitSound, |s| {
+            let select_units = s.select_units(actx)?;
+            let play_sound = s.play_sound(actx)?;
+            clientside::play_unit_sound(actx, select_units, play_sound)
+        })
+    }
+
     fn play_sound(&mut self, actx: &AnalysisCtx<'e, E>) -> Option<E::VirtualAddress> {
         self.cache_single_address(AddressAnalysis::PlaySound, |s| {
             sound::play_sound(actx, s.step_iscript_switch(actx)?)
@@ -3678,6 +4821,33 @@ impl<'e, E: ExecutionState<'e>> AnalysisCache<'e, E> {
         })
     }
 
+    fn add_to_replay_data(&mut self, actx: &AnalysisCtx<'e, E>) -> Option<E::VirtualAddress> {
+        self.cache_many_addr(AddressAnalysis::AddToReplayData, |s| s.cache_print_text(actx))
+    }
+
+    fn is_recording_replay(&mut self, actx: &AnalysisCtx<'e, E>) -> Option<Operand<'e>> {
+        self.cache_single_operand(OperandAnalysis::IsRecordingReplay, |s| {
+            let add_to_replay_data = s.add_to_replay_data(actx)?;
+            let is_replay = s.is_replay(actx)?;
+            let replay_data = s.replay_data(actx)?;
+            commands::is_recording_replay(actx, add_to_replay_data, is_replay, replay_data)
+        })
+    }
+
+    fn player_action_counts(&mut self, actx: &AnalysisCtx<'e, E>) -> Option<Operand<'e>> {
+        self.cache_single_operand(OperandAnalysis::PlayerActionCounts, |s| {
+            let process_commands = s.process_commands(actx)?;
+            let command_user = s.command_user(actx)?;
+            let storm_command_user = s.storm_command_user(actx)?;
+            commands::player_action_counts(
+                actx,
+                process_commands,
+                command_user,
+                storm_command_user,
+            )
+        })
+    }
+
     fn ai_train_military(&mut self, actx: &AnalysisCtx<'e, E>) -> Option<E::VirtualAddress> {
         self.cache_single_address(AddressAnalysis::AiTrainMilitary, |s| {
             ai::train_military(actx, s.ai_spend_money(actx)?, s.game(actx)?)
@@ -3701,6 +4871,21 @@ impl<'e, E: ExecutionState<'e>> AnalysisCache<'e, E> {
         })
     }
 
+    fn draw_quad(&mut self, actx: &AnalysisCtx<'e, E>) -> Option<E::VirtualAddress> {
+        self.cache_single_address(AddressAnalysis::DrawQuad, |s| {
+            let draw_image = s.draw_image(actx)?;
+            let vertex_buffer = s.vertex_buffer(actx)?;
+            renderer::draw_quad(actx, draw_image, vertex_buffer)
+        })
+    }
+
+    fn bind_texture(&mut self, actx: &AnalysisCtx<'e, E>) -> Option<E::VirtualAddress> {
+        self.cache_single_address(AddressAnalysis::BindTexture, |s| {
+            let vtables = s.vtables(actx);
+            renderer::bind_texture(actx, &vtables)
+        })
+    }
+
     fn crt_fastfail(&mut self, actx: &AnalysisCtx<'e, E>) -> Rc<Vec<E::VirtualAddress>> {
         if let Some(cached) = self.crt_fastfail.cached() {
             return cached;
@@ -3754,6 +4939,45 @@ impl<'e, E: ExecutionState<'e>> AnalysisCache<'e, E> {
         )
     }
 
+    fn ui_default_key_down_handler(
+        &mut self,
+        actx: &AnalysisCtx<'e, E>,
+    ) -> Option<E::VirtualAddress> {
+        self.cache_many_addr(
+            AddressAnalysis::UiDefaultKeyDownHandler,
+            |s| s.cache_ui_event_handlers(actx),
+        )
+    }
+
+    fn ui_default_char_handler(
+        &mut self,
+        actx: &AnalysisCtx<'e, E>,
+    ) -> Option<E::VirtualAddress> {
+        self.cache_many_addr(
+            AddressAnalysis::UiDefaultCharHandler,
+            |s| s.cache_ui_event_handlers(actx),
+        )
+    }
+
+    fn cache_chat_input(&mut self, actx: &AnalysisCtx<'e, E>) {
+        use AddressAnalysis::*;
+        use OperandAnalysis::*;
+        self.cache_many(&[ChatInputHandler], &[ChatInputBuffer], |s| {
+            let char_handler = s.ui_default_char_handler(actx)?;
+            let result = dialog::chat_input(actx, char_handler);
+            Some(([result.chat_input_handler], [result.chat_input_buffer]))
+        })
+    }
+
+    fn cache_game_menu_funcs(&mut self, actx: &AnalysisCtx<'e, E>) {
+        use AddressAnalysis::*;
+        self.cache_many(&[OpenGameMenu, CloseGameMenu], &[], |s| {
+            let key_down_handler = s.ui_default_key_down_handler(actx)?;
+            let result = dialog::game_menu_funcs(actx, key_down_handler);
+            Some(([result.open_game_menu, result.close_game_menu], []))
+        })
+    }
+
     fn reset_ui_event_handlers(&mut self, actx: &AnalysisCtx<'e, E>) -> Option<E::VirtualAddress> {
         self.cache_many_addr(
             AddressAnalysis::ResetUiEventHandlers,
@@ -3802,6 +5026,17 @@ impl<'e, E: ExecutionState<'e>> AnalysisCache<'e, E> {
         })
     }
 
+    fn replay_visions(&mut self, actx: &AnalysisCtx<'e, E>) -> Option<Operand<'e>> {
+        self.cache_many_op(OperandAnalysis::ReplayVisions, |s| s.cache_replay_visions(actx))
+    }
+
+    fn set_replay_vision(&mut self, actx: &AnalysisCtx<'e, E>) -> Option<E::VirtualAddress> {
+        self.cache_single_address(AddressAnalysis::SetReplayVision, |s| {
+            let replay_visions = s.replay_visions(actx)?;
+            minimap::set_replay_vision(actx, replay_visions, &s.function_finder())
+        })
+    }
+
     fn cache_menu_screens(&mut self, actx: &AnalysisCtx<'e, E>) {
         use AddressAnalysis::*;
         self.cache_many(&[SetBriefingMusic, PreMissionGlue, ShowMissionGlue,
@@ -3824,10 +5059,12 @@ impl<'e, E: ExecutionState<'e>> AnalysisCache<'e, E> {
         })
     }
 
-    fn ai_spell_cast(&mut self, actx: &AnalysisCtx<'e, E>) -> Option<E::VirtualAddress> {
-        self.cache_single_address(AddressAnalysis::AiSpellCast, |s| {
+    fn cache_order_guard(&mut self, actx: &AnalysisCtx<'e, E>) {
+        use AddressAnalysis::*;
+        self.cache_many(&[AiSpellCast, AiReturnToGuard], &[], |s| {
             let order_guard = s.order_function(0xa0, actx)?;
-            ai::ai_spell_cast(actx, order_guard)
+            let result = ai::analyze_order_guard(actx, order_guard);
+            Some(([result.ai_spell_cast, result.ai_return_to_guard], []))
         })
     }
 
@@ -3872,6 +5109,66 @@ impl<'e, E: ExecutionState<'e>> AnalysisCache<'e, E> {
         )
     }
 
+    fn clear_build_queue(&mut self, actx: &AnalysisCtx<'e, E>) -> Option<E::VirtualAddress> {
+        self.cache_many_addr(
+            AddressAnalysis::ClearBuildQueue,
+            |s| s.cache_set_unit_player_fns(actx),
+        )
+    }
+
+    fn unit_build_queue_offset(&mut self, actx: &AnalysisCtx<'e, E>) -> Option<u16> {
+        if self.unit_build_queue_offset == u16::MAX {
+            self.unit_build_queue_offset = 0xfffe;
+            let clear_build_queue = self.clear_build_queue(actx)?;
+            if let Some(result) = units::unit_build_queue_offset(actx, clear_build_queue) {
+                self.unit_build_queue_offset = result;
+            }
+        }
+        Some(self.unit_build_queue_offset).filter(|&x| x < 0xfffe)
+    }
+
+    fn enqueue_build(&mut self, actx: &AnalysisCtx<'e, E>) -> Option<E::VirtualAddress> {
+        self.cache_single_address(AddressAnalysis::EnqueueBuild, |s| {
+            let process_commands = s.process_commands(actx)?;
+            let switch = s.process_commands_switch(actx)?;
+            let build_queue_offset = s.unit_build_queue_offset(actx)?;
+            commands::enqueue_build(actx, process_commands, &switch, build_queue_offset)
+        })
+    }
+
+    fn queue_tech(&mut self, actx: &AnalysisCtx<'e, E>) -> Option<E::VirtualAddress> {
+        self.cache_single_address(AddressAnalysis::QueueTech, |s| {
+            let process_commands = s.process_commands(actx)?;
+            let switch = s.process_commands_switch(actx)?;
+            commands::queue_research(actx, process_commands, &switch, 0x30)
+        })
+    }
+
+    fn queue_upgrade(&mut self, actx: &AnalysisCtx<'e, E>) -> Option<E::VirtualAddress> {
+        self.cache_single_address(AddressAnalysis::QueueUpgrade, |s| {
+            let process_commands = s.process_commands(actx)?;
+            let switch = s.process_commands_switch(actx)?;
+            commands::queue_research(actx, process_commands, &switch, 0x32)
+        })
+    }
+
+    fn apply_stim(&mut self, actx: &AnalysisCtx<'e, E>) -> Option<E::VirtualAddress> {
+        self.cache_single_address(AddressAnalysis::ApplyStim, |s| {
+            let process_commands = s.process_commands(actx)?;
+            let switch = s.process_commands_switch(actx)?;
+            commands::apply_stim(actx, process_commands, &switch)
+        })
+    }
+
+    fn can_afford(&mut self, actx: &AnalysisCtx<'e, E>) -> Option<E::VirtualAddress> {
+        self.cache_single_address(AddressAnalysis::CanAfford, |s| {
+            let process_commands = s.process_commands(actx)?;
+            let switch = s.process_commands_switch(actx)?;
+            let game = s.game(actx)?;
+            commands::can_afford(actx, process_commands, &switch, game)
+        })
+    }
+
     fn cache_unit_speed(&mut self, actx: &AnalysisCtx<'e, E>) {
         use AddressAnalysis::*;
         self.cache_many(&[
@@ -3900,6 +5197,34 @@ impl<'e, E: ExecutionState<'e>> AnalysisCache<'e, E> {
         })
     }
 
+    fn flingy_speed_offsets(
+        &mut self,
+        actx: &AnalysisCtx<'e, E>,
+    ) -> Option<units::FlingySpeedOffsets> {
+        if let Some(cached) = self.flingy_speed_offsets.cached() {
+            return cached;
+        }
+        use AddressAnalysis::*;
+        let result = Some(()).and_then(|()| {
+            let mut speed_func = |addr| self.cache_many_addr(addr, |s| s.cache_unit_speed(actx));
+            // update_speed may have been inlined to apply_speed_upgrades
+            let update_speed = speed_func(UnitUpdateSpeed)
+                .or_else(|| speed_func(UnitApplySpeedUpgrades))?;
+            let buffed_flingy_speed = speed_func(UnitBuffedFlingySpeed)?;
+            let buffed_acceleration = speed_func(UnitBuffedAcceleration)?;
+            let buffed_turn_speed = speed_func(UnitBuffedTurnSpeed)?;
+            units::flingy_speed_offsets(
+                actx,
+                update_speed,
+                buffed_flingy_speed,
+                buffed_acceleration,
+                buffed_turn_speed,
+            )
+        });
+        self.flingy_speed_offsets.cache(&result);
+        result
+    }
+
     fn start_udp_server(&mut self, actx: &AnalysisCtx<'e, E>) -> Option<E::VirtualAddress> {
         self.cache_single_address(AddressAnalysis::StartUdpServer, |s| {
             network::start_udp_server(actx, &s.function_finder())
@@ -4053,6 +5378,61 @@ impl<'e, E: ExecutionState<'e>> AnalysisCache<'e, E> {
         })
     }
 
+    fn cache_unit_step_energy(&mut self, actx: &AnalysisCtx<'e, E>) {
+        use AddressAnalysis::*;
+        self.cache_many(&[UnitStepEnergy], &[], |s| {
+            let step_active_unit = s.step_active_unit_frame(actx)?;
+            let result = units::analyze_unit_step_energy(actx, step_active_unit);
+            s.unit_energy_offset = result.energy_offset;
+            Some(([result.step_energy], []))
+        })
+    }
+
+    fn cache_step_construction(&mut self, actx: &AnalysisCtx<'e, E>) {
+        use AddressAnalysis::*;
+        self.cache_many(&[StepConstruction], &[], |s| {
+            let step_active_unit = s.step_active_unit_frame(actx)?;
+            let result = units::analyze_step_construction(actx, step_active_unit);
+            s.unit_remaining_build_time_offset = result.remaining_build_time_offset;
+            Some(([result.step_construction], []))
+        })
+    }
+
+    fn update_cloak_state(&mut self, actx: &AnalysisCtx<'e, E>) -> Option<E::VirtualAddress> {
+        self.cache_many_addr(AddressAnalysis::UpdateCloakState, |s| s.cache_step_objects(actx))
+    }
+
+    fn unit_step_energy(&mut self, actx: &AnalysisCtx<'e, E>) -> Option<E::VirtualAddress> {
+        self.cache_many_addr(AddressAnalysis::UnitStepEnergy, |s| s.cache_unit_step_energy(actx))
+    }
+
+    fn cloak_energy_drain(&mut self, actx: &AnalysisCtx<'e, E>) -> Option<E::VirtualAddress> {
+        self.cache_single_address(AddressAnalysis::CloakEnergyDrain, |s| {
+            let update_cloak_state = s.update_cloak_state(actx)?;
+            s.unit_step_energy(actx)?;
+            let energy_offset = s.unit_energy_offset;
+            units::cloak_energy_drain(actx, update_cloak_state, energy_offset)
+        })
+    }
+
+    fn spawn_larva(&mut self, actx: &AnalysisCtx<'e, E>) -> Option<E::VirtualAddress> {
+        self.cache_single_address(AddressAnalysis::SpawnLarva, |s| {
+            let step_active_unit = s.step_active_unit_frame(actx)?;
+            let create_unit = s.create_unit(actx)?;
+            units::spawn_larva(actx, step_active_unit, create_unit)
+        })
+    }
+
+    fn cache_step_unit_status_effects(&mut self, actx: &AnalysisCtx<'e, E>) {
+        use AddressAnalysis::*;
+        self.cache_many(&[StepUnitStatusEffects], &[], |s| {
+            let step_active_unit = s.step_active_unit_frame(actx)?;
+            let result = units::analyze_step_unit_status_effects(actx, step_active_unit);
+            s.unit_status_timers = result.timers;
+            Some(([result.step_unit_status_effects], []))
+        })
+    }
+
     fn step_unit_movement(&mut self, actx: &AnalysisCtx<'e, E>) -> Option<E::VirtualAddress> {
         self.cache_many_addr(AddressAnalysis::StepUnitMovement, |s| s.cache_step_active_unit(actx))
     }
@@ -4103,10 +5483,48 @@ impl<'e, E: ExecutionState<'e>> AnalysisCache<'e, E> {
         })
     }
 
+    fn step_game_loop(&mut self, actx: &AnalysisCtx<'e, E>) -> Option<E::VirtualAddress> {
+        self.cache_many_addr(AddressAnalysis::StepGameLoop, |s| s.cache_game_loop(actx))
+    }
+
+    fn sync_data(&mut self, actx: &AnalysisCtx<'e, E>) -> Option<Operand<'e>> {
+        self.cache_many_op(OperandAnalysis::SyncData, |s| s.cache_game_loop(actx))
+    }
+
+    fn compute_sync_checksum(&mut self, actx: &AnalysisCtx<'e, E>) -> Option<E::VirtualAddress> {
+        self.cache_single_address(AddressAnalysis::ComputeSyncChecksum, |s| {
+            let step_game_loop = s.step_game_loop(actx)?;
+            let sync_data = s.sync_data(actx)?;
+            game_init::compute_sync_checksum(actx, step_game_loop, sync_data)
+        })
+    }
+
+    fn step_game_logic(&mut self, actx: &AnalysisCtx<'e, E>) -> Option<E::VirtualAddress> {
+        self.cache_many_addr(AddressAnalysis::StepGameLogic, |s| s.cache_game_loop(actx))
+    }
+
     fn step_network(&mut self, actx: &AnalysisCtx<'e, E>) -> Option<E::VirtualAddress> {
         self.cache_many_addr(AddressAnalysis::StepNetwork, |s| s.cache_game_loop(actx))
     }
 
+    fn load_pcx(&mut self, actx: &AnalysisCtx<'e, E>) -> Option<E::VirtualAddress> {
+        self.cache_many_addr(AddressAnalysis::LoadPcx, |s| s.cache_game_loop(actx))
+    }
+
+    fn cache_blit_pcx_to_surface(&mut self, actx: &AnalysisCtx<'e, E>) {
+        use AddressAnalysis::*;
+        use OperandAnalysis::*;
+        self.cache_many(&[BlitPcxToSurface], &[PcxTargetSurface], |s| {
+            let load_pcx = s.load_pcx(actx)?;
+            let result = game_init::blit_pcx_to_surface(actx, load_pcx, &s.function_finder());
+            Some(([result.blit_pcx_to_surface], [result.pcx_target_surface]))
+        })
+    }
+
+    fn net_player_flags(&mut self, actx: &AnalysisCtx<'e, E>) -> Option<Operand<'e>> {
+        self.cache_many_op(OperandAnalysis::NetPlayerFlags, |s| s.cache_step_network(actx))
+    }
+
     fn process_events(&mut self, actx: &AnalysisCtx<'e, E>) -> Option<E::VirtualAddress> {
         self.cache_many_addr(AddressAnalysis::ProcessEvents, |s| s.cache_game_loop(actx))
     }
@@ -4189,6 +5607,18 @@ impl<'e, E: ExecutionState<'e>> AnalysisCache<'e, E> {
         self.cache_many_addr(AddressAnalysis::SinglePlayerMapEnd, |s| s.cache_sp_map_end(actx))
     }
 
+    fn local_game_result(&mut self, actx: &AnalysisCtx<'e, E>) -> Option<Operand<'e>> {
+        self.cache_many_op(OperandAnalysis::LocalGameResult, |s| s.cache_sp_map_end(actx))
+    }
+
+    fn check_game_over(&mut self, actx: &AnalysisCtx<'e, E>) -> Option<E::VirtualAddress> {
+        self.cache_single_address(AddressAnalysis::CheckGameOver, |s| {
+            let step_game_logic = s.step_game_logic(actx)?;
+            let local_game_result = s.local_game_result(actx)?;
+            game_init::check_game_over(actx, step_game_logic, local_game_result)
+        })
+    }
+
     fn cache_sp_map_end_analysis(&mut self, actx: &AnalysisCtx<'e, E>) {
         use AddressAnalysis::*;
         use OperandAnalysis::*;
@@ -4268,6 +5698,24 @@ impl<'e, E: ExecutionState<'e>> AnalysisCache<'e, E> {
         })
     }
 
+    fn open_map_mpq(&mut self, actx: &AnalysisCtx<'e, E>) -> Option<E::VirtualAddress> {
+        self.cache_many_addr(AddressAnalysis::OpenMapMpq, |s| s.cache_init_map_from_path(actx))
+    }
+
+    fn mount_archive(&mut self, actx: &AnalysisCtx<'e, E>) -> Option<E::VirtualAddress> {
+        self.cache_single_address(AddressAnalysis::MountArchive, |s| {
+            let open_map_mpq = s.open_map_mpq(actx)?;
+            game_init::mount_archive(actx, open_map_mpq)
+        })
+    }
+
+    fn archive_chain_head(&mut self, actx: &AnalysisCtx<'e, E>) -> Option<Operand<'e>> {
+        self.cache_single_operand(OperandAnalysis::ArchiveChainHead, |s| {
+            let mount_archive = s.mount_archive(actx)?;
+            storm::archive_chain_head(actx, mount_archive)
+        })
+    }
+
     fn read_whole_mpq_file(&mut self, actx: &AnalysisCtx<'e, E>) -> Option<E::VirtualAddress> {
         self.cache_many_addr(
             AddressAnalysis::ReadWholeMpqFile2,
@@ -4298,6 +5746,15 @@ impl<'e, E: ExecutionState<'e>> AnalysisCache<'e, E> {
             });
     }
 
+    fn update_button_states(&mut self, actx: &AnalysisCtx<'e, E>) -> Option<E::VirtualAddress> {
+        self.cache_single_address(AddressAnalysis::UpdateButtonStates, |s| {
+            let firegraft = s.firegraft_addresses(actx);
+            let buttonsets = *firegraft.buttonsets.get(0)?;
+            let functions = s.function_finder();
+            clientside::update_button_states(actx, buttonsets, &functions)
+        })
+    }
+
     fn cache_targeting_lclick(&mut self, actx: &AnalysisCtx<'e, E>) {
         use AddressAnalysis::*;
         self.cache_many(
@@ -4314,6 +5771,20 @@ impl<'e, E: ExecutionState<'e>> AnalysisCache<'e, E> {
             });
     }
 
+    fn find_unit_for_click(&mut self, actx: &AnalysisCtx<'e, E>) -> Option<E::VirtualAddress> {
+        self.cache_many_addr(
+            AddressAnalysis::FindUnitForClick,
+            |s| s.cache_targeting_lclick(actx),
+        )
+    }
+
+    fn unit_under_cursor(&mut self, actx: &AnalysisCtx<'e, E>) -> Option<Operand<'e>> {
+        self.cache_single_operand(OperandAnalysis::UnitUnderCursor, |s| {
+            let find_unit_for_click = s.find_unit_for_click(actx)?;
+            clientside::unit_under_cursor(actx, find_unit_for_click, &s.function_finder())
+        })
+    }
+
     fn handle_targeted_click(&mut self, actx: &AnalysisCtx<'e, E>) -> Option<E::VirtualAddress> {
         self.cache_many_addr(
             AddressAnalysis::HandleTargetedClick,
@@ -4389,6 +5860,74 @@ impl<'e, E: ExecutionState<'e>> AnalysisCache<'e, E> {
         self.cache_many_addr(AddressAnalysis::AddAiToTrainedUnit, |s| s.cache_order_train(actx))
     }
 
+    fn cache_order_spell(&mut self, actx: &AnalysisCtx<'e, E>) {
+        use AddressAnalysis::*;
+        self.cache_many(
+            &[CastSpell],
+            &[],
+            |s| {
+                // Psionic storm, uses the generic spell order
+                let spell = s.order_function(0x8e, actx)?;
+                let result = step_order::analyze_order_spell(actx, spell);
+                Some((
+                    [result.cast_spell],
+                    [],
+                ))
+            });
+    }
+
+    fn cache_order_hallucination(&mut self, actx: &AnalysisCtx<'e, E>) {
+        use AddressAnalysis::*;
+        self.cache_many(
+            &[CreateHallucination],
+            &[],
+            |s| {
+                let hallucination = s.order_function(0x94, actx)?;
+                let create_unit = s.create_unit(actx)?;
+                let result =
+                    step_order::analyze_order_hallucination(actx, hallucination, create_unit);
+                Some((
+                    [result.create_hallucination],
+                    [],
+                ))
+            });
+    }
+
+    fn cache_burrow_orders(&mut self, actx: &AnalysisCtx<'e, E>) {
+        use AddressAnalysis::*;
+        self.cache_many(&[BurrowUnit, UnburrowUnit], &[], |s| {
+            let burrow = s.order_function(0x74, actx);
+            let unburrow = s.order_function(0x76, actx);
+            Some(([burrow, unburrow], []))
+        })
+    }
+
+    fn cache_order_return_cargo(&mut self, actx: &AnalysisCtx<'e, E>) {
+        use AddressAnalysis::*;
+        self.cache_many(
+            &[ReturnCargo],
+            &[],
+            |s| {
+                let return_minerals = s.order_function(0x5a, actx)?;
+                let game = s.game(actx)?;
+                let result = step_order::analyze_order_return_cargo(actx, return_minerals, game);
+                Some((
+                    [result.return_cargo],
+                    [],
+                ))
+            });
+    }
+
+    fn cache_order_place_addon(&mut self, actx: &AnalysisCtx<'e, E>) {
+        use AddressAnalysis::*;
+        self.cache_many(&[AttachAddon], &[], |s| {
+            let place_addon = s.order_function(0x24, actx)?;
+            let result = step_order::analyze_order_place_addon(actx, place_addon);
+            s.unit_addon_offset = result.addon_offset;
+            Some(([result.attach_addon], []))
+        })
+    }
+
     fn cache_order_matrix(&mut self, actx: &AnalysisCtx<'e, E>) {
         use AddressAnalysis::*;
         self.cache_many(
@@ -4632,6 +6171,13 @@ impl<'e, E: ExecutionState<'e>> AnalysisCache<'e, E> {
             })
     }
 
+    fn iscript_death_call(&mut self, actx: &AnalysisCtx<'e, E>) -> Option<E::VirtualAddress> {
+        self.cache_single_address(AddressAnalysis::IscriptDeathCall, |s| {
+            let order_die = s.order_function(0x0, actx)?;
+            step_order::iscript_death_call(actx, order_die)
+        })
+    }
+
     fn kill_unit(&mut self, actx: &AnalysisCtx<'e, E>) -> Option<E::VirtualAddress> {
         self.cache_many_addr(AddressAnalysis::KillUnit, |s| s.cache_do_missile_damage(actx))
     }
@@ -4760,6 +6306,18 @@ impl<'e, E: ExecutionState<'e>> AnalysisCache<'e, E> {
         self.cache_many_addr(AddressAnalysis::SelectMouseUp, |s| s.cache_game_screen_lclick(actx))
     }
 
+    fn select_start_x(&mut self, actx: &AnalysisCtx<'e, E>) -> Option<Operand<'e>> {
+        self.cache_many_op(OperandAnalysis::SelectStartX, |s| s.cache_game_screen_lclick(actx))
+    }
+
+    fn finalize_drag_select(&mut self, actx: &AnalysisCtx<'e, E>) -> Option<E::VirtualAddress> {
+        self.cache_single_address(AddressAnalysis::FinalizeDragSelect, |s| {
+            let select_mouse_up = s.select_mouse_up(actx)?;
+            let select_start_x = s.select_start_x(actx)?;
+            clientside::finalize_drag_select(actx, select_mouse_up, select_start_x)
+        })
+    }
+
     fn cache_select_mouse_up(&mut self, actx: &AnalysisCtx<'e, E>) {
         use AddressAnalysis::*;
         self.cache_many(
@@ -4776,6 +6334,10 @@ impl<'e, E: ExecutionState<'e>> AnalysisCache<'e, E> {
             })
     }
 
+    fn select_units(&mut self, actx: &AnalysisCtx<'e, E>) -> Option<E::VirtualAddress> {
+        self.cache_many_addr(AddressAnalysis::SelectUnits, |s| s.cache_select_mouse_up(actx))
+    }
+
     fn cache_run_dialog_children(&mut self, actx: &AnalysisCtx<'e, E>) {
         use AddressAnalysis::*;
         use OperandAnalysis::*;
@@ -4799,6 +6361,21 @@ impl<'e, E: ExecutionState<'e>> AnalysisCache<'e, E> {
             })
     }
 
+    fn sound_channels(&mut self, actx: &AnalysisCtx<'e, E>) -> Option<Operand<'e>> {
+        self.cache_many_op(OperandAnalysis::SoundChannels, |s| s.cache_play_sound(actx))
+    }
+
+    fn cache_sound_volume(&mut self, actx: &AnalysisCtx<'e, E>) {
+        use AddressAnalysis::*;
+        use OperandAnalysis::*;
+        self.cache_many(&[SetSoundVolume], &[SoundBankHandle], |s| {
+            let play = s.play_sound(actx)?;
+            let sound_channels = s.sound_channels(actx)?;
+            let r = sound::analyze_sound_volume(actx, play, sound_channels);
+            Some(([r.set_sound_volume], [r.sound_bank_handle]))
+        })
+    }
+
     fn cache_play_sound(&mut self, actx: &AnalysisCtx<'e, E>) {
         use OperandAnalysis::*;
         self.cache_many(
@@ -4868,6 +6445,16 @@ impl<'e, E: ExecutionState<'e>> AnalysisCache<'e, E> {
             })
     }
 
+    fn cache_unit_rank(&mut self, actx: &AnalysisCtx<'e, E>) {
+        use AddressAnalysis::*;
+        self.cache_many(&[UpdateUnitRank], &[], |s| {
+            let kill_unit = s.kill_unit(actx)?;
+            let result = units::analyze_unit_rank(actx, kill_unit);
+            s.unit_rank_offset = result.rank_offset;
+            Some(([result.update_unit_rank], []))
+        })
+    }
+
     fn ai_remove_unit(&mut self, actx: &AnalysisCtx<'e, E>) -> Option<E::VirtualAddress> {
         self.cache_many_addr(AddressAnalysis::AiRemoveUnit, |s| s.cache_kill_unit(actx))
     }
@@ -5010,7 +6597,7 @@ impl<'e, E: ExecutionState<'e>> AnalysisCache<'e, E> {
         use AddressAnalysis::*;
         self.cache_many(
             &[AiRegionUpdateStrength, AiRegionUpdateTarget, AiRegionAbandonIfOverwhelmed,
-                AiRegionPickAttackTarget],
+                AiRegionPickAttackTarget, AiManageAttack],
             &[],
             |s| {
                 let ai_step_region = s.ai_step_region(actx)?;
@@ -5021,7 +6608,8 @@ impl<'e, E: ExecutionState<'e>> AnalysisCache<'e, E> {
                     assert_eq!(r.change_ai_region_state, change_ai_region_state);
                 }
                 Some(([r.ai_region_update_strength, r.ai_region_update_target,
-                    r.ai_region_abandon_if_overwhelmed, r.ai_region_pick_attack_target], []))
+                    r.ai_region_abandon_if_overwhelmed, r.ai_region_pick_attack_target,
+                    r.ai_manage_attack], []))
             },
         )
     }
@@ -5155,6 +6743,17 @@ impl<'e, E: ExecutionState<'e>> AnalysisCache<'e, E> {
         })
     }
 
+    fn cache_rally_command(&mut self, actx: &AnalysisCtx<'e, E>) {
+        use AddressAnalysis::*;
+        self.cache_many(&[SetRallyPoint], &[], |s| {
+            let process_commands = s.process_commands(actx)?;
+            let switch = s.process_commands_switch(actx)?;
+            let result = commands::rally(actx, process_commands, &switch);
+            s.unit_rally_offset = result.unit_rally_offset;
+            Some(([result.set_rally_point], []))
+        })
+    }
+
     fn cache_ai_order(&mut self, actx: &AnalysisCtx<'e, E>) {
         use AddressAnalysis::*;
         self.cache_many(
@@ -5187,6 +6786,12 @@ impl<'e, E: ExecutionState<'e>> AnalysisCache<'e, E> {
         self.cache_many_addr(AddressAnalysis::MakePath, |s| s.cache_step_unit_movement(actx))
     }
 
+    fn update_unit_facing(&mut self, actx: &AnalysisCtx<'e, E>) -> Option<E::VirtualAddress> {
+        self.cache_single_address(AddressAnalysis::UpdateUnitFacing, |s| {
+            pathing::update_unit_facing(actx, s.step_unit_movement(actx)?)
+        })
+    }
+
     fn cache_make_path(&mut self, actx: &AnalysisCtx<'e, E>) {
         use AddressAnalysis::*;
         self.cache_many(&[CalculatePath], &[], |s| {
@@ -5,13 +5,14 @@ use scarf::analysis::{self, Control, FuncAnalysis};
 use scarf::exec_state::{ExecutionState, VirtualAddress};
 
 use crate::analysis::{AnalysisCtx, ArgCache};
+use crate::analysis_find::FunctionFinder;
 use crate::analysis_state::{AnalysisState, StateEnum, FindCreateBulletState};
 use crate::call_tracker::CallTracker;
 use crate::linked_list::DetectListAdd;
 use crate::switch::{self, CompleteSwitch};
 use crate::util::{
     bumpvec_with_capacity, ControlExt, MemAccessExt, OptionExt, OperandExt, is_global,
-    seems_assertion_call, ExecStateExt,
+    is_global_struct, seems_assertion_call, ExecStateExt,
 };
 
 pub(crate) struct BulletCreation<'e, Va: VirtualAddress> {
@@ -49,6 +50,12 @@ pub(crate) struct StepMovingBulletFrame<'e, Va: VirtualAddress> {
     pub step_flingy_turning: Option<Va>,
 }
 
+pub(crate) struct TerrainHeight<'e, Va: VirtualAddress> {
+    pub get_terrain_height: Option<Va>,
+    pub compute_miss_chance: Option<Va>,
+    pub elevation_data: Option<Operand<'e>>,
+}
+
 pub(crate) struct DoMissileDamage<Va: VirtualAddress> {
     pub hit_unit: Option<Va>,
     pub unit_was_hit: Option<Va>,
@@ -1679,3 +1686,221 @@ impl<'a, 'e, E: ExecutionState<'e>> scarf::Analyzer<'e> for SplashLurkerAnalyzer
         }
     }
 }
+
+/// Finds the bullet array and size of a bullet from the function initializing
+/// bullet free list:
+///     first_free_bullet = &bullets[0]
+///     bullets[0].next = &bullets[1]
+pub(crate) fn bullet_array<'e, E: ExecutionState<'e>>(
+    actx: &AnalysisCtx<'e, E>,
+    first_free_bullet: Operand<'e>,
+    functions: &FunctionFinder<'_, 'e, E>,
+) -> Option<(Operand<'e>, u32)> {
+    let binary = actx.binary;
+    let ctx = actx.ctx;
+    let bump = &actx.bump;
+    let first_free_mem = first_free_bullet.if_memory()?;
+    let first_free_addr = first_free_mem.if_constant_address()?;
+    let mut global_refs =
+        functions.find_functions_using_global(actx, E::VirtualAddress::from_u64(first_free_addr));
+    global_refs.sort_unstable_by_key(|x| x.func_entry);
+    global_refs.dedup_by_key(|x| x.func_entry);
+    for global in &global_refs {
+        let mut analyzer = FindBulletArray::<E> {
+            result: None,
+            first_free_bullet: *first_free_mem,
+            array_candidates: bumpvec_with_capacity(4, bump),
+        };
+        let mut analysis = FuncAnalysis::new(binary, ctx, global.func_entry);
+        analysis.analyze(&mut analyzer);
+        if analyzer.result.is_some() {
+            return analyzer.result;
+        }
+    }
+    None
+}
+
+struct FindBulletArray<'acx, 'e, E: ExecutionState<'e>> {
+    result: Option<(Operand<'e>, u32)>,
+    first_free_bullet: MemAccess<'e>,
+    array_candidates: BumpVec<'acx, Operand<'e>>,
+}
+
+impl<'acx, 'e: 'acx, E: ExecutionState<'e>> analysis::Analyzer<'e> for
+    FindBulletArray<'acx, 'e, E>
+{
+    type State = analysis::DefaultState;
+    type Exec = E;
+    fn operation(&mut self, ctrl: &mut Control<'e, '_, '_, Self>, op: &Operation<'e>) {
+        if let Operation::Move(DestOperand::Memory(ref mem), value) = *op {
+            if mem.size != E::WORD_SIZE {
+                return;
+            }
+            let ctx = ctrl.ctx();
+            let dest = ctrl.resolve_mem(mem);
+            let value = ctrl.resolve(value);
+            if dest == self.first_free_bullet {
+                if is_global(value) {
+                    self.array_candidates.push(value);
+                }
+                return;
+            }
+            let dest_addr = dest.address_op(ctx);
+            for &array in self.array_candidates.iter() {
+                if dest_addr != ctx.add_const(array, E::VirtualAddress::SIZE as u64) {
+                    continue;
+                }
+                let size = ctx.sub(value, array).if_constant()
+                    .filter(|&c| c > 0x40 && c < 0x200);
+                if let Some(size) = size {
+                    self.result = Some((array, size as u32));
+                    ctrl.end_analysis();
+                    return;
+                }
+            }
+        }
+    }
+}
+
+pub(crate) fn terrain_height<'e, E: ExecutionState<'e>>(
+    actx: &AnalysisCtx<'e, E>,
+    create_bullet: E::VirtualAddress,
+) -> TerrainHeight<'e, E::VirtualAddress> {
+    let binary = actx.binary;
+    let ctx = actx.ctx;
+    let bump = &actx.bump;
+    let mut result = TerrainHeight {
+        get_terrain_height: None,
+        compute_miss_chance: None,
+        elevation_data: None,
+    };
+
+    // Bullet initialization decides if the bullet misses by comparing
+    // get_terrain_height(target) > get_terrain_height(source),
+    // so find a jump comparing results of two calls to the same function.
+    // The function containing that jump is compute_miss_chance, unless it
+    // was inlined to create_bullet.
+    let mut analysis = FuncAnalysis::new(binary, ctx, create_bullet);
+    let mut analyzer = FindGetTerrainHeight::<E> {
+        result: &mut result,
+        inline_depth: 0,
+        func_entry: create_bullet,
+        call_dests: bumpvec_with_capacity(0x40, bump),
+        checked_functions: bumpvec_with_capacity(0x20, bump),
+    };
+    analysis.analyze(&mut analyzer);
+
+    // get_terrain_height checks `elevation_data[index] & flags`
+    if let Some(get_terrain_height) = result.get_terrain_height {
+        let mut analysis = FuncAnalysis::new(binary, ctx, get_terrain_height);
+        let mut analyzer = FindElevationData::<E> {
+            result: &mut result,
+            phantom: Default::default(),
+        };
+        analysis.analyze(&mut analyzer);
+    }
+    result
+}
+
+struct FindGetTerrainHeight<'a, 'acx, 'e, E: ExecutionState<'e>> {
+    result: &'a mut TerrainHeight<'e, E::VirtualAddress>,
+    inline_depth: u8,
+    func_entry: E::VirtualAddress,
+    /// Index is the Custom id of call result.
+    call_dests: BumpVec<'acx, E::VirtualAddress>,
+    checked_functions: BumpVec<'acx, E::VirtualAddress>,
+}
+
+impl<'a, 'acx, 'e: 'acx, E: ExecutionState<'e>> analysis::Analyzer<'e> for
+    FindGetTerrainHeight<'a, 'acx, 'e, E>
+{
+    type State = analysis::DefaultState;
+    type Exec = E;
+    fn operation(&mut self, ctrl: &mut Control<'e, '_, '_, Self>, op: &Operation<'e>) {
+        let ctx = ctrl.ctx();
+        match *op {
+            Operation::Call(dest) => {
+                if let Some(dest) = ctrl.resolve_va(dest) {
+                    if self.inline_depth < 2 &&
+                        !self.checked_functions.iter().any(|&x| x == dest)
+                    {
+                        self.checked_functions.push(dest);
+                        let old_entry = self.func_entry;
+                        self.func_entry = dest;
+                        self.inline_depth += 1;
+                        ctrl.analyze_with_current_state(self, dest);
+                        self.inline_depth -= 1;
+                        self.func_entry = old_entry;
+                        if self.result.get_terrain_height.is_some() {
+                            ctrl.end_analysis();
+                            return;
+                        }
+                    }
+                    let custom = ctx.custom(self.call_dests.len() as u32);
+                    self.call_dests.push(dest);
+                    ctrl.do_call_with_result(custom);
+                }
+            }
+            Operation::Jump { condition, .. } => {
+                let condition = ctrl.resolve(condition);
+                let mut first = None;
+                for id in condition.iter_no_mem_addr().filter_map(|x| x.if_custom()) {
+                    let dest = match self.call_dests.get(id as usize) {
+                        Some(&s) => s,
+                        None => continue,
+                    };
+                    match first {
+                        None => first = Some((id, dest)),
+                        Some((first_id, first_dest)) => {
+                            if first_id != id && first_dest == dest {
+                                self.result.get_terrain_height = Some(dest);
+                                if self.inline_depth != 0 {
+                                    self.result.compute_miss_chance = Some(self.func_entry);
+                                }
+                                ctrl.end_analysis();
+                                return;
+                            }
+                        }
+                    }
+                }
+            }
+            _ => (),
+        }
+    }
+}
+
+struct FindElevationData<'a, 'e, E: ExecutionState<'e>> {
+    result: &'a mut TerrainHeight<'e, E::VirtualAddress>,
+    phantom: std::marker::PhantomData<(*const E, &'e ())>,
+}
+
+impl<'a, 'e, E: ExecutionState<'e>> analysis::Analyzer<'e> for FindElevationData<'a, 'e, E> {
+    type State = analysis::DefaultState;
+    type Exec = E;
+    fn operation(&mut self, ctrl: &mut Control<'e, '_, '_, Self>, op: &Operation<'e>) {
+        if let Operation::Jump { condition, .. } = *op {
+            let ctx = ctrl.ctx();
+            let condition = ctrl.resolve(condition);
+            let result = condition.iter_no_mem_addr()
+                .filter_map(|x| x.if_arithmetic_and())
+                .filter(|x| x.1.if_constant().is_some())
+                .filter_map(|x| x.0.if_memory())
+                .find_map(|mem| {
+                    let (base, offset) = mem.address();
+                    if offset > 0x1000 {
+                        // Static array
+                        Some(ctx.constant(offset))
+                    } else {
+                        // Pointer to array
+                        base.if_arithmetic_add()
+                            .and_either(|x| Some(x).filter(|&x| is_global_struct::<E>(x)))
+                            .map(|x| x.0)
+                    }
+                });
+            if let Some(result) = result {
+                self.result.elevation_data = Some(result);
+                ctrl.end_analysis();
+            }
+        }
+    }
+}
@@ -27,11 +27,11 @@ pub enum ResultOrEntries<'acx, T, Va: VirtualAddress> {
     Entries(BumpVec<'acx, Va>),
 }
 
-#[derive(Default)]
-pub struct GameCoordConversion<'e> {
+pub struct GameCoordConversion<'e, Va: VirtualAddress> {
     pub screen_x: Option<Operand<'e>>,
     pub screen_y: Option<Operand<'e>>,
     pub scale: Option<Operand<'e>>,
+    pub screen_to_game_coords: Option<Va>,
 }
 
 #[derive(Clone, Debug)]
@@ -378,8 +378,13 @@ pub(crate) fn game_coord_conversion<'a, E: ExecutionState<'a>>(
     analysis: &AnalysisCtx<'a, E>,
     game_screen_rclick: E::VirtualAddress,
     is_outside_game_screen: E::VirtualAddress,
-) -> GameCoordConversion<'a> {
-    let mut result = GameCoordConversion::default();
+) -> GameCoordConversion<'a, E::VirtualAddress> {
+    let mut result = GameCoordConversion {
+        screen_x: None,
+        screen_y: None,
+        scale: None,
+        screen_to_game_coords: None,
+    };
 
     // Search for the collowing start in game_screen_rclick:
     // if is_outside_game_screen(event.x, event.y) == 0 {
@@ -391,7 +396,7 @@ pub(crate) fn game_coord_conversion<'a, E: ExecutionState<'a>>(
 
     struct Analyzer<'a, 'b, E: ExecutionState<'a>> {
         depth: u32,
-        result: &'b mut GameCoordConversion<'a>,
+        result: &'b mut GameCoordConversion<'a, E::VirtualAddress>,
         set_eax_to_zero: bool,
         is_outside_game_screen: E::VirtualAddress,
         is_outside_game_screen_seen: bool,
@@ -450,6 +455,9 @@ pub(crate) fn game_coord_conversion<'a, E: ExecutionState<'a>>(
                                 ctrl.inline(self, dest);
                                 ctrl.skip_operation();
                                 self.depth -= 1;
+                                if self.result.screen_x.is_some() {
+                                    self.result.screen_to_game_coords = Some(dest);
+                                }
                             }
                         } else if self.depth == 3 {
                             ctrl.end_analysis();
@@ -504,6 +512,166 @@ pub(crate) fn game_coord_conversion<'a, E: ExecutionState<'a>>(
     result
 }
 
+pub(crate) fn unit_under_cursor<'e, E: ExecutionState<'e>>(
+    actx: &AnalysisCtx<'e, E>,
+    find_unit_for_click: E::VirtualAddress,
+    functions: &FunctionFinder<'_, 'e, E>,
+) -> Option<Operand<'e>> {
+    let binary = actx.binary;
+    let ctx = actx.ctx;
+    // Game screen mouse move handler does
+    //      unit_under_cursor = find_unit_for_click(x, y)
+    // Other callers (click handlers) keep the result in a local, so take the
+    // caller that stores the returned unit to a global.
+    let callers = functions.find_callers(actx, find_unit_for_click);
+    let funcs = functions.functions();
+    let mut result = None;
+    for caller in callers {
+        let new = entry_of_until(binary, &funcs, caller, |entry| {
+            let mut analyzer = FindUnitUnderCursor::<E> {
+                result: EntryOf::Retry,
+                find_unit_for_click,
+                caller,
+                call_seen: false,
+            };
+            let mut analysis = FuncAnalysis::new(binary, ctx, entry);
+            analysis.analyze(&mut analyzer);
+            match analyzer.result {
+                EntryOf::Retry if analyzer.call_seen => EntryOf::Stop,
+                x => x,
+            }
+        }).into_option();
+        if single_result_assign(new, &mut result) {
+            break;
+        }
+    }
+    result
+}
+
+struct FindUnitUnderCursor<'e, E: ExecutionState<'e>> {
+    result: EntryOf<Operand<'e>>,
+    find_unit_for_click: E::VirtualAddress,
+    caller: E::VirtualAddress,
+    call_seen: bool,
+}
+
+impl<'e, E: ExecutionState<'e>> scarf::Analyzer<'e> for FindUnitUnderCursor<'e, E> {
+    type State = analysis::DefaultState;
+    type Exec = E;
+    fn operation(&mut self, ctrl: &mut Control<'e, '_, '_, Self>, op: &Operation<'e>) {
+        let ctx = ctrl.ctx();
+        match *op {
+            Operation::Call(dest) => {
+                if ctrl.resolve_va(dest) == Some(self.find_unit_for_click) {
+                    if ctrl.address() == self.caller {
+                        self.call_seen = true;
+                    }
+                    ctrl.do_call_with_result(ctx.custom(0));
+                }
+            }
+            Operation::Move(DestOperand::Memory(ref mem), value) => {
+                if mem.size != E::WORD_SIZE {
+                    return;
+                }
+                let value = ctrl.resolve(value);
+                if value.if_custom() != Some(0) {
+                    return;
+                }
+                let mem = ctrl.resolve_mem(mem);
+                if mem.if_constant_address().is_some() {
+                    self.result = EntryOf::Ok(ctx.memory(&mem));
+                    ctrl.end_analysis();
+                }
+            }
+            _ => (),
+        }
+    }
+}
+
+pub(crate) fn game_to_screen_coords<'e, E: ExecutionState<'e>>(
+    actx: &AnalysisCtx<'e, E>,
+    screen_x: Operand<'e>,
+    scale: Operand<'e>,
+    functions: &FunctionFinder<'_, 'e, E>,
+) -> Option<E::VirtualAddress> {
+    let binary = actx.binary;
+    let ctx = actx.ctx;
+    // Inverse of screen_to_game_coords; a function using screen_x that
+    // converts its argument with
+    //      (arg_x - screen_x) * scale
+    // (screen_to_game_coords does screen_x + event_x / scale instead)
+    let screen_x_addr = screen_x.if_memory()?.if_constant_address()?;
+    let global_refs = functions.find_functions_using_global(
+        actx,
+        E::VirtualAddress::from_u64(screen_x_addr),
+    );
+    let funcs = functions.functions();
+    let mut result = None;
+    for global_ref in global_refs {
+        let new = entry_of_until(binary, &funcs, global_ref.use_address, |entry| {
+            let mut analyzer = FindGameToScreenCoords::<E> {
+                result: EntryOf::Retry,
+                use_address: global_ref.use_address,
+                screen_x,
+                scale,
+                arg_cache: &actx.arg_cache,
+            };
+            let mut analysis = FuncAnalysis::new(binary, ctx, entry);
+            analysis.analyze(&mut analyzer);
+            analyzer.result
+        }).into_option_with_entry().map(|x| x.0);
+        if single_result_assign(new, &mut result) {
+            break;
+        }
+    }
+    result
+}
+
+struct FindGameToScreenCoords<'a, 'e, E: ExecutionState<'e>> {
+    result: EntryOf<()>,
+    use_address: E::VirtualAddress,
+    screen_x: Operand<'e>,
+    scale: Operand<'e>,
+    arg_cache: &'a ArgCache<'e, E>,
+}
+
+impl<'a, 'e, E: ExecutionState<'e>> scarf::Analyzer<'e> for FindGameToScreenCoords<'a, 'e, E> {
+    type State = analysis::DefaultState;
+    type Exec = E;
+    fn operation(&mut self, ctrl: &mut Control<'e, '_, '_, Self>, op: &Operation<'e>) {
+        let address = ctrl.address();
+        if address <= self.use_address && ctrl.current_instruction_end() > self.use_address {
+            self.result = EntryOf::Stop;
+        }
+        match *op {
+            Operation::Call(..) => {
+                // Expecting the function to be a leaf function
+                ctrl.end_analysis();
+            }
+            Operation::Move(_, value) => {
+                let value = ctrl.resolve(value);
+                let has_scale = value.iter().any(|x| x == self.scale);
+                if !has_scale {
+                    return;
+                }
+                let has_sub = value.iter().any(|x| {
+                    x.if_arithmetic_sub()
+                        .filter(|&(_, r)| r == self.screen_x)
+                        .filter(|&(l, _)| {
+                            (0..2).any(|i| l.iter().any(|y| y == self.arg_cache.on_entry(i)))
+                        })
+                        .is_some()
+                });
+                if has_sub {
+                    self.result = EntryOf::Ok(());
+                    ctrl.end_analysis();
+                }
+            }
+            _ => (),
+        }
+    }
+}
+
 pub(crate) fn game_screen_rclick<'e, E: ExecutionState<'e>>(
     analysis: &AnalysisCtx<'e, E>,
     units_dat: (E::VirtualAddress, u32),
@@ -990,6 +1158,90 @@ pub(crate) fn start_targeting<'e, E: ExecutionState<'e>>(
     result
 }
 
+pub(crate) fn update_button_states<'e, E: ExecutionState<'e>>(
+    actx: &AnalysisCtx<'e, E>,
+    button_sets: E::VirtualAddress,
+    functions: &FunctionFinder<'_, 'e, E>,
+) -> Option<E::VirtualAddress> {
+    let binary = actx.binary;
+    let ctx = actx.ctx;
+    let bump = &actx.bump;
+    // update_button_states gets the button set of selected unit from button_sets,
+    // and for each button calls
+    //      button.condition(button.condition_param, player, unit)
+    // to decide if the button is enabled, disabled, or hidden.
+    // Check functions referring to button_sets for such call, inlining once
+    // in case the button set lookup is in a child function.
+    let funcs = functions.functions();
+    let global_refs = functions.find_functions_using_global(actx, button_sets);
+    let global_refs = BumpVec::from_iter_in(global_refs.iter().map(|x| x.use_address), bump);
+    let mut result = None;
+    for &use_address in &global_refs {
+        let new = entry_of_until(binary, &funcs, use_address, |entry| {
+            let mut analyzer = IsUpdateButtonStates::<E> {
+                result: EntryOf::Retry,
+                use_address,
+                inline_depth: 0,
+            };
+            let mut analysis = FuncAnalysis::new(binary, ctx, entry);
+            analysis.analyze(&mut analyzer);
+            analyzer.result
+        }).into_option_with_entry().map(|x| x.0);
+        if single_result_assign(new, &mut result) {
+            break;
+        }
+    }
+    result
+}
+
+struct IsUpdateButtonStates<'e, E: ExecutionState<'e>> {
+    result: EntryOf<()>,
+    use_address: E::VirtualAddress,
+    inline_depth: u8,
+}
+
+impl<'e, E: ExecutionState<'e>> analysis::Analyzer<'e> for IsUpdateButtonStates<'e, E> {
+    type State = analysis::DefaultState;
+    type Exec = E;
+    fn operation(&mut self, ctrl: &mut Control<'e, '_, '_, Self>, op: &Operation<'e>) {
+        if self.inline_depth == 0 {
+            let use_address = self.use_address;
+            if use_address >= ctrl.address() && use_address < ctrl.current_instruction_end() {
+                if let EntryOf::Retry = self.result {
+                    self.result = EntryOf::Stop;
+                }
+            }
+        }
+        if let Operation::Call(dest) = *op {
+            let dest = ctrl.resolve(dest);
+            if let Some(dest) = dest.if_constant() {
+                if self.inline_depth == 0 {
+                    self.inline_depth = 1;
+                    ctrl.analyze_with_current_state(self, E::VirtualAddress::from_u64(dest));
+                    self.inline_depth = 0;
+                    if let EntryOf::Ok(..) = self.result {
+                        ctrl.end_analysis();
+                    }
+                }
+                return;
+            }
+            let layouts = E::struct_layouts();
+            let button = match ctrl.if_mem_word_offset(dest, layouts.button_condition_func()) {
+                Some(s) => s,
+                None => return,
+            };
+            let param_offset = layouts.button_condition_param();
+            // 32bit condition functions are fastcall
+            let ok = [ctrl.resolve_arg(0), ctrl.resolve_register(1)].iter()
+                .any(|&x| x.unwrap_and_mask().if_mem16_offset(param_offset) == Some(button));
+            if ok {
+                self.result = EntryOf::Ok(());
+                ctrl.end_analysis();
+            }
+        }
+    }
+}
+
 struct StartTargetingAnalyzer<'e, 'a, E: ExecutionState<'e>> {
     result: &'a mut StartTargeting<'e, E::VirtualAddress>,
     arg_cache: &'a ArgCache<'e, E>,
@@ -2391,6 +2643,68 @@ impl<'e, 'acx, 'a, E: ExecutionState<'e>> scarf::Analyzer<'e> for
     }
 }
 
+pub(crate) fn finalize_drag_select<'e, E: ExecutionState<'e>>(
+    actx: &AnalysisCtx<'e, E>,
+    select_mouse_up: E::VirtualAddress,
+    select_start_x: Operand<'e>,
+) -> Option<E::VirtualAddress> {
+    let binary = actx.binary;
+    let ctx = actx.ctx;
+    // When the mouse has been dragged far enough, select_mouse_up calls
+    //      finalize_drag_select(&rect)
+    // (Or with the coordinates as separate arguments), where the rect is built from
+    // select_start_x/y and the mouse up event position.
+    // Find the first call which gets select_start_x either directly as an argument
+    // or through a pointer argument.
+    let start_address = select_start_x.if_memory()?.address();
+    let mut analyzer = FindFinalizeDragSelect::<E> {
+        result: None,
+        start_address,
+    };
+    let mut analysis = FuncAnalysis::new(binary, ctx, select_mouse_up);
+    analysis.analyze(&mut analyzer);
+    analyzer.result
+}
+
+struct FindFinalizeDragSelect<'e, E: ExecutionState<'e>> {
+    result: Option<E::VirtualAddress>,
+    start_address: (Operand<'e>, u64),
+}
+
+impl<'e, E: ExecutionState<'e>> FindFinalizeDragSelect<'e, E> {
+    fn refers_select_start(&self, op: Operand<'e>) -> bool {
+        op.iter().any(|x| {
+            x.if_memory().map(|mem| mem.address()) == Some(self.start_address)
+        })
+    }
+}
+
+impl<'e, E: ExecutionState<'e>> scarf::Analyzer<'e> for FindFinalizeDragSelect<'e, E> {
+    type State = analysis::DefaultState;
+    type Exec = E;
+    fn operation(&mut self, ctrl: &mut Control<'e, '_, '_, Self>, op: &Operation<'e>) {
+        if let Operation::Call(dest) = *op {
+            if let Some(dest) = ctrl.resolve_va(dest) {
+                let ctx = ctrl.ctx();
+                let ok = (0..4).any(|i| {
+                    let arg = ctrl.resolve_arg(i);
+                    if self.refers_select_start(arg) {
+                        return true;
+                    }
+                    let pointed = ctrl.read_memory(&ctx.mem_access(arg, 0, MemAccessSize::Mem16));
+                    self.refers_select_start(pointed)
+                });
+                if ok {
+                    if single_result_assign(Some(dest), &mut self.result) {
+                        ctrl.end_analysis();
+                    }
+                    ctrl.end_branch();
+                }
+            }
+        }
+    }
+}
+
 pub(crate) fn analyze_update_game_screen_size<'e, E: ExecutionState<'e>>(
     actx: &AnalysisCtx<'e, E>,
     update_game_screen_size: E::VirtualAddress,
@@ -2991,3 +3305,353 @@ fn is_stack_memory<'e, Va: VirtualAddress>(mem: &MemAccess<'e>, ctx: OperandCtx<
         false
     }
 }
+
+/// Finds the function that select_units calls to play the "what" response of the
+/// selected unit. Recognized as the first call that ends up calling play_sound.
+pub(crate) fn play_unit_sound<'e, E: ExecutionState<'e>>(
+    actx: &AnalysisCtx<'e, E>,
+    select_units: E::VirtualAddress,
+    play_sound: E::VirtualAddress,
+) -> Option<E::VirtualAddress> {
+    let binary = actx.binary;
+    let ctx = actx.ctx;
+    let mut analyzer = FindPlayUnitSound::<E> {
+        result: None,
+        play_sound,
+        inline_depth: 0,
+        found: false,
+    };
+    let mut analysis = FuncAnalysis::new(binary, ctx, select_units);
+    analysis.analyze(&mut analyzer);
+    analyzer.result
+}
+
+struct FindPlayUnitSound<'e, E: ExecutionState<'e>> {
+    result: Option<E::VirtualAddress>,
+    play_sound: E::VirtualAddress,
+    inline_depth: u8,
+    found: bool,
+}
+
+impl<'e, E: ExecutionState<'e>> analysis::Analyzer<'e> for FindPlayUnitSound<'e, E> {
+    type State = analysis::DefaultState;
+    type Exec = E;
+    fn operation(&mut self, ctrl: &mut Control<'e, '_, '_, Self>, op: &Operation<'e>) {
+        // play_unit_sound -> play_sound_outermost -> play_sound_outer -> play_sound,
+        // so follow up to 4 calls deep.
+        if let Operation::Call(dest) = *op {
+            if let Some(dest) = ctrl.resolve_va(dest) {
+                if dest == self.play_sound {
+                    self.found = true;
+                    ctrl.end_analysis();
+                    return;
+                }
+                if self.inline_depth < 4 {
+                    self.inline_depth += 1;
+                    ctrl.analyze_with_current_state(self, dest);
+                    self.inline_depth -= 1;
+                    if self.found {
+                        if self.inline_depth == 0 {
+                            self.result = Some(dest);
+                        }
+                        ctrl.end_analysis();
+                    }
+                }
+            }
+        }
+    }
+}
+
+pub(crate) fn toggle_pause<'e, E: ExecutionState<'e>>(
+    actx: &AnalysisCtx<'e, E>,
+    key_down_handler: E::VirtualAddress,
+    is_paused: Operand<'e>,
+) -> Option<E::VirtualAddress> {
+    let binary = actx.binary;
+    let ctx = actx.ctx;
+    let bump = &actx.bump;
+    // The default key down handler (eventually) calls
+    // toggle_pause()
+    // {
+    //     if is_paused != 0 {
+    //         unpause_game();
+    //     } else {
+    //         pause_game();
+    //     }
+    // }
+    // pause_game/unpause_game themselves only do work on one side of a
+    // is_paused check, so require both branches to do something.
+    let mut analyzer = FindTogglePause::<E> {
+        result: None,
+        is_paused,
+        checked_calls: bumpvec_with_capacity(0x20, bump),
+        inline_depth: 0,
+        actx,
+    };
+    let mut analysis = FuncAnalysis::new(binary, ctx, key_down_handler);
+    analysis.analyze(&mut analyzer);
+    analyzer.result
+}
+
+struct FindTogglePause<'acx, 'e, E: ExecutionState<'e>> {
+    result: Option<E::VirtualAddress>,
+    is_paused: Operand<'e>,
+    checked_calls: BumpVec<'acx, E::VirtualAddress>,
+    inline_depth: u8,
+    actx: &'acx AnalysisCtx<'e, E>,
+}
+
+impl<'acx, 'e: 'acx, E: ExecutionState<'e>> analysis::Analyzer<'e> for
+    FindTogglePause<'acx, 'e, E>
+{
+    type State = analysis::DefaultState;
+    type Exec = E;
+    fn operation(&mut self, ctrl: &mut Control<'e, '_, '_, Self>, op: &Operation<'e>) {
+        if let Operation::Call(dest) = *op {
+            if let Some(dest) = ctrl.resolve_va(dest) {
+                if self.checked_calls.iter().any(|&x| x == dest) {
+                    return;
+                }
+                self.checked_calls.push(dest);
+                if is_toggle_pause(self.actx, dest, self.is_paused) {
+                    self.result = Some(dest);
+                    ctrl.end_analysis();
+                    return;
+                }
+                if self.inline_depth < 3 {
+                    self.inline_depth += 1;
+                    ctrl.analyze_with_current_state(self, dest);
+                    self.inline_depth -= 1;
+                    if self.result.is_some() {
+                        ctrl.end_analysis();
+                    }
+                }
+            }
+        }
+    }
+}
+
+pub(crate) fn center_on_townhall<'e, E: ExecutionState<'e>>(
+    actx: &AnalysisCtx<'e, E>,
+    key_down_handler: E::VirtualAddress,
+    units_dat: (E::VirtualAddress, u32),
+) -> Option<E::VirtualAddress> {
+    let binary = actx.binary;
+    let ctx = actx.ctx;
+    let bump = &actx.bump;
+    // The default key down handler (eventually) calls center_on_townhall(),
+    // which loops through local player's units, picks next one that has
+    // resource depot flag (0x2000) set, and moves the screen to it.
+    // Search for the first function reachable from key down handler that checks the
+    // resource depot flag and calls something once the flag check has been done.
+    let units_dat_flags = binary.read_address(units_dat.0 + 0x16 * units_dat.1).ok()?;
+    let mut analyzer = FindCenterOnTownhall::<E> {
+        result: None,
+        units_dat_flags: units_dat_flags.as_u64(),
+        checked_calls: bumpvec_with_capacity(0x20, bump),
+        inline_depth: 0,
+        actx,
+    };
+    let mut analysis = FuncAnalysis::new(binary, ctx, key_down_handler);
+    analysis.analyze(&mut analyzer);
+    analyzer.result
+}
+
+struct FindCenterOnTownhall<'acx, 'e, E: ExecutionState<'e>> {
+    result: Option<E::VirtualAddress>,
+    units_dat_flags: u64,
+    checked_calls: BumpVec<'acx, E::VirtualAddress>,
+    inline_depth: u8,
+    actx: &'acx AnalysisCtx<'e, E>,
+}
+
+impl<'acx, 'e: 'acx, E: ExecutionState<'e>> analysis::Analyzer<'e> for
+    FindCenterOnTownhall<'acx, 'e, E>
+{
+    type State = analysis::DefaultState;
+    type Exec = E;
+    fn operation(&mut self, ctrl: &mut Control<'e, '_, '_, Self>, op: &Operation<'e>) {
+        if let Operation::Call(dest) = *op {
+            if let Some(dest) = ctrl.resolve_va(dest) {
+                if self.checked_calls.iter().any(|&x| x == dest) {
+                    return;
+                }
+                self.checked_calls.push(dest);
+                if is_center_on_townhall(self.actx, dest, self.units_dat_flags) {
+                    self.result = Some(dest);
+                    ctrl.end_analysis();
+                    return;
+                }
+                if self.inline_depth < 3 {
+                    self.inline_depth += 1;
+                    ctrl.analyze_with_current_state(self, dest);
+                    self.inline_depth -= 1;
+                    if self.result.is_some() {
+                        ctrl.end_analysis();
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn is_center_on_townhall<'e, E: ExecutionState<'e>>(
+    actx: &AnalysisCtx<'e, E>,
+    func: E::VirtualAddress,
+    units_dat_flags: u64,
+) -> bool {
+    let binary = actx.binary;
+    let ctx = actx.ctx;
+    let mut analyzer = IsCenterOnTownhall::<E> {
+        units_dat_flags,
+        flag_checked: false,
+        found: false,
+        phantom: Default::default(),
+    };
+    let mut analysis = FuncAnalysis::new(binary, ctx, func);
+    analysis.analyze(&mut analyzer);
+    analyzer.found
+}
+
+struct IsCenterOnTownhall<'e, E: ExecutionState<'e>> {
+    units_dat_flags: u64,
+    flag_checked: bool,
+    found: bool,
+    phantom: std::marker::PhantomData<(*const E, &'e ())>,
+}
+
+impl<'e, E: ExecutionState<'e>> analysis::Analyzer<'e> for IsCenterOnTownhall<'e, E> {
+    type State = analysis::DefaultState;
+    type Exec = E;
+    fn operation(&mut self, ctrl: &mut Control<'e, '_, '_, Self>, op: &Operation<'e>) {
+        match *op {
+            Operation::Call(..) => {
+                if self.flag_checked {
+                    self.found = true;
+                    ctrl.end_analysis();
+                }
+            }
+            Operation::Jump { condition, .. } => {
+                let ctx = ctrl.ctx();
+                let condition = ctrl.resolve(condition);
+                let is_flag_check = condition.if_arithmetic_eq_neq_zero(ctx)
+                    .filter(|x| self.is_resource_depot_flag(x.0))
+                    .is_some();
+                if is_flag_check {
+                    self.flag_checked = true;
+                }
+            }
+            _ => (),
+        }
+    }
+}
+
+impl<'e, E: ExecutionState<'e>> IsCenterOnTownhall<'e, E> {
+    /// Checks for `units_dat_flags[x] & 0x2000`, either as a u32 read or u8 read
+    /// of the second byte.
+    fn is_resource_depot_flag(&self, op: Operand<'e>) -> bool {
+        let (mem, mask) = match op.if_arithmetic_and()
+            .and_then(|(l, r)| Some((l.unwrap_and_mask().if_memory()?, r.if_constant()?)))
+        {
+            Some(s) => s,
+            None => return false,
+        };
+        let offset = mem.address().1;
+        match mem.size {
+            MemAccessSize::Mem32 => mask == 0x2000 && offset == self.units_dat_flags,
+            MemAccessSize::Mem8 => mask == 0x20 && offset == self.units_dat_flags + 1,
+            _ => false,
+        }
+    }
+}
+
+fn is_toggle_pause<'e, E: ExecutionState<'e>>(
+    actx: &AnalysisCtx<'e, E>,
+    func: E::VirtualAddress,
+    is_paused: Operand<'e>,
+) -> bool {
+    let binary = actx.binary;
+    let ctx = actx.ctx;
+    let mut analyzer = IsTogglePause::<E> {
+        is_paused,
+        branches: None,
+    };
+    let mut analysis = FuncAnalysis::new(binary, ctx, func);
+    analysis.analyze(&mut analyzer);
+    let (a, b) = match analyzer.branches {
+        Some(s) => s,
+        None => return false,
+    };
+    [a, b].iter().all(|&branch| {
+        let mut analyzer = TogglePauseBranch::<E> {
+            is_paused,
+            found: false,
+            phantom: Default::default(),
+        };
+        let mut analysis = FuncAnalysis::new(binary, ctx, branch);
+        analysis.analyze(&mut analyzer);
+        analyzer.found
+    })
+}
+
+struct IsTogglePause<'e, E: ExecutionState<'e>> {
+    is_paused: Operand<'e>,
+    /// Both branch destinations of the `is_paused == 0` jump.
+    branches: Option<(E::VirtualAddress, E::VirtualAddress)>,
+}
+
+impl<'e, E: ExecutionState<'e>> analysis::Analyzer<'e> for IsTogglePause<'e, E> {
+    type State = analysis::DefaultState;
+    type Exec = E;
+    fn operation(&mut self, ctrl: &mut Control<'e, '_, '_, Self>, op: &Operation<'e>) {
+        match *op {
+            // The is_paused check should be first thing done in the function.
+            Operation::Call(..) => ctrl.end_analysis(),
+            Operation::Jump { condition, to } => {
+                let condition = ctrl.resolve(condition);
+                let is_paused_check = if_arithmetic_eq_neq(condition)
+                    .map(|(l, r, _)| (l, r))
+                    .and_either_other(|x| x.if_constant().filter(|&c| c == 0))
+                    .filter(|&x| Operand::and_masked(x).0 == self.is_paused)
+                    .is_some();
+                if is_paused_check {
+                    if let Some(to) = ctrl.resolve_va(to) {
+                        self.branches = Some((to, ctrl.current_instruction_end()));
+                    }
+                }
+                ctrl.end_analysis();
+            }
+            _ => (),
+        }
+    }
+}
+
+struct TogglePauseBranch<'e, E: ExecutionState<'e>> {
+    is_paused: Operand<'e>,
+    found: bool,
+    phantom: std::marker::PhantomData<(*const E, &'e ())>,
+}
+
+impl<'e, E: ExecutionState<'e>> analysis::Analyzer<'e> for TogglePauseBranch<'e, E> {
+    type State = analysis::DefaultState;
+    type Exec = E;
+    fn operation(&mut self, ctrl: &mut Control<'e, '_, '_, Self>, op: &Operation<'e>) {
+        match *op {
+            Operation::Call(..) => {
+                self.found = true;
+                ctrl.end_analysis();
+            }
+            Operation::Move(DestOperand::Memory(ref mem), _) => {
+                let mem = ctrl.resolve_mem(mem);
+                let is_paused_write = self.is_paused.if_memory()
+                    .filter(|x| x.address() == mem.address())
+                    .is_some();
+                if is_paused_write {
+                    self.found = true;
+                    ctrl.end_analysis();
+                }
+            }
+            _ => (),
+        }
+    }
+}
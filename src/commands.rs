@@ -13,13 +13,19 @@ use crate::switch::CompleteSwitch;
 use crate::struct_layouts;
 use crate::util::{
     ControlExt, MemAccessExt, OptionExt, OperandExt, read_u32_at,
-    if_arithmetic_eq_neq, is_global, bumpvec_with_capacity, single_result_assign, ExecStateExt,
+    if_arithmetic_eq_neq, is_global, is_stack_address, bumpvec_with_capacity,
+    single_result_assign, ExecStateExt,
 };
 
 #[derive(Clone, Debug)]
 pub struct Selections<'e> {
     pub unique_command_user: Option<Operand<'e>>,
     pub selections: Option<Operand<'e>>,
+    /// Amount of units a player can have selected; also the amount of entries
+    /// in one player's row of `selections`.
+    pub max_selection_count: u32,
+    /// Byte size of one player's row of `selections`.
+    pub selection_array_stride: u32,
 }
 
 #[derive(Clone, Debug)]
@@ -30,10 +36,16 @@ pub struct StepNetwork<'e, Va: VirtualAddressTrait> {
     pub net_player_flags: Option<Operand<'e>>,
     pub player_turns: Option<Operand<'e>>,
     pub player_turns_size: Option<Operand<'e>>,
+    pub storm_turn_count: Option<Operand<'e>>,
     pub network_ready: Option<Operand<'e>>,
     pub storm_command_user: Option<Operand<'e>>,
 }
 
+pub(crate) struct PlayerDrop<'e, Va: VirtualAddressTrait> {
+    pub handle_player_drop: Option<Va>,
+    pub player_left_flags: Option<Operand<'e>>,
+}
+
 pub(crate) struct StepReplayCommands<'e, Va: VirtualAddressTrait> {
     pub replay_end: Option<Va>,
     pub replay_header: Option<Operand<'e>>,
@@ -52,6 +64,11 @@ pub(crate) struct Morph<Va: VirtualAddressTrait> {
     pub prepare_build_unit: Option<Va>,
 }
 
+pub(crate) struct Rally<Va: VirtualAddressTrait> {
+    pub set_rally_point: Option<Va>,
+    pub unit_rally_offset: u16,
+}
+
 pub(crate) struct Colors<'e> {
     pub use_rgb: Option<Operand<'e>>,
     pub rgb_colors: Option<Operand<'e>>,
@@ -443,6 +460,8 @@ pub(crate) fn selections<'e, E: ExecutionState<'e>>(
     let mut result = Selections {
         selections: None,
         unique_command_user: None,
+        max_selection_count: 0,
+        selection_array_stride: 0,
     };
     let cancel_nuke_command = process_commands_switch.branch(binary, ctx, 0x2e);
     let cancel_nuke = match cancel_nuke_command {
@@ -453,6 +472,7 @@ pub(crate) fn selections<'e, E: ExecutionState<'e>>(
     let mut analysis = FuncAnalysis::new(binary, ctx, cancel_nuke);
     let mut analyzer = SelectionsAnalyzer::<E> {
         sel_state: SelectionState::Start,
+        modded_limit: None,
         result: &mut result,
         checked_calls: bumpvec_with_capacity(8, bump),
         inline_depth: 0,
@@ -463,12 +483,17 @@ pub(crate) fn selections<'e, E: ExecutionState<'e>>(
 
 enum SelectionState<'e> {
     Start,
-    LimitJumped(Operand<'e>),
+    /// Selection position operand, selection limit
+    LimitJumped(Operand<'e>, u32),
 }
 
 struct SelectionsAnalyzer<'a, 'acx, 'e, E: ExecutionState<'e>> {
     result: &'a mut Selections<'e>,
     sel_state: SelectionState<'e>,
+    /// Selection position operand, selection limit for a limit jump that didn't
+    /// compare against vanilla limit of 12. Only accepted if the selections
+    /// access afterwards is indexed with the same limit.
+    modded_limit: Option<(Operand<'e>, u32)>,
     checked_calls: BumpVec<'acx, E::VirtualAddress>,
     inline_depth: u8,
 }
@@ -505,15 +530,29 @@ impl<'a, 'acx, 'e, E: ExecutionState<'e>> analysis::Analyzer<'e> for
                     }
                 }
                 let condition = ctrl.resolve(condition);
-                if let SelectionState::Start = self.sel_state {
-                    if let Some((l, r)) = condition.if_arithmetic(ArithOpType::GreaterThan) {
-                        if l.if_constant() == Some(0xc) {
-                            self.sel_state = SelectionState::LimitJumped(r.clone());
+                let limit_jump = condition.if_arithmetic(ArithOpType::GreaterThan)
+                    .and_then(|(l, r)| Some((l.if_constant()?, r)));
+                if let Some((limit, selection_pos)) = limit_jump {
+                    if limit == 0xc {
+                        if let SelectionState::Start = self.sel_state {
+                            self.sel_state = SelectionState::LimitJumped(selection_pos, 0xc);
                         }
+                    } else if limit > 0xc && limit <= 0x400 {
+                        // Selection limit may have been extended; keep track of the latest
+                        // candidate but require it to be confirmed by the selections access.
+                        self.modded_limit = Some((selection_pos, limit as u32));
                     }
-                } else if let SelectionState::LimitJumped(selection_pos) = self.sel_state {
+                    return;
+                }
+                let limit_jump = match self.sel_state {
+                    SelectionState::LimitJumped(selection_pos, limit) =>
+                        Some((selection_pos, limit)),
+                    SelectionState::Start => self.modded_limit,
+                };
+                if let Some((selection_pos, limit)) = limit_jump {
                     // Check if the condition is
-                    // (selections + (unique_command_user * 0xc + selection_pos) * 4) == 0
+                    // (selections + (unique_command_user * limit + selection_pos) * word_size)
+                    //      == 0
                     let ctx = ctrl.ctx();
                     let x = if_arithmetic_eq_neq(condition)
                         .filter(|x| x.1 == ctx.const_0())
@@ -526,12 +565,15 @@ impl<'a, 'acx, 'e, E: ExecutionState<'e>> analysis::Analyzer<'e> for
                     if let Some((index, selections)) = x {
                         let unique_command_user = index.if_arithmetic_add()
                             .and_if_either_other(|x| x == selection_pos)
-                            .and_then(|x| x.if_arithmetic_mul_const(0xc));
+                            .and_then(|x| x.if_arithmetic_mul_const(limit as u64));
                         if let Some(unique_command_user) = unique_command_user {
                             single_result_assign(
                                 Some(unique_command_user.clone()),
                                 &mut self.result.unique_command_user,
                             );
+                            self.result.max_selection_count = limit;
+                            self.result.selection_array_stride =
+                                limit * E::VirtualAddress::SIZE;
                             let end = single_result_assign(
                                 Some(selections.clone()),
                                 &mut self.result.selections,
@@ -642,6 +684,144 @@ impl<'acx, 'e: 'acx, E: ExecutionState<'e>> analysis::Analyzer<'e> for
     }
 }
 
+pub(crate) fn handle_player_drop<'e, E: ExecutionState<'e>>(
+    actx: &AnalysisCtx<'e, E>,
+    step_network: E::VirtualAddress,
+    net_player_flags: Operand<'e>,
+) -> PlayerDrop<'e, E::VirtualAddress> {
+    let mut result = PlayerDrop {
+        handle_player_drop: None,
+        player_left_flags: None,
+    };
+    let binary = actx.binary;
+    let ctx = actx.ctx;
+    let bump = &actx.bump;
+    let net_player_flags = match net_player_flags.if_constant() {
+        Some(s) => s,
+        None => return result,
+    };
+
+    // After receive_storm_turns, step_network checks net_player_flags of each player,
+    // and calls handle_player_drop(player) for players that have been dropped.
+    // handle_player_drop is recognized from it doing
+    //      player_left_flags |= 1 << player
+    // Only calls after a jump depending on net_player_flags are checked.
+    let mut analyzer = FindHandlePlayerDrop::<E> {
+        result: &mut result,
+        state: HandlePlayerDropState::FindFlagCheck,
+        net_player_flags,
+        inline_depth: 0,
+        checked_calls: bumpvec_with_capacity(0x20, bump),
+    };
+    let mut analysis = FuncAnalysis::new(binary, ctx, step_network);
+    analysis.analyze(&mut analyzer);
+    result
+}
+
+struct FindHandlePlayerDrop<'a, 'acx, 'e, E: ExecutionState<'e>> {
+    result: &'a mut PlayerDrop<'e, E::VirtualAddress>,
+    state: HandlePlayerDropState,
+    net_player_flags: u64,
+    inline_depth: u8,
+    checked_calls: BumpVec<'acx, E::VirtualAddress>,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+enum HandlePlayerDropState {
+    /// Inline once (step_network may have the player loop in a child function),
+    /// wait for jump on net_player_flags[x].
+    FindFlagCheck,
+    /// Check calls for handle_player_drop.
+    FindHandlePlayerDrop,
+    /// Find `global = global | (1 << x)`
+    Verify,
+}
+
+impl<'a, 'acx, 'e: 'acx, E: ExecutionState<'e>> analysis::Analyzer<'e> for
+    FindHandlePlayerDrop<'a, 'acx, 'e, E>
+{
+    type State = analysis::DefaultState;
+    type Exec = E;
+    fn operation(&mut self, ctrl: &mut Control<'e, '_, '_, Self>, op: &Operation<'e>) {
+        let ctx = ctrl.ctx();
+        match self.state {
+            HandlePlayerDropState::FindFlagCheck | HandlePlayerDropState::FindHandlePlayerDrop => {
+                match *op {
+                    Operation::Jump { condition, .. } => {
+                        let condition = ctrl.resolve(condition);
+                        let flags_start = self.net_player_flags;
+                        let flags_end = flags_start + 0x20;
+                        let is_flag_check = condition.iter().any(|x| {
+                            x.if_memory()
+                                .filter(|mem| {
+                                    let (_, offset) = mem.address();
+                                    offset >= flags_start && offset < flags_end
+                                })
+                                .is_some()
+                        });
+                        if is_flag_check {
+                            self.state = HandlePlayerDropState::FindHandlePlayerDrop;
+                        }
+                    }
+                    Operation::Call(dest) => {
+                        let dest = match ctrl.resolve_va(dest) {
+                            Some(s) => s,
+                            None => return,
+                        };
+                        if self.checked_calls.iter().any(|&x| x == dest) {
+                            return;
+                        }
+                        self.checked_calls.push(dest);
+                        if self.state == HandlePlayerDropState::FindHandlePlayerDrop {
+                            let arg1 = ctrl.resolve_arg(0);
+                            if arg1.if_constant().is_none() {
+                                self.state = HandlePlayerDropState::Verify;
+                                ctrl.analyze_with_current_state(self, dest);
+                                self.state = HandlePlayerDropState::FindHandlePlayerDrop;
+                                if self.result.player_left_flags.is_some() {
+                                    self.result.handle_player_drop = Some(dest);
+                                    ctrl.end_analysis();
+                                    return;
+                                }
+                            }
+                        }
+                        if self.inline_depth == 0 {
+                            let old_state = self.state;
+                            self.inline_depth = 1;
+                            ctrl.analyze_with_current_state(self, dest);
+                            self.inline_depth = 0;
+                            self.state = old_state;
+                            if self.result.handle_player_drop.is_some() {
+                                ctrl.end_analysis();
+                            }
+                        }
+                    }
+                    _ => (),
+                }
+            }
+            HandlePlayerDropState::Verify => {
+                if let Operation::Move(DestOperand::Memory(ref mem), value) = *op {
+                    let mem = ctrl.resolve_mem(mem);
+                    if !mem.is_global() {
+                        return;
+                    }
+                    let dest_op = ctx.memory(&mem);
+                    let ok = ctrl.resolve(value).unwrap_and_mask()
+                        .if_arithmetic_or()
+                        .and_if_either_other(|x| x.unwrap_and_mask() == dest_op)
+                        .and_then(|x| x.if_arithmetic_lsh())
+                        .filter(|x| x.0 == ctx.const_1())
+                        .is_some();
+                    if ok {
+                        self.result.player_left_flags = Some(dest_op);
+                        ctrl.end_analysis();
+                    }
+                }
+            }
+        }
+    }
+}
+
 pub(crate) fn analyze_step_network<'e, E: ExecutionState<'e>>(
     analysis: &AnalysisCtx<'e, E>,
     step_network: E::VirtualAddress,
@@ -651,6 +831,7 @@ pub(crate) fn analyze_step_network<'e, E: ExecutionState<'e>>(
         net_player_flags: None,
         player_turns: None,
         player_turns_size: None,
+        storm_turn_count: None,
         network_ready: None,
         storm_command_user: None,
         process_commands: None,
@@ -731,6 +912,7 @@ impl<'a, 'acx, 'e: 'acx, E: ExecutionState<'e>> analysis::Analyzer<'e> for
                                 self.result.receive_storm_turns = Some(dest);
                                 self.result.player_turns = Some(arg3);
                                 self.result.player_turns_size = Some(arg4);
+                                self.result.storm_turn_count = Some(ctrl.resolve_arg(1));
                                 self.result.net_player_flags = Some(arg5);
                                 ctrl.user_state().set(StepNetworkState {
                                     after_receive_storm_turns: true,
@@ -1024,6 +1206,156 @@ impl<'e, E: ExecutionState<'e>> analysis::Analyzer<'e> for FindReplayData<'e, E>
     }
 }
 
+pub(crate) fn player_action_counts<'e, E: ExecutionState<'e>>(
+    actx: &AnalysisCtx<'e, E>,
+    process_commands: E::VirtualAddress,
+    command_user: Operand<'e>,
+    storm_command_user: Operand<'e>,
+) -> Option<Operand<'e>> {
+    let binary = actx.binary;
+    let ctx = actx.ctx;
+    let bump = &actx.bump;
+    // Check for `action_counts[player] = action_counts[player] + 1` (u32 array)
+    // in process_commands or a function it calls, where player is either
+    // command_user or storm_command_user.
+    // Versions that don't have it will just not find anything.
+    let mut analyzer = FindPlayerActionCounts::<E> {
+        result: None,
+        command_user,
+        storm_command_user,
+        inline_depth: 0,
+        checked_calls: bumpvec_with_capacity(0x20, bump),
+    };
+    let mut analysis = FuncAnalysis::new(binary, ctx, process_commands);
+    analysis.analyze(&mut analyzer);
+    analyzer.result
+}
+
+struct FindPlayerActionCounts<'acx, 'e, E: ExecutionState<'e>> {
+    result: Option<Operand<'e>>,
+    command_user: Operand<'e>,
+    storm_command_user: Operand<'e>,
+    inline_depth: u8,
+    checked_calls: BumpVec<'acx, E::VirtualAddress>,
+}
+
+impl<'acx, 'e: 'acx, E: ExecutionState<'e>> analysis::Analyzer<'e> for
+    FindPlayerActionCounts<'acx, 'e, E>
+{
+    type State = analysis::DefaultState;
+    type Exec = E;
+    fn operation(&mut self, ctrl: &mut Control<'e, '_, '_, Self>, op: &Operation<'e>) {
+        match *op {
+            Operation::Call(dest) if self.inline_depth < 1 => {
+                if let Some(dest) = ctrl.resolve_va(dest) {
+                    if self.checked_calls.iter().any(|&x| x == dest) {
+                        return;
+                    }
+                    self.checked_calls.push(dest);
+                    self.inline_depth += 1;
+                    ctrl.analyze_with_current_state(self, dest);
+                    self.inline_depth -= 1;
+                    if self.result.is_some() {
+                        ctrl.end_analysis();
+                    }
+                }
+            }
+            Operation::Move(DestOperand::Memory(ref mem), value) => {
+                if mem.size != MemAccessSize::Mem32 {
+                    return;
+                }
+                let ctx = ctrl.ctx();
+                let mem = ctrl.resolve_mem(mem);
+                let (index, array) = mem.address();
+                let is_player_index = array > 0x1000 &&
+                    index.if_arithmetic_mul_const(4)
+                        .filter(|&x| x == self.command_user || x == self.storm_command_user)
+                        .is_some();
+                if !is_player_index {
+                    return;
+                }
+                let value = ctrl.resolve(value);
+                let is_increment = value.if_arithmetic_add_const(1)
+                    .and_then(|x| x.if_mem32())
+                    .filter(|x| x.address() == (index, array))
+                    .is_some();
+                if is_increment {
+                    let result = ctx.constant(array);
+                    if single_result_assign(Some(result), &mut self.result) {
+                        ctrl.end_analysis();
+                    }
+                }
+            }
+            _ => (),
+        }
+    }
+}
+
+pub(crate) fn is_recording_replay<'e, E: ExecutionState<'e>>(
+    actx: &AnalysisCtx<'e, E>,
+    add_to_replay_data: E::VirtualAddress,
+    is_replay: Operand<'e>,
+    replay_data: Operand<'e>,
+) -> Option<Operand<'e>> {
+    // add_to_replay_data(data, len) returns early if the replay isn't being recorded:
+    //      if is_recording_replay == 0 {
+    //          return;
+    //      }
+    // Check first few jumps for x == 0 where x is a global that isn't
+    // is_replay or replay_data.
+    let binary = actx.binary;
+    let ctx = actx.ctx;
+
+    let mut analysis = FuncAnalysis::new(binary, ctx, add_to_replay_data);
+    let mut analyzer = FindIsRecordingReplay::<E> {
+        result: None,
+        is_replay,
+        replay_data,
+        limit: 4,
+        phantom: Default::default(),
+    };
+    analysis.analyze(&mut analyzer);
+    analyzer.result
+}
+
+struct FindIsRecordingReplay<'e, E: ExecutionState<'e>> {
+    result: Option<Operand<'e>>,
+    is_replay: Operand<'e>,
+    replay_data: Operand<'e>,
+    limit: u8,
+    phantom: std::marker::PhantomData<(*const E, &'e ())>,
+}
+
+impl<'e, E: ExecutionState<'e>> analysis::Analyzer<'e> for FindIsRecordingReplay<'e, E> {
+    type State = analysis::DefaultState;
+    type Exec = E;
+    fn operation(&mut self, ctrl: &mut Control<'e, '_, '_, Self>, op: &Operation<'e>) {
+        match *op {
+            Operation::Call(..) => {
+                // The check should be done before anything else
+                ctrl.end_branch();
+            }
+            Operation::Jump { condition, .. } => {
+                if self.limit == 0 {
+                    ctrl.end_analysis();
+                    return;
+                }
+                self.limit -= 1;
+                let condition = ctrl.resolve(condition);
+                let result = condition.if_arithmetic_eq_neq_zero(ctrl.ctx())
+                    .map(|x| x.0.unwrap_and_mask())
+                    .filter(|&x| x != self.is_replay && x != self.replay_data)
+                    .filter(|&x| x.if_memory().filter(|mem| mem.is_global()).is_some());
+                if let Some(result) = result {
+                    self.result = Some(result);
+                    ctrl.end_analysis();
+                }
+            }
+            _ => (),
+        }
+    }
+}
+
 pub(crate) fn analyze_step_replay_commands<'e, E: ExecutionState<'e>>(
     analysis: &AnalysisCtx<'e, E>,
     step_replay_commands: E::VirtualAddress,
@@ -1584,6 +1916,671 @@ impl<'a, 'e, E: ExecutionState<'e>> scarf::Analyzer<'e> for MorphAnalyzer<'a, 'e
     }
 }
 
+pub(crate) fn rally<'e, E: ExecutionState<'e>>(
+    actx: &AnalysisCtx<'e, E>,
+    process_commands: E::VirtualAddress,
+    process_commands_switch: &CompleteSwitch<'e>,
+) -> Rally<E::VirtualAddress> {
+
+    let binary = actx.binary;
+    let ctx = actx.ctx;
+
+    // Targeted order command 0x15 is
+    // { u8 id, u16 x, u16 y, u16 target, u16 unit_id, u8 order, u8 queued }
+    // Inline once to command_targeted_order(data), which checks for order
+    // 0x27 / 0x28 (RallyPointUnit / RallyPointTile) and calls set_rally_point
+    // for selected units instead of issuing an order.
+    // set_rally_point writes Mem16[data + 1] (x) to unit.rally_pos, which is used
+    // to get the offset.
+    let mut result = Rally {
+        set_rally_point: None,
+        unit_rally_offset: 0,
+    };
+    let branch = match process_commands_switch.branch(binary, ctx, 0x15) {
+        Some(s) => s,
+        None => return result,
+    };
+
+    let mut analyzer = RallyAnalyzer::<E> {
+        state: RallyState::BeforeSwitch,
+        inline_depth: 0,
+        branch,
+        result: &mut result,
+        arg_cache: &actx.arg_cache,
+    };
+    let mut exec_state = E::initial_state(ctx, binary);
+    // Set arg3 to 1 so the replay-specific switch will be skipped
+    exec_state.move_resolved(
+        &DestOperand::from_oper(actx.arg_cache.on_entry(2)),
+        ctx.const_1(),
+    );
+    let mut analysis =
+        FuncAnalysis::custom_state(binary, ctx, process_commands, exec_state, Default::default());
+    analysis.analyze(&mut analyzer);
+    result
+}
+
+struct RallyAnalyzer<'a, 'e, E: ExecutionState<'e>> {
+    state: RallyState,
+    inline_depth: u8,
+    result: &'a mut Rally<E::VirtualAddress>,
+    branch: E::VirtualAddress,
+    arg_cache: &'a ArgCache<'e, E>,
+}
+
+enum RallyState {
+    /// Wait for the command switch jump, then continue at command 0x15 branch.
+    BeforeSwitch,
+    /// Find jump on Mem8[data + 9] == 0x27 or 0x28, inline to functions
+    /// taking data as a1.
+    RallyOrderJump,
+    /// Check calls on the rally branch, one of them should write the position.
+    FindSetRallyPoint,
+    /// Find write of Mem16[data + 1] or Mem32[data + 1] to unit.
+    VerifySetRallyPoint,
+}
+
+impl<'a, 'e, E: ExecutionState<'e>> scarf::Analyzer<'e> for RallyAnalyzer<'a, 'e, E> {
+    type State = analysis::DefaultState;
+    type Exec = E;
+    fn operation(&mut self, ctrl: &mut Control<'e, '_, '_, Self>, op: &Operation<'e>) {
+        match self.state {
+            RallyState::BeforeSwitch => {
+                if let Operation::Jump { to, .. } = *op {
+                    if to.if_constant().is_none() {
+                        self.state = RallyState::RallyOrderJump;
+                        ctrl.clear_all_branches();
+                        ctrl.continue_at_address(self.branch);
+                    }
+                } else if let Operation::Call(..) = *op {
+                    ctrl.check_stack_probe();
+                }
+            }
+            RallyState::RallyOrderJump => {
+                if let Operation::Call(dest) = *op {
+                    if let Some(dest) = ctrl.resolve_va(dest) {
+                        if self.inline_depth < 2 {
+                            let arg1 = ctrl.resolve_arg(0);
+                            if arg1 == self.arg_cache.on_entry(0) {
+                                self.inline_depth += 1;
+                                ctrl.analyze_with_current_state(self, dest);
+                                self.inline_depth -= 1;
+                                if self.result.set_rally_point.is_some() {
+                                    ctrl.end_analysis();
+                                }
+                            }
+                        }
+                    }
+                } else if let Operation::Jump { condition, to } = *op {
+                    if to.if_constant().is_none() {
+                        ctrl.end_branch();
+                        return;
+                    }
+                    let condition = ctrl.resolve(condition);
+                    let data = self.arg_cache.on_entry(0);
+                    let ok = condition.if_arithmetic_eq_neq()
+                        .filter(|x| matches!(x.1.if_constant(), Some(0x27) | Some(0x28)))
+                        .filter(|x| x.0.unwrap_and_mask().if_mem8_offset(9) == Some(data))
+                        .map(|x| x.2);
+                    if let Some(eq) = ok {
+                        self.state = RallyState::FindSetRallyPoint;
+                        ctrl.clear_unchecked_branches();
+                        ctrl.continue_at_eq_address(eq, to);
+                    }
+                }
+            }
+            RallyState::FindSetRallyPoint => {
+                if let Operation::Call(dest) = *op {
+                    if let Some(dest) = ctrl.resolve_va(dest) {
+                        self.state = RallyState::VerifySetRallyPoint;
+                        ctrl.analyze_with_current_state(self, dest);
+                        if self.result.set_rally_point.is_some() {
+                            self.result.set_rally_point = Some(dest);
+                            ctrl.end_analysis();
+                        } else {
+                            self.state = RallyState::FindSetRallyPoint;
+                        }
+                    }
+                }
+            }
+            RallyState::VerifySetRallyPoint => {
+                if let Operation::Move(DestOperand::Memory(ref mem), value) = *op {
+                    let data = self.arg_cache.on_entry(0);
+                    let value = ctrl.resolve(value);
+                    let is_pos = match mem.size {
+                        MemAccessSize::Mem16 => value.if_mem16_offset(1) == Some(data),
+                        MemAccessSize::Mem32 => value.if_mem32_offset(1) == Some(data),
+                        _ => false,
+                    };
+                    if is_pos {
+                        let mem = ctrl.resolve_mem(mem);
+                        let (base, offset) = mem.address();
+                        if !is_stack_address(base) && !mem.is_global() && offset < 0x400 {
+                            self.result.unit_rally_offset = offset as u16;
+                            self.result.set_rally_point = Some(E::VirtualAddress::from_u64(0));
+                            ctrl.end_analysis();
+                        }
+                    }
+                } else if let Operation::Call(..) = *op {
+                    // Writes should be directly in set_rally_point
+                    ctrl.skip_call_preserve_esp();
+                }
+            }
+        }
+    }
+}
+
+pub(crate) fn enqueue_build<'e, E: ExecutionState<'e>>(
+    actx: &AnalysisCtx<'e, E>,
+    process_commands: E::VirtualAddress,
+    process_commands_switch: &CompleteSwitch<'e>,
+    build_queue_offset: u16,
+) -> Option<E::VirtualAddress> {
+    let binary = actx.binary;
+    let ctx = actx.ctx;
+
+    // Train command 0x1f is { u8 id, u16 unit_id }
+    // Inline once to command_train(data), which calls enqueue_build(this = unit, unit_id)
+    // after requirement checks.
+    // enqueue_build is verified by checking for Mem16[data + 1] write to
+    // unit.build_queue[x].
+    let branch = process_commands_switch.branch(binary, ctx, 0x1f)?;
+
+    let mut analyzer = EnqueueBuildAnalyzer::<E> {
+        state: EnqueueBuildState::BeforeSwitch,
+        inline_depth: 0,
+        branch,
+        result: None,
+        verified: false,
+        build_queue_offset: build_queue_offset as u64,
+        arg_cache: &actx.arg_cache,
+    };
+    let mut exec_state = E::initial_state(ctx, binary);
+    // Set arg3 to 1 so the replay-specific switch will be skipped
+    exec_state.move_resolved(
+        &DestOperand::from_oper(actx.arg_cache.on_entry(2)),
+        ctx.const_1(),
+    );
+    let mut analysis =
+        FuncAnalysis::custom_state(binary, ctx, process_commands, exec_state, Default::default());
+    analysis.analyze(&mut analyzer);
+    analyzer.result
+}
+
+struct EnqueueBuildAnalyzer<'a, 'e, E: ExecutionState<'e>> {
+    state: EnqueueBuildState,
+    inline_depth: u8,
+    result: Option<E::VirtualAddress>,
+    /// Set in VerifyEnqueueBuild state when the build queue write was seen.
+    verified: bool,
+    branch: E::VirtualAddress,
+    build_queue_offset: u64,
+    arg_cache: &'a ArgCache<'e, E>,
+}
+
+enum EnqueueBuildState {
+    /// Wait for the command switch jump, then continue at command 0x1f branch.
+    BeforeSwitch,
+    /// Inline to functions taking data as a1, check calls with
+    /// a1 = Mem16[data + 1] (thiscall or not)
+    FindEnqueueBuild,
+    /// Find write of Mem16[data + 1] to unit.build_queue
+    VerifyEnqueueBuild,
+}
+
+impl<'a, 'e, E: ExecutionState<'e>> scarf::Analyzer<'e> for EnqueueBuildAnalyzer<'a, 'e, E> {
+    type State = analysis::DefaultState;
+    type Exec = E;
+    fn operation(&mut self, ctrl: &mut Control<'e, '_, '_, Self>, op: &Operation<'e>) {
+        match self.state {
+            EnqueueBuildState::BeforeSwitch => {
+                if let Operation::Jump { to, .. } = *op {
+                    if to.if_constant().is_none() {
+                        self.state = EnqueueBuildState::FindEnqueueBuild;
+                        ctrl.clear_all_branches();
+                        ctrl.continue_at_address(self.branch);
+                    }
+                } else if let Operation::Call(..) = *op {
+                    ctrl.check_stack_probe();
+                }
+            }
+            EnqueueBuildState::FindEnqueueBuild => {
+                if let Operation::Call(dest) = *op {
+                    if let Some(dest) = ctrl.resolve_va(dest) {
+                        let data = self.arg_cache.on_entry(0);
+                        let arg1 = ctrl.resolve_arg(0);
+                        if self.inline_depth == 0 && arg1 == data {
+                            self.inline_depth = 1;
+                            ctrl.analyze_with_current_state(self, dest);
+                            self.inline_depth = 0;
+                            if self.result.is_some() {
+                                ctrl.end_analysis();
+                            }
+                            return;
+                        }
+                        let is_unit_id = |op: Operand<'e>| {
+                            op.unwrap_and_mask().if_mem16_offset(1) == Some(data)
+                        };
+                        if is_unit_id(arg1) || is_unit_id(ctrl.resolve_arg_thiscall(0)) {
+                            self.state = EnqueueBuildState::VerifyEnqueueBuild;
+                            self.verified = false;
+                            ctrl.analyze_with_current_state(self, dest);
+                            self.state = EnqueueBuildState::FindEnqueueBuild;
+                            if self.verified {
+                                self.result = Some(dest);
+                                ctrl.end_analysis();
+                            }
+                        }
+                    }
+                } else if let Operation::Jump { to, .. } = *op {
+                    if to.if_constant().is_none() {
+                        // Switch again
+                        ctrl.end_branch();
+                    }
+                }
+            }
+            EnqueueBuildState::VerifyEnqueueBuild => {
+                if let Operation::Move(DestOperand::Memory(ref mem), value) = *op {
+                    if mem.size != MemAccessSize::Mem16 {
+                        return;
+                    }
+                    let data = self.arg_cache.on_entry(0);
+                    let value = ctrl.resolve(value);
+                    if value.unwrap_and_mask().if_mem16_offset(1) == Some(data) {
+                        let mem = ctrl.resolve_mem(mem);
+                        let (base, offset) = mem.address();
+                        let queue_start = self.build_queue_offset;
+                        let ok = !is_stack_address(base) &&
+                            offset >= queue_start &&
+                            offset < queue_start + 10;
+                        if ok {
+                            self.verified = true;
+                            ctrl.end_analysis();
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Finds function that research (0x30) or upgrade (0x32) command calls with
+/// tech/upgrade id after requirement checks.
+pub(crate) fn queue_research<'e, E: ExecutionState<'e>>(
+    actx: &AnalysisCtx<'e, E>,
+    process_commands: E::VirtualAddress,
+    process_commands_switch: &CompleteSwitch<'e>,
+    command: u32,
+) -> Option<E::VirtualAddress> {
+    let binary = actx.binary;
+    let ctx = actx.ctx;
+
+    // Research / upgrade commands are { u8 id, u8 tech_or_upgrade_id }
+    // Inline once to command_research(data), which calls queue_tech(this = unit, tech_id)
+    // queue_tech is verified by checking for Mem8[data + 1] write to
+    // unit.building.tech / unit.building.upgrade.
+    let branch = process_commands_switch.branch(binary, ctx, command)?;
+
+    let mut analyzer = QueueResearchAnalyzer::<E> {
+        state: QueueResearchState::BeforeSwitch,
+        inline_depth: 0,
+        branch,
+        result: None,
+        verified: false,
+        arg_cache: &actx.arg_cache,
+    };
+    let mut exec_state = E::initial_state(ctx, binary);
+    // Set arg3 to 1 so the replay-specific switch will be skipped
+    exec_state.move_resolved(
+        &DestOperand::from_oper(actx.arg_cache.on_entry(2)),
+        ctx.const_1(),
+    );
+    let mut analysis =
+        FuncAnalysis::custom_state(binary, ctx, process_commands, exec_state, Default::default());
+    analysis.analyze(&mut analyzer);
+    analyzer.result
+}
+
+struct QueueResearchAnalyzer<'a, 'e, E: ExecutionState<'e>> {
+    state: QueueResearchState,
+    inline_depth: u8,
+    result: Option<E::VirtualAddress>,
+    /// Set in VerifyQueueResearch state when the tech / upgrade id write was seen.
+    verified: bool,
+    branch: E::VirtualAddress,
+    arg_cache: &'a ArgCache<'e, E>,
+}
+
+enum QueueResearchState {
+    /// Wait for the command switch jump, then continue at command branch.
+    BeforeSwitch,
+    /// Inline to functions taking data as a1, check calls with
+    /// a1 = Mem8[data + 1] (thiscall or not)
+    FindQueueResearch,
+    /// Find write of Mem8[data + 1] to unit struct
+    VerifyQueueResearch,
+}
+
+impl<'a, 'e, E: ExecutionState<'e>> scarf::Analyzer<'e> for QueueResearchAnalyzer<'a, 'e, E> {
+    type State = analysis::DefaultState;
+    type Exec = E;
+    fn operation(&mut self, ctrl: &mut Control<'e, '_, '_, Self>, op: &Operation<'e>) {
+        let data = self.arg_cache.on_entry(0);
+        let is_research_id = |op: Operand<'e>| {
+            op.unwrap_and_mask().if_mem8_offset(1) == Some(data)
+        };
+        match self.state {
+            QueueResearchState::BeforeSwitch => {
+                if let Operation::Jump { to, .. } = *op {
+                    if to.if_constant().is_none() {
+                        self.state = QueueResearchState::FindQueueResearch;
+                        ctrl.clear_all_branches();
+                        ctrl.continue_at_address(self.branch);
+                    }
+                } else if let Operation::Call(..) = *op {
+                    ctrl.check_stack_probe();
+                }
+            }
+            QueueResearchState::FindQueueResearch => {
+                if let Operation::Call(dest) = *op {
+                    if let Some(dest) = ctrl.resolve_va(dest) {
+                        let arg1 = ctrl.resolve_arg(0);
+                        if self.inline_depth == 0 && arg1 == data {
+                            self.inline_depth = 1;
+                            ctrl.analyze_with_current_state(self, dest);
+                            self.inline_depth = 0;
+                            if self.result.is_some() {
+                                ctrl.end_analysis();
+                            }
+                            return;
+                        }
+                        if is_research_id(arg1) || is_research_id(ctrl.resolve_arg_thiscall(0)) {
+                            self.state = QueueResearchState::VerifyQueueResearch;
+                            self.verified = false;
+                            ctrl.analyze_with_current_state(self, dest);
+                            self.state = QueueResearchState::FindQueueResearch;
+                            if self.verified {
+                                self.result = Some(dest);
+                                ctrl.end_analysis();
+                            }
+                        }
+                    }
+                } else if let Operation::Jump { to, .. } = *op {
+                    if to.if_constant().is_none() {
+                        // Switch again
+                        ctrl.end_branch();
+                    }
+                }
+            }
+            QueueResearchState::VerifyQueueResearch => {
+                if let Operation::Move(DestOperand::Memory(ref mem), value) = *op {
+                    if !matches!(mem.size, MemAccessSize::Mem8 | MemAccessSize::Mem16) {
+                        return;
+                    }
+                    let value = ctrl.resolve(value);
+                    if is_research_id(value) {
+                        let mem = ctrl.resolve_mem(mem);
+                        let (base, offset) = mem.address();
+                        if !is_stack_address(base) && !mem.is_global() && offset < 0x400 {
+                            self.verified = true;
+                            ctrl.end_analysis();
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+pub(crate) fn apply_stim<'e, E: ExecutionState<'e>>(
+    actx: &AnalysisCtx<'e, E>,
+    process_commands: E::VirtualAddress,
+    process_commands_switch: &CompleteSwitch<'e>,
+) -> Option<E::VirtualAddress> {
+    let binary = actx.binary;
+    let ctx = actx.ctx;
+
+    // Stim command (0x36) has no arguments; command_stim() loops through
+    // selected units and calls apply_stim(this = unit), which damages the unit
+    // and sets `unit.stim_timer = constant`.
+    // Inline up to depth 2 and take the innermost function that writes a constant
+    // to stim timer.
+    let branch = process_commands_switch.branch(binary, ctx, 0x36)?;
+
+    let mut analyzer = ApplyStimAnalyzer::<E> {
+        state: ApplyStimState::BeforeSwitch,
+        inline_depth: 0,
+        branch,
+        result: None,
+        found_write: false,
+    };
+    let mut exec_state = E::initial_state(ctx, binary);
+    // Set arg3 to 1 so the replay-specific switch will be skipped
+    exec_state.move_resolved(
+        &DestOperand::from_oper(actx.arg_cache.on_entry(2)),
+        ctx.const_1(),
+    );
+    let mut analysis =
+        FuncAnalysis::custom_state(binary, ctx, process_commands, exec_state, Default::default());
+    analysis.analyze(&mut analyzer);
+    analyzer.result
+}
+
+struct ApplyStimAnalyzer<'e, E: ExecutionState<'e>> {
+    state: ApplyStimState,
+    inline_depth: u8,
+    result: Option<E::VirtualAddress>,
+    branch: E::VirtualAddress,
+    /// Set once the stim timer write has been seen; result is then the innermost
+    /// function that contained it.
+    found_write: bool,
+}
+
+enum ApplyStimState {
+    /// Wait for the command switch jump, then continue at command branch.
+    BeforeSwitch,
+    /// Inline to calls, find Mem8[unit + stim_timer] = constant
+    FindApplyStim,
+}
+
+impl<'e, E: ExecutionState<'e>> scarf::Analyzer<'e> for ApplyStimAnalyzer<'e, E> {
+    type State = analysis::DefaultState;
+    type Exec = E;
+    fn operation(&mut self, ctrl: &mut Control<'e, '_, '_, Self>, op: &Operation<'e>) {
+        match self.state {
+            ApplyStimState::BeforeSwitch => {
+                if let Operation::Jump { to, .. } = *op {
+                    if to.if_constant().is_none() {
+                        self.state = ApplyStimState::FindApplyStim;
+                        ctrl.clear_all_branches();
+                        ctrl.continue_at_address(self.branch);
+                    }
+                } else if let Operation::Call(..) = *op {
+                    ctrl.check_stack_probe();
+                }
+            }
+            ApplyStimState::FindApplyStim => match *op {
+                Operation::Call(dest) if self.inline_depth < 2 => {
+                    if let Some(dest) = ctrl.resolve_va(dest) {
+                        self.inline_depth += 1;
+                        ctrl.analyze_with_current_state(self, dest);
+                        self.inline_depth -= 1;
+                        if self.found_write {
+                            if self.result.is_none() {
+                                self.result = Some(dest);
+                            }
+                            ctrl.end_analysis();
+                        }
+                    }
+                }
+                Operation::Move(DestOperand::Memory(ref mem), value)
+                    if self.inline_depth != 0 =>
+                {
+                    if mem.size != MemAccessSize::Mem8 {
+                        return;
+                    }
+                    if ctrl.resolve(value).if_constant().is_none() {
+                        return;
+                    }
+                    let mem = ctrl.resolve_mem(mem);
+                    let (base, offset) = mem.address();
+                    if offset == E::struct_layouts().unit_stim_timer() &&
+                        !is_stack_address(base) &&
+                        !mem.is_global()
+                    {
+                        self.found_write = true;
+                        ctrl.end_analysis();
+                    }
+                }
+                Operation::Jump { to, .. } if self.inline_depth == 0 => {
+                    if to.if_constant().is_none() {
+                        // Switch again
+                        ctrl.end_branch();
+                    }
+                }
+                _ => (),
+            },
+        }
+    }
+}
+
+pub(crate) fn can_afford<'e, E: ExecutionState<'e>>(
+    actx: &AnalysisCtx<'e, E>,
+    process_commands: E::VirtualAddress,
+    process_commands_switch: &CompleteSwitch<'e>,
+    game: Operand<'e>,
+) -> Option<E::VirtualAddress> {
+    let binary = actx.binary;
+    let ctx = actx.ctx;
+
+    // Same as enqueue_build; command_train(data) checks
+    // can_afford(player, unit_id) before calling enqueue_build.
+    // can_afford is verified by checking for a comparison against
+    // game.minerals[player] / game.gas[player].
+    let branch = process_commands_switch.branch(binary, ctx, 0x1f)?;
+
+    let mut analyzer = CanAffordAnalyzer::<E> {
+        state: CanAffordState::BeforeSwitch,
+        inline_depth: 0,
+        branch,
+        result: None,
+        verified: false,
+        game,
+        arg_cache: &actx.arg_cache,
+    };
+    let mut exec_state = E::initial_state(ctx, binary);
+    // Set arg3 to 1 so the replay-specific switch will be skipped
+    exec_state.move_resolved(
+        &DestOperand::from_oper(actx.arg_cache.on_entry(2)),
+        ctx.const_1(),
+    );
+    let mut analysis =
+        FuncAnalysis::custom_state(binary, ctx, process_commands, exec_state, Default::default());
+    analysis.analyze(&mut analyzer);
+    analyzer.result
+}
+
+struct CanAffordAnalyzer<'a, 'e, E: ExecutionState<'e>> {
+    state: CanAffordState,
+    inline_depth: u8,
+    result: Option<E::VirtualAddress>,
+    /// Set in VerifyCanAfford state when the resource comparison was seen.
+    verified: bool,
+    branch: E::VirtualAddress,
+    game: Operand<'e>,
+    arg_cache: &'a ArgCache<'e, E>,
+}
+
+enum CanAffordState {
+    /// Wait for the command switch jump, then continue at command 0x1f branch.
+    BeforeSwitch,
+    /// Inline to functions taking data as a1, check calls with
+    /// Mem16[data + 1] in arguments.
+    FindCanAfford,
+    /// Find comparison of game.minerals[x] or game.gas[x]
+    VerifyCanAfford,
+}
+
+impl<'a, 'e, E: ExecutionState<'e>> scarf::Analyzer<'e> for CanAffordAnalyzer<'a, 'e, E> {
+    type State = analysis::DefaultState;
+    type Exec = E;
+    fn operation(&mut self, ctrl: &mut Control<'e, '_, '_, Self>, op: &Operation<'e>) {
+        match self.state {
+            CanAffordState::BeforeSwitch => {
+                if let Operation::Jump { to, .. } = *op {
+                    if to.if_constant().is_none() {
+                        self.state = CanAffordState::FindCanAfford;
+                        ctrl.clear_all_branches();
+                        ctrl.continue_at_address(self.branch);
+                    }
+                } else if let Operation::Call(..) = *op {
+                    ctrl.check_stack_probe();
+                }
+            }
+            CanAffordState::FindCanAfford => {
+                if let Operation::Call(dest) = *op {
+                    if let Some(dest) = ctrl.resolve_va(dest) {
+                        let data = self.arg_cache.on_entry(0);
+                        let arg1 = ctrl.resolve_arg(0);
+                        if self.inline_depth == 0 && arg1 == data {
+                            self.inline_depth = 1;
+                            ctrl.analyze_with_current_state(self, dest);
+                            self.inline_depth = 0;
+                            if self.result.is_some() {
+                                ctrl.end_analysis();
+                            }
+                            return;
+                        }
+                        let is_unit_id = |op: Operand<'e>| {
+                            op.unwrap_and_mask().if_mem16_offset(1) == Some(data)
+                        };
+                        let has_unit_id_arg = is_unit_id(arg1) ||
+                            is_unit_id(ctrl.resolve_arg(1)) ||
+                            is_unit_id(ctrl.resolve_arg_thiscall(0));
+                        if has_unit_id_arg {
+                            self.state = CanAffordState::VerifyCanAfford;
+                            self.verified = false;
+                            ctrl.analyze_with_current_state(self, dest);
+                            self.state = CanAffordState::FindCanAfford;
+                            if self.verified {
+                                self.result = Some(dest);
+                                ctrl.end_analysis();
+                            }
+                        }
+                    }
+                } else if let Operation::Jump { to, .. } = *op {
+                    if to.if_constant().is_none() {
+                        // Switch again
+                        ctrl.end_branch();
+                    }
+                }
+            }
+            CanAffordState::VerifyCanAfford => {
+                if let Operation::Jump { condition, .. } = *op {
+                    let condition = ctrl.resolve(condition);
+                    let game = self.game;
+                    let is_resource_check = condition.if_arithmetic(ArithOpType::GreaterThan)
+                        .filter(|&(l, r)| {
+                            [l, r].iter().any(|&x| {
+                                x.unwrap_and_mask().if_mem32()
+                                    .filter(|mem| mem.address().0.iter().any(|y| y == game))
+                                    .is_some()
+                            })
+                        })
+                        .is_some();
+                    if is_resource_check {
+                        self.verified = true;
+                        ctrl.end_analysis();
+                    }
+                } else if let Operation::Call(..) = *op {
+                    // Resource comparisions should be directly in can_afford
+                    ctrl.skip_call_preserve_esp();
+                }
+            }
+        }
+    }
+}
+
 pub(crate) fn save_replay<'e, E: ExecutionState<'e>>(
     analysis: &AnalysisCtx<'e, E>,
     functions: &FunctionFinder<'_, 'e, E>,
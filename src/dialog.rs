@@ -15,7 +15,7 @@ use crate::call_tracker::CallTracker;
 use crate::switch::CompleteSwitch;
 use crate::util::{
     ControlExt, MemAccessExt, OperandExt, OptionExt, bumpvec_with_capacity, single_result_assign,
-    is_global, is_stack_address, ExecStateExt,
+    if_arithmetic_eq_neq, is_global, is_stack_address, ExecStateExt,
 };
 
 #[derive(Clone)]
@@ -74,6 +74,11 @@ pub(crate) struct UiEventHandlers<'e, Va: VirtualAddress> {
     pub default_scroll_handler: Option<Va>,
 }
 
+pub(crate) struct ChatInput<'e, Va: VirtualAddress> {
+    pub chat_input_handler: Option<Va>,
+    pub chat_input_buffer: Option<Operand<'e>>,
+}
+
 pub(crate) struct RunMenus<Va: VirtualAddress> {
     pub set_music: Option<Va>,
     pub pre_mission_glue: Option<Va>,
@@ -88,6 +93,8 @@ pub(crate) struct RunDialog<Va: VirtualAddress> {
     pub event_handler: Option<Va>,
     /// Function calling run_dialog
     pub parent_function: Option<Va>,
+    /// Function loading the dialog from .bin / .ui file, returning the dialog
+    pub load_dialog: Option<Va>,
 }
 
 pub(crate) struct GluCmpgnEvents<'e, Va: VirtualAddress> {
@@ -138,6 +145,7 @@ fn run_dialog_analysis<'e, E: ExecutionState<'e>>(
         run_dialog: None,
         event_handler: None,
         parent_function: None,
+        load_dialog: None,
     };
     let ctx = analysis.ctx;
 
@@ -157,6 +165,9 @@ fn run_dialog_analysis<'e, E: ExecutionState<'e>>(
             if result.run_dialog.is_some() {
                 EntryOf::Ok(())
             } else {
+                // load_dialog is only valid if it was seen in the same function
+                // as run_dialog.
+                result.load_dialog = None;
                 EntryOf::Retry
             }
         }).into_option_with_entry();
@@ -223,6 +234,10 @@ impl<'exec, 'b, E: ExecutionState<'exec>> scarf::Analyzer<'exec> for
                         .is_some_and(|c| c == self.string_address.as_u64())
                 };
                 if arg1_is_string_ptr {
+                    // dialog = load_dialog(path, ...)
+                    if self.result.load_dialog.is_none() {
+                        self.result.load_dialog = ctrl.resolve_va(to);
+                    }
                     ctrl.do_call_with_result(ctx.custom(0));
                 }
                 let arg4_is_string_ptr = arg4.if_constant()
@@ -366,6 +381,245 @@ pub(crate) fn spawn_dialog<'e, E: ExecutionState<'e>>(
     run_dialog_analysis(analysis, functions, b"rez\\statlb", b"statlb.ui")
 }
 
+pub(crate) fn find_dialog_control<'e, E: ExecutionState<'e>>(
+    actx: &AnalysisCtx<'e, E>,
+    event_handler: E::VirtualAddress,
+) -> Option<E::VirtualAddress> {
+    let binary = actx.binary;
+    let ctx = actx.ctx;
+    let bump = &actx.bump;
+    let arg_cache = &actx.arg_cache;
+    // Dialog event handlers look up their child controls with
+    //      find_dialog_control(dialog, id)
+    // where dialog is the event handler's a1 and id is a small constant.
+    // find_dialog_control is verified by it comparing a Mem16 (control.id) to a2.
+    let mut analyzer = FindDialogControl::<E> {
+        result: None,
+        arg_cache,
+        dialog: arg_cache.on_entry(0),
+        inline_depth: 0,
+        checked_calls: bumpvec_with_capacity(0x20, bump),
+    };
+    let mut analysis = FuncAnalysis::new(binary, ctx, event_handler);
+    analysis.analyze(&mut analyzer);
+    analyzer.result
+}
+
+struct FindDialogControl<'a, 'acx, 'e, E: ExecutionState<'e>> {
+    result: Option<E::VirtualAddress>,
+    arg_cache: &'a ArgCache<'e, E>,
+    dialog: Operand<'e>,
+    inline_depth: u8,
+    checked_calls: BumpVec<'acx, E::VirtualAddress>,
+}
+
+impl<'a, 'acx, 'e: 'acx, E: ExecutionState<'e>> scarf::Analyzer<'e> for
+    FindDialogControl<'a, 'acx, 'e, E>
+{
+    type State = analysis::DefaultState;
+    type Exec = E;
+    fn operation(&mut self, ctrl: &mut Control<'e, '_, '_, Self>, op: &Operation<'e>) {
+        if let Operation::Call(dest) = *op {
+            let dest = match ctrl.resolve_va(dest) {
+                Some(s) => s,
+                None => return,
+            };
+            if self.checked_calls.iter().any(|&x| x == dest) {
+                return;
+            }
+            let ctx = ctrl.ctx();
+            let arg1 = ctrl.resolve_arg(0);
+            let arg2 = ctrl.resolve_arg(1);
+            // 32-bit may also use fastcall
+            let ecx = ctrl.resolve_register(1);
+            let edx = ctrl.resolve_register(2);
+            let is_small_id = |op: Operand<'e>| {
+                ctx.and_const(op, 0xffff).if_constant().is_some_and(|c| c != 0 && c < 0x100)
+            };
+            let id_arg = if arg1 == self.dialog && is_small_id(arg2) {
+                Some(self.arg_cache.on_entry(1))
+            } else if ecx == self.dialog && is_small_id(edx) {
+                Some(ctx.register(2))
+            } else {
+                None
+            };
+            if let Some(id_arg) = id_arg {
+                self.checked_calls.push(dest);
+                let mut analyzer = IsFindDialogControl::<E> {
+                    result: false,
+                    id_arg,
+                    phantom: Default::default(),
+                };
+                let mut analysis = FuncAnalysis::new(ctrl.binary(), ctx, dest);
+                analysis.analyze(&mut analyzer);
+                if analyzer.result {
+                    self.result = Some(dest);
+                    ctrl.end_analysis();
+                }
+            } else if self.inline_depth < 2 && (arg1 == self.dialog || ecx == self.dialog) {
+                // Event handler may dispatch events to other functions
+                self.checked_calls.push(dest);
+                self.inline_depth += 1;
+                ctrl.analyze_with_current_state(self, dest);
+                self.inline_depth -= 1;
+                if self.result.is_some() {
+                    ctrl.end_analysis();
+                }
+            }
+        }
+    }
+}
+
+struct IsFindDialogControl<'e, E: ExecutionState<'e>> {
+    result: bool,
+    id_arg: Operand<'e>,
+    phantom: std::marker::PhantomData<(*const E, &'e ())>,
+}
+
+impl<'e, E: ExecutionState<'e>> scarf::Analyzer<'e> for IsFindDialogControl<'e, E> {
+    type State = analysis::DefaultState;
+    type Exec = E;
+    fn operation(&mut self, ctrl: &mut Control<'e, '_, '_, Self>, op: &Operation<'e>) {
+        match *op {
+            Operation::Call(..) => {
+                // Should be a simple loop without calls
+                ctrl.end_analysis();
+            }
+            Operation::Jump { condition, .. } => {
+                let condition = ctrl.resolve(condition);
+                let id_arg = self.id_arg;
+                let ok = if_arithmetic_eq_neq(condition)
+                    .map(|x| (x.0, x.1))
+                    .and_if_either_other(|x| Operand::and_masked(x).0 == id_arg)
+                    .filter(|&x| x.unwrap_and_mask().if_mem16().is_some())
+                    .is_some();
+                if ok {
+                    self.result = true;
+                    ctrl.end_analysis();
+                }
+            }
+            _ => (),
+        }
+    }
+}
+
+pub(crate) fn chat_input<'e, E: ExecutionState<'e>>(
+    actx: &AnalysisCtx<'e, E>,
+    char_handler: E::VirtualAddress,
+) -> ChatInput<'e, E::VirtualAddress> {
+    let binary = actx.binary;
+    let ctx = actx.ctx;
+    let bump = &actx.bump;
+    let mut result = ChatInput {
+        chat_input_handler: None,
+        chat_input_buffer: None,
+    };
+    // The default char handler forwards characters to chat input handler when
+    // the chat box is open, which then does
+    //      chat_input_buffer[chat_input_pos] = event.wchar
+    //      chat_input_pos += 1
+    // Find a store of a value read from the event to an array indexed by a global,
+    // and require the same function to increment that global.
+    let mut analyzer = FindChatInput::<E> {
+        result: &mut result,
+        event: actx.arg_cache.on_entry(0),
+        current_entry: char_handler,
+        inline_depth: 0,
+        checked_calls: bumpvec_with_capacity(0x20, bump),
+        buffer_stores: bumpvec_with_capacity(0x8, bump),
+        increments: bumpvec_with_capacity(0x8, bump),
+    };
+    let mut analysis = FuncAnalysis::new(binary, ctx, char_handler);
+    analysis.analyze(&mut analyzer);
+    result
+}
+
+struct FindChatInput<'a, 'acx, 'e, E: ExecutionState<'e>> {
+    result: &'a mut ChatInput<'e, E::VirtualAddress>,
+    event: Operand<'e>,
+    current_entry: E::VirtualAddress,
+    inline_depth: u8,
+    checked_calls: BumpVec<'acx, E::VirtualAddress>,
+    /// (Function, buffer address, position global)
+    buffer_stores: BumpVec<'acx, (E::VirtualAddress, u64, Operand<'e>)>,
+    /// (Function, global) for `global = global + 1`
+    increments: BumpVec<'acx, (E::VirtualAddress, Operand<'e>)>,
+}
+
+impl<'a, 'acx, 'e: 'acx, E: ExecutionState<'e>> scarf::Analyzer<'e> for
+    FindChatInput<'a, 'acx, 'e, E>
+{
+    type State = analysis::DefaultState;
+    type Exec = E;
+    fn operation(&mut self, ctrl: &mut Control<'e, '_, '_, Self>, op: &Operation<'e>) {
+        match *op {
+            Operation::Call(dest) => {
+                if self.inline_depth >= 2 {
+                    return;
+                }
+                let dest = match ctrl.resolve_va(dest) {
+                    Some(s) => s,
+                    None => return,
+                };
+                if self.checked_calls.iter().any(|&x| x == dest) {
+                    return;
+                }
+                self.checked_calls.push(dest);
+                let old_entry = self.current_entry;
+                self.current_entry = dest;
+                self.inline_depth += 1;
+                ctrl.analyze_with_current_state(self, dest);
+                self.inline_depth -= 1;
+                self.current_entry = old_entry;
+                if self.result.chat_input_handler.is_some() {
+                    ctrl.end_analysis();
+                }
+            }
+            Operation::Move(DestOperand::Memory(ref mem), value) => {
+                if self.inline_depth == 0 {
+                    return;
+                }
+                let ctx = ctrl.ctx();
+                let value = ctrl.resolve(value);
+                let mem = ctrl.resolve_mem(mem);
+                let entry = self.current_entry;
+                if matches!(mem.size, MemAccessSize::Mem8 | MemAccessSize::Mem16) &&
+                    value.iter().any(|x| x == self.event)
+                {
+                    let (index, buffer) = mem.address();
+                    let index = index.if_arithmetic_mul_const(2).unwrap_or(index);
+                    let pos = Operand::and_masked(index).0;
+                    let ok = pos.if_memory().is_some_and(|x| x.is_global()) &&
+                        buffer > ctrl.binary().base().as_u64();
+                    if ok {
+                        self.buffer_stores.push((entry, buffer, pos));
+                        if self.increments.iter().any(|&x| x == (entry, pos)) {
+                            self.result.chat_input_handler = Some(entry);
+                            self.result.chat_input_buffer = Some(ctx.constant(buffer));
+                            ctrl.end_analysis();
+                        }
+                    }
+                } else if mem.is_global() {
+                    let dest = ctx.memory(&mem);
+                    let is_increment = Operand::and_masked(value).0
+                        .if_arithmetic_add_const(1) == Some(dest);
+                    if is_increment {
+                        self.increments.push((entry, dest));
+                        let store = self.buffer_stores.iter()
+                            .find(|x| x.0 == entry && x.2 == dest);
+                        if let Some(&(_, buffer, _)) = store {
+                            self.result.chat_input_handler = Some(entry);
+                            self.result.chat_input_buffer = Some(ctx.constant(buffer));
+                            ctrl.end_analysis();
+                        }
+                    }
+                }
+            }
+            _ => (),
+        }
+    }
+}
+
 pub(crate) fn init_statres<'e, E: ExecutionState<'e>>(
     analysis: &AnalysisCtx<'e, E>,
     functions: &FunctionFinder<'_, 'e, E>,
@@ -846,6 +1100,96 @@ impl<'a, 'e, E: ExecutionState<'e>> scarf::Analyzer<'e> for CmdIconsDdsGrp<'a, '
     }
 }
 
+pub(crate) fn draw_command_button<'e, E: ExecutionState<'e>>(
+    analysis: &AnalysisCtx<'e, E>,
+    unit_status_funcs: E::VirtualAddress,
+) -> Option<E::VirtualAddress> {
+    let ctx = analysis.ctx;
+    let binary = analysis.binary;
+
+    let offset = 0xa0 * E::VirtualAddress::SIZE * 0x3 + E::VirtualAddress::SIZE * 2;
+    let gateway_status = binary.read_address(unit_status_funcs + offset).ok()?;
+    // Same function as what button_ddsgrps uses; the command button controls
+    // get Control.user_u16 set to 0xc, and their draw func set to draw_command_button
+    // (Which then draws cmdbtns / cmdicons frames).
+    // Both writes should be in the same function, but their order isn't known.
+    let arg_cache = &analysis.arg_cache;
+    let mut analysis = FuncAnalysis::new(binary, ctx, gateway_status);
+    let mut analyzer = FindDrawCommandButton::<E> {
+        result: None,
+        arg_cache,
+        inline_depth: 0,
+        u16_param_base: None,
+        draw_func: None,
+    };
+    analysis.analyze(&mut analyzer);
+    analyzer.result
+}
+
+struct FindDrawCommandButton<'a, 'e, E: ExecutionState<'e>> {
+    result: Option<E::VirtualAddress>,
+    arg_cache: &'a ArgCache<'e, E>,
+    inline_depth: u8,
+    /// Control which had user_u16 set to 0xc in current function
+    u16_param_base: Option<Operand<'e>>,
+    /// (Control, draw func) set in current function
+    draw_func: Option<(Operand<'e>, E::VirtualAddress)>,
+}
+
+impl<'a, 'e, E: ExecutionState<'e>> scarf::Analyzer<'e> for FindDrawCommandButton<'a, 'e, E> {
+    type State = analysis::DefaultState;
+    type Exec = E;
+    fn operation(&mut self, ctrl: &mut Control<'e, '_, '_, Self>, op: &Operation<'e>) {
+        match *op {
+            Operation::Call(dest) if self.inline_depth < 5 => {
+                if let Some(dest) = ctrl.resolve_va(dest) {
+                    // Only inline when status_screen dialog is being passed to the function
+                    // as arg1
+                    if ctrl.resolve_arg(0) == self.arg_cache.on_entry(0) {
+                        let old_base = self.u16_param_base.take();
+                        let old_draw_func = self.draw_func.take();
+                        self.inline_depth += 1;
+                        ctrl.analyze_with_current_state(self, dest);
+                        self.inline_depth -= 1;
+                        self.u16_param_base = old_base;
+                        self.draw_func = old_draw_func;
+                        if self.result.is_some() {
+                            ctrl.end_analysis();
+                        }
+                    }
+                }
+            }
+            Operation::Move(DestOperand::Memory(ref mem), val) => {
+                let layouts = E::struct_layouts();
+                let val = match ctrl.resolve(val).if_constant() {
+                    Some(s) => s,
+                    None => return,
+                };
+                let dest = ctrl.resolve_mem(mem);
+                let (base, offset) = dest.address();
+                if mem.size == MemAccessSize::Mem16 && val == 0xc {
+                    if layouts.control_u16_value().iter().any(|&x| x == offset) {
+                        self.u16_param_base = Some(base);
+                    }
+                } else if mem.size == E::WORD_SIZE && val > 0x10000 {
+                    if layouts.control_draw_funcs().iter().any(|&x| x == offset) {
+                        self.draw_func = Some((base, E::VirtualAddress::from_u64(val)));
+                    }
+                }
+                if let (Some(u16_base), Some((draw_base, func))) =
+                    (self.u16_param_base, self.draw_func)
+                {
+                    if u16_base == draw_base {
+                        self.result = Some(func);
+                        ctrl.end_analysis();
+                    }
+                }
+            }
+            _ => (),
+        }
+    }
+}
+
 pub(crate) fn mouse_xy<'e, E: ExecutionState<'e>>(
     analysis: &AnalysisCtx<'e, E>,
     run_dialog: E::VirtualAddress,
@@ -2502,3 +2846,193 @@ impl<'a, 'e, E: ExecutionState<'e>> scarf::Analyzer<'e> for RunDialogChildAnalyz
         }
     }
 }
+
+pub(crate) struct GameMenuFuncs<Va: VirtualAddress> {
+    pub open_game_menu: Option<Va>,
+    pub close_game_menu: Option<Va>,
+}
+
+/// Virtual key code of F10
+const VK_F10: u32 = 0x79;
+
+pub(crate) fn game_menu_funcs<'e, E: ExecutionState<'e>>(
+    actx: &AnalysisCtx<'e, E>,
+    key_down_handler: E::VirtualAddress,
+) -> GameMenuFuncs<E::VirtualAddress> {
+    let binary = actx.binary;
+    let ctx = actx.ctx;
+    let bump = &actx.bump;
+    let mut result = GameMenuFuncs {
+        open_game_menu: None,
+        close_game_menu: None,
+    };
+    // Key down handler has (possibly after few calls)
+    // if key == VK_F10 {
+    //     if game_menu_open == 0 {
+    //         open_game_menu();
+    //     } else {
+    //         close_game_menu();
+    //     }
+    // }
+    // The key check may be a switch too.
+    // Older versions may not check for game menu being open, and only have
+    // open_game_menu() call.
+    let mut analyzer = FindGameMenuFuncs::<E> {
+        state: GameMenuState::FindF10Check,
+        result: &mut result,
+        menu_open_branches: None,
+        checked_calls: bumpvec_with_capacity(0x10, bump),
+        inline_depth: 0,
+    };
+    let mut analysis = FuncAnalysis::new(binary, ctx, key_down_handler);
+    analysis.analyze(&mut analyzer);
+    if let Some((closed, open)) = analyzer.menu_open_branches {
+        result.open_game_menu = first_call_from::<E>(actx, closed);
+        result.close_game_menu = first_call_from::<E>(actx, open);
+        if result.open_game_menu == result.close_game_menu {
+            result.close_game_menu = None;
+        }
+    }
+    result
+}
+
+fn first_call_from<'e, E: ExecutionState<'e>>(
+    actx: &AnalysisCtx<'e, E>,
+    start: E::VirtualAddress,
+) -> Option<E::VirtualAddress> {
+    let mut analyzer = FirstCall::<E> {
+        result: None,
+        phantom: Default::default(),
+    };
+    let mut analysis = FuncAnalysis::new(actx.binary, actx.ctx, start);
+    analysis.analyze(&mut analyzer);
+    analyzer.result
+}
+
+struct FirstCall<'e, E: ExecutionState<'e>> {
+    result: Option<E::VirtualAddress>,
+    phantom: std::marker::PhantomData<&'e ()>,
+}
+
+impl<'e, E: ExecutionState<'e>> analysis::Analyzer<'e> for FirstCall<'e, E> {
+    type State = analysis::DefaultState;
+    type Exec = E;
+    fn operation(&mut self, ctrl: &mut Control<'e, '_, '_, Self>, op: &Operation<'e>) {
+        if let Operation::Call(dest) = *op {
+            self.result = ctrl.resolve_va(dest);
+            ctrl.end_analysis();
+        }
+    }
+}
+
+#[derive(Copy, Clone, Eq, PartialEq)]
+enum GameMenuState {
+    /// Find `key == VK_F10` jump or switch branch, and continue from there.
+    FindF10Check,
+    /// Find either `game_menu_open == 0` jump or a call which
+    /// will be open_game_menu.
+    F10Branch,
+}
+
+struct FindGameMenuFuncs<'a, 'acx, 'e, E: ExecutionState<'e>> {
+    state: GameMenuState,
+    result: &'a mut GameMenuFuncs<E::VirtualAddress>,
+    /// (menu closed branch, menu open branch)
+    menu_open_branches: Option<(E::VirtualAddress, E::VirtualAddress)>,
+    checked_calls: BumpVec<'acx, E::VirtualAddress>,
+    inline_depth: u8,
+}
+
+impl<'a, 'acx, 'e: 'acx, E: ExecutionState<'e>> analysis::Analyzer<'e> for
+    FindGameMenuFuncs<'a, 'acx, 'e, E>
+{
+    type State = analysis::DefaultState;
+    type Exec = E;
+    fn operation(&mut self, ctrl: &mut Control<'e, '_, '_, Self>, op: &Operation<'e>) {
+        let ctx = ctrl.ctx();
+        match self.state {
+            GameMenuState::FindF10Check => match *op {
+                Operation::Call(dest) => {
+                    if self.inline_depth < 2 {
+                        if let Some(dest) = ctrl.resolve_va(dest) {
+                            if !self.checked_calls.iter().any(|&x| x == dest) {
+                                self.checked_calls.push(dest);
+                                self.inline_depth += 1;
+                                ctrl.analyze_with_current_state(self, dest);
+                                self.inline_depth -= 1;
+                                if self.state != GameMenuState::FindF10Check {
+                                    ctrl.end_analysis();
+                                }
+                            }
+                        }
+                    }
+                }
+                Operation::Jump { condition, to } => {
+                    let to = ctrl.resolve(to);
+                    if to.if_constant().is_some() {
+                        let condition = ctrl.resolve(condition);
+                        let f10_check = condition.if_arithmetic_eq_neq()
+                            .and_then(|(l, r, is_eq)| {
+                                Some((l, r))
+                                    .and_either_other(|x| {
+                                        x.if_constant().filter(|&c| c == VK_F10 as u64)
+                                    })
+                                    .filter(|x| x.if_constant().is_none())?;
+                                Some(is_eq)
+                            });
+                        if let Some(is_eq) = f10_check {
+                            self.state = GameMenuState::F10Branch;
+                            ctrl.continue_at_eq_address(is_eq, to);
+                        }
+                    } else if let Some(switch) = CompleteSwitch::new(to, ctx, ctrl.exec_state()) {
+                        let index = match switch.index_operand(ctx) {
+                            Some(s) => Operand::and_masked(s).0,
+                            None => return,
+                        };
+                        let branch = match index.if_arithmetic_sub() {
+                            Some((_, r)) => r.if_constant()
+                                .map(|c| VK_F10.wrapping_sub(c as u32)),
+                            None => match index.if_arithmetic_add() {
+                                Some((_, r)) => r.if_constant()
+                                    .map(|c| VK_F10.wrapping_add(c as u32)),
+                                None => Some(VK_F10),
+                            },
+                        };
+                        let binary = ctrl.binary();
+                        let dest = branch.and_then(|x| switch.branch(binary, ctx, x));
+                        if let Some(dest) = dest {
+                            self.state = GameMenuState::F10Branch;
+                            ctrl.end_branch();
+                            ctrl.add_branch_with_current_state(dest);
+                        }
+                    }
+                }
+                _ => (),
+            },
+            GameMenuState::F10Branch => match *op {
+                Operation::Call(dest) => {
+                    if let Some(dest) = ctrl.resolve_va(dest) {
+                        self.result.open_game_menu = Some(dest);
+                    }
+                    ctrl.end_analysis();
+                }
+                Operation::Jump { condition, to } => {
+                    let condition = ctrl.resolve(condition);
+                    let zero_check = condition.if_arithmetic_eq_neq_zero(ctx)
+                        .filter(|x| x.0.if_constant().is_none());
+                    if let Some((_, is_eq)) = zero_check {
+                        if let Some(to) = ctrl.resolve_va(to) {
+                            let next = ctrl.current_instruction_end();
+                            self.menu_open_branches = match is_eq {
+                                true => Some((to, next)),
+                                false => Some((next, to)),
+                            };
+                        }
+                        ctrl.end_analysis();
+                    }
+                }
+                _ => (),
+            },
+        }
+    }
+}
@@ -69,6 +69,10 @@ pub fn dump<'e, E: ExecutionState<'e>>(
         let val = analysis.bnet_message_switch_op();
         out!(&mut out, "bnet_message_switch: {}", format_op_operand(val));
     }
+    if check_filter(&filter, "process_commands_switch") {
+        let val = analysis.process_commands_switch_op();
+        out!(&mut out, "process_commands_switch: {}", format_op_operand(val));
+    }
 
     if filter.is_none() {
         let mut firegraft = (*analysis.firegraft_addresses()).clone();
@@ -164,9 +168,64 @@ pub fn dump<'e, E: ExecutionState<'e>>(
             &mut out, "anim_struct_size: {:?}",
             anim_struct_size.map(|x| format!("0x{:x}", x)),
         );
+        out!(
+            &mut out, "ai_region_struct_size: {:?}",
+            analysis.ai_region_struct_size().map(|x| format!("0x{:x}", x)),
+        );
+        out!(
+            &mut out, "ai_region_state_offset: {:?}",
+            analysis.ai_region_state_offset().map(|x| format!("0x{:x}", x)),
+        );
+        out!(
+            &mut out, "unit_build_queue_offset: {:?}",
+            analysis.unit_build_queue_offset().map(|x| format!("0x{:x}", x)),
+        );
+        out!(
+            &mut out, "unit_energy_offset: {:?}",
+            analysis.unit_energy_offset().map(|x| format!("0x{:x}", x)),
+        );
+        out!(
+            &mut out, "unit_remaining_build_time_offset: {:?}",
+            analysis.unit_remaining_build_time_offset().map(|x| format!("0x{:x}", x)),
+        );
+        out!(
+            &mut out, "unit_addon_offset: {:?}",
+            analysis.unit_addon_offset().map(|x| format!("0x{:x}", x)),
+        );
+        out!(
+            &mut out, "unit_rank_offset: {:?}",
+            analysis.unit_rank_offset().map(|x| format!("0x{:x}", x)),
+        );
+        out!(&mut out, "unit_status_timer_offsets: {:?}", analysis.unit_status_timer_offsets());
+        out!(
+            &mut out, "chk_section_handler_count: {:?}",
+            analysis.chk_section_handler_count(),
+        );
+        out!(
+            &mut out, "location_struct_size: {:?}",
+            analysis.location_struct_size().map(|x| format!("0x{:x}", x)),
+        );
+        out!(
+            &mut out, "death_counters_row_size: {:?}",
+            analysis.death_counters_row_size().map(|x| format!("0x{:x}", x)),
+        );
+        out!(
+            &mut out, "max_selection_count: {:?}",
+            analysis.max_selection_count(),
+        );
+        out!(
+            &mut out, "selection_array_stride: {:?}",
+            analysis.selection_array_stride().map(|x| format!("0x{:x}", x)),
+        );
+        let unit_rally_offset = analysis.unit_rally_offset();
+        out!(
+            &mut out, "unit_rally_offset: {:?}",
+            unit_rally_offset.map(|x| format!("0x{:x}", x)),
+        );
 
         let limits = analysis.limits();
         out!(&mut out, "set_limits: {:?}", limits.set_limits);
+        out!(&mut out, "unit_limits: {:?}", analysis.unit_limits());
         for (i, arr) in limits.arrays.iter().enumerate() {
             let name = match i {
                 0 => "images".into(),
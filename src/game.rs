@@ -34,6 +34,35 @@ pub struct Limits<'e, Va: VirtualAddress> {
     pub smem_alloc: Option<Va>,
     pub smem_free: Option<Va>,
     pub allocator: Option<Operand<'e>>,
+    /// Values of the limit struct passed to set_limits, in same order as `arrays`.
+    /// None if the value wasn't a constant.
+    pub values: [Option<u32>; 7],
+}
+
+/// `Limits::values` with names.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct UnitLimits {
+    pub images: Option<u32>,
+    pub sprites: Option<u32>,
+    pub lone_sprites: Option<u32>,
+    pub units: Option<u32>,
+    pub bullets: Option<u32>,
+    pub orders: Option<u32>,
+    pub fow_sprites: Option<u32>,
+}
+
+impl UnitLimits {
+    pub fn from_values(values: &[Option<u32>; 7]) -> UnitLimits {
+        UnitLimits {
+            images: values[0],
+            sprites: values[1],
+            lone_sprites: values[2],
+            units: values[3],
+            bullets: values[4],
+            orders: values[5],
+            fow_sprites: values[6],
+        }
+    }
 }
 
 pub(crate) struct StepObjectsAnalysis<'e, Va: VirtualAddress> {
@@ -426,6 +455,7 @@ pub(crate) fn limits<'e, E: ExecutionState<'e>>(
         smem_alloc: None,
         smem_free: None,
         allocator: None,
+        values: [None; 7],
     };
 
     let binary = actx.binary;
@@ -597,6 +627,12 @@ impl<'a, 'acx, 'e: 'a + 'acx, E: ExecutionState<'e>> analysis::Analyzer<'e> for
                             self.result.arrays.len() >= 4;
                         if ok {
                             self.result.set_limits = Some(dest);
+                            for i in 0..7 {
+                                let mem = ctx.mem_access(arg1, i * 4, MemAccessSize::Mem32);
+                                self.result.values[i as usize] = ctrl.read_memory(&mem)
+                                    .if_constant()
+                                    .map(|x| x as u32);
+                            }
                             for arr in &mut self.result.arrays {
                                 arr.sort_unstable();
                                 arr.dedup();
@@ -64,6 +64,33 @@ pub struct SinglePlayerStart<'e, Va: VirtualAddress> {
     pub skins_size: u32,
 }
 
+/// Offsets of lobby settings in GameData.
+///
+/// GameData is sent between players when joining a game, so its layout is the same
+/// in all versions. Player slots aren't part of GameData; they are in `players`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct GameDataOffsets {
+    /// `[u8; 0x18]`
+    pub game_name: u16,
+    /// u8
+    pub active_human_players: u16,
+    /// u8
+    pub max_players: u16,
+    /// u8
+    pub game_speed: u16,
+    /// `[u8; 0x19]`
+    pub host_name: u16,
+    /// `[u8; 0x20]`
+    pub map_title: u16,
+    /// u16, start of the game template.
+    pub game_type: u16,
+}
+
+pub struct InitObserver<'e, Va: VirtualAddress> {
+    pub init_observer: Option<Va>,
+    pub is_observer: Option<Operand<'e>>,
+}
+
 pub struct SelectMapEntry<'e, Va: VirtualAddress> {
     pub select_map_entry: Option<Va>,
     pub is_multiplayer: Option<Operand<'e>>,
@@ -81,6 +108,16 @@ pub(crate) struct InitMapFromPath<Va: VirtualAddress> {
     pub map_init_chk_callbacks: Va,
 }
 
+pub(crate) struct PlaySmkAnalysis<'e, Va: VirtualAddress> {
+    pub stop_smk: Option<Va>,
+    pub current_smk_context: Option<Operand<'e>>,
+}
+
+pub(crate) struct LoadMapStrings<'e, Va: VirtualAddress> {
+    pub load_map_strings: Option<Va>,
+    pub map_string_table: Option<Operand<'e>>,
+}
+
 pub(crate) struct LoadImagesAnalysis<'e, Va: VirtualAddress> {
     pub open_anim_single_file: Option<Va>,
     pub open_anim_multi_file: Option<Va>,
@@ -148,6 +185,11 @@ pub(crate) struct SinglePlayerMapEndAnalysis<'e, Va: VirtualAddress> {
     pub current_campaign_mission: Option<Operand<'e>>,
 }
 
+pub(crate) struct BlitPcxToSurface<'e, Va: VirtualAddress> {
+    pub blit_pcx_to_surface: Option<Va>,
+    pub pcx_target_surface: Option<Operand<'e>>,
+}
+
 pub(crate) struct InitMapFromPathAnalysis<'e, Va: VirtualAddress> {
     pub read_whole_mpq_file: Option<Va>,
     pub read_whole_mpq_file2: Option<Va>,
@@ -272,6 +314,128 @@ impl<'a, 'e, E: ExecutionState<'e>> analysis::Analyzer<'e> for IsPlaySmk<'a, 'e,
     }
 }
 
+pub(crate) fn analyze_play_smk<'e, E: ExecutionState<'e>>(
+    analysis: &AnalysisCtx<'e, E>,
+    play_smk: E::VirtualAddress,
+) -> PlaySmkAnalysis<'e, E::VirtualAddress> {
+    let mut result = PlaySmkAnalysis {
+        stop_smk: None,
+        current_smk_context: None,
+    };
+    let binary = analysis.binary;
+    let ctx = analysis.ctx;
+    let mut analyzer = PlaySmkAnalyzer::<E> {
+        result: &mut result,
+        state: PlaySmkState::FindContext,
+        context_value: None,
+        verify_limit: 0,
+    };
+    let mut analysis = FuncAnalysis::new(binary, ctx, play_smk);
+    analysis.analyze(&mut analyzer);
+    result
+}
+
+struct PlaySmkAnalyzer<'a, 'e, E: ExecutionState<'e>> {
+    result: &'a mut PlaySmkAnalysis<'e, E::VirtualAddress>,
+    state: PlaySmkState,
+    /// Value that was written to current_smk_context
+    context_value: Option<Operand<'e>>,
+    verify_limit: u8,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+enum PlaySmkState {
+    /// Find first non-constant store to a global, current_smk_context = create_video(...)
+    FindContext,
+    /// Check calls with this or a1 = current_smk_context
+    FindStopSmk,
+    /// stop_smk should clear current_smk_context
+    VerifyStopSmk,
+}
+
+impl<'a, 'e, E: ExecutionState<'e>> analysis::Analyzer<'e> for PlaySmkAnalyzer<'a, 'e, E> {
+    type State = analysis::DefaultState;
+    type Exec = E;
+    fn operation(&mut self, ctrl: &mut Control<'e, '_, '_, Self>, op: &Operation<'e>) {
+        match self.state {
+            PlaySmkState::FindContext => {
+                if let Operation::Move(DestOperand::Memory(ref mem), value) = *op {
+                    if mem.size != E::WORD_SIZE {
+                        return;
+                    }
+                    let mem = ctrl.resolve_mem(mem);
+                    if mem.if_constant_address().is_none() {
+                        return;
+                    }
+                    let value = ctrl.resolve(value);
+                    if value.if_constant().is_some() {
+                        return;
+                    }
+                    let ctx = ctrl.ctx();
+                    self.result.current_smk_context = Some(ctx.memory(&mem));
+                    self.context_value = Some(value);
+                    self.state = PlaySmkState::FindStopSmk;
+                }
+            }
+            PlaySmkState::FindStopSmk => {
+                if let Operation::Call(dest) = *op {
+                    let (context, value) =
+                        match (self.result.current_smk_context, self.context_value) {
+                            (Some(a), Some(b)) => (a, b),
+                            _ => return,
+                        };
+                    let this = ctrl.resolve_register(1);
+                    let arg1 = ctrl.resolve_arg(0);
+                    let is_context = |x: Operand<'e>| x == context || x == value;
+                    if !is_context(this) && !is_context(arg1) {
+                        return;
+                    }
+                    if let Some(dest) = ctrl.resolve_va(dest) {
+                        self.state = PlaySmkState::VerifyStopSmk;
+                        self.verify_limit = 16;
+                        ctrl.analyze_with_current_state(self, dest);
+                        if self.result.stop_smk.is_some() {
+                            self.result.stop_smk = Some(dest);
+                            ctrl.end_analysis();
+                        } else {
+                            self.state = PlaySmkState::FindStopSmk;
+                        }
+                    }
+                }
+            }
+            PlaySmkState::VerifyStopSmk => {
+                match *op {
+                    Operation::Call(..) | Operation::Jump { .. } => {
+                        if self.verify_limit == 0 {
+                            ctrl.end_analysis();
+                            return;
+                        }
+                        self.verify_limit -= 1;
+                    }
+                    Operation::Move(DestOperand::Memory(ref mem), value) => {
+                        if mem.size != E::WORD_SIZE {
+                            return;
+                        }
+                        let context = match self.result.current_smk_context {
+                            Some(s) => s,
+                            None => return,
+                        };
+                        let mem = ctrl.resolve_mem(mem);
+                        let ctx = ctrl.ctx();
+                        if ctx.memory(&mem) == context &&
+                            ctrl.resolve(value) == ctx.const_0()
+                        {
+                            self.result.stop_smk = Some(E::VirtualAddress::from_u64(0));
+                            ctrl.end_analysis();
+                        }
+                    }
+                    _ => (),
+                }
+            }
+        }
+    }
+}
+
 pub(crate) fn game_init<'e, E: ExecutionState<'e>>(
     analysis: &AnalysisCtx<'e, E>,
     play_smk: E::VirtualAddress,
@@ -1093,7 +1257,7 @@ impl<'a, 'acx, 'e: 'acx, E: ExecutionState<'e>> analysis::Analyzer<'e> for
                     // Check for memcpy(&mut game_data, arg1, 0x8d) call
                     // Maybe broken since 1232e at least uses rep movs
                     let arg3 = ctrl.resolve_arg(2);
-                    if arg3.if_constant() == Some(0x8d) {
+                    if arg3.if_constant() == Some(E::struct_layouts().game_data_size()) {
                         let arg2 = ctrl.resolve_arg(1);
                         if arg2 == self.arg_cache.on_entry(0) {
                             let arg1 = ctrl.resolve_arg(0);
@@ -1158,7 +1322,7 @@ impl<'a, 'acx, 'e: 'acx, E: ExecutionState<'e>> analysis::Analyzer<'e> for
                         let len = ctrl.resolve_register(1);
                         let from = ctrl.resolve_register(6);
                         let dest = ctrl.resolve_register(7);
-                        if len.if_constant() == Some(0x23) {
+                        if len.if_constant() == Some(E::struct_layouts().game_data_size() / 4) {
                             if from == self.arg_cache.on_entry(0) {
                                 self.result.game_data = Some(dest);
                             }
@@ -1770,6 +1934,93 @@ pub(crate) fn init_game_network<'e, E: ExecutionState<'e>>(
     result
 }
 
+pub(crate) fn init_observer<'e, E: ExecutionState<'e>>(
+    actx: &AnalysisCtx<'e, E>,
+    init_game_network: E::VirtualAddress,
+    local_storm_player: Operand<'e>,
+) -> InitObserver<'e, E::VirtualAddress> {
+    let binary = actx.binary;
+    let ctx = actx.ctx;
+    let mut result = InitObserver {
+        init_observer: None,
+        is_observer: None,
+    };
+    // init_game_network calls init_observer, which checks the slot of local player
+    // and sets is_observer flag if the local player isn't playing:
+    // if players[local_storm_player].type == OBSERVER {
+    //     is_observer = 1;
+    //     ...
+    // }
+    // Find the first function called from init_game_network that does a check
+    // indexed by local_storm_player and then writes 1 to a global byte.
+    let mut analyzer = FindInitObserver::<E> {
+        result: &mut result,
+        local_storm_player,
+        inline_depth: 0,
+        player_check_seen: false,
+    };
+    let mut analysis = FuncAnalysis::new(binary, ctx, init_game_network);
+    analysis.analyze(&mut analyzer);
+    result
+}
+
+struct FindInitObserver<'a, 'e, E: ExecutionState<'e>> {
+    result: &'a mut InitObserver<'e, E::VirtualAddress>,
+    local_storm_player: Operand<'e>,
+    inline_depth: u8,
+    player_check_seen: bool,
+}
+
+impl<'a, 'e, E: ExecutionState<'e>> analysis::Analyzer<'e> for FindInitObserver<'a, 'e, E> {
+    type State = analysis::DefaultState;
+    type Exec = E;
+    fn operation(&mut self, ctrl: &mut Control<'e, '_, '_, Self>, op: &Operation<'e>) {
+        match *op {
+            Operation::Call(dest) => {
+                if self.inline_depth == 0 {
+                    if let Some(dest) = ctrl.resolve_va(dest) {
+                        self.inline_depth = 1;
+                        self.player_check_seen = false;
+                        ctrl.analyze_with_current_state(self, dest);
+                        self.inline_depth = 0;
+                        if self.result.is_observer.is_some() {
+                            self.result.init_observer = Some(dest);
+                            ctrl.end_analysis();
+                        }
+                    }
+                }
+            }
+            Operation::Jump { condition, .. } => {
+                if self.inline_depth != 0 && !self.player_check_seen {
+                    let condition = ctrl.resolve(condition);
+                    let local_storm_player = self.local_storm_player;
+                    // Skip plain `local_storm_player == 0` checks,
+                    // only accept ones that index a player array.
+                    self.player_check_seen = condition.iter().any(|x| {
+                        x.if_memory()
+                            .filter(|mem| mem.address().0.iter().any(|y| y == local_storm_player))
+                            .is_some()
+                    });
+                }
+            }
+            Operation::Move(DestOperand::Memory(ref mem), value) => {
+                if self.inline_depth != 0 && self.player_check_seen {
+                    let value = ctrl.resolve(value);
+                    if mem.size == MemAccessSize::Mem8 && value.if_constant() == Some(1) {
+                        let mem = ctrl.resolve_mem(mem);
+                        if mem.if_constant_address().is_some() {
+                            let ctx = ctrl.ctx();
+                            self.result.is_observer = Some(ctx.memory(&mem));
+                            ctrl.end_analysis();
+                        }
+                    }
+                }
+            }
+            _ => (),
+        }
+    }
+}
+
 struct FindInitGameNetwork<'e, E: ExecutionState<'e>> {
     result: Option<E::VirtualAddress>,
     local_storm_player: Operand<'e>,
@@ -2560,26 +2811,121 @@ impl<'a, 'e, E: ExecutionState<'e>> analysis::Analyzer<'e> for FindSnetInitProvi
     }
 }
 
-pub(crate) fn chk_init_players<'e, E: ExecutionState<'e>>(
+pub(crate) fn chk_section_handler_count<'e, E: ExecutionState<'e>>(
     analysis: &AnalysisCtx<'e, E>,
     chk_callbacks: E::VirtualAddress,
-) -> Option<Operand<'e>> {
+) -> Option<u16> {
     let binary = analysis.binary;
-    let ctx = analysis.ctx;
-    let mut ownr_callback = None;
+    let struct_size = if E::WORD_SIZE == MemAccessSize::Mem32 { 0xc } else { 0x10 };
+    for i in 0..0x100 {
+        let section_id = binary.read_u32(chk_callbacks + i * struct_size).ok()?;
+        if section_id == 0 {
+            return Some(i as u16);
+        }
+    }
+    None
+}
+
+fn chk_section_callback<'e, E: ExecutionState<'e>>(
+    binary: &'e BinaryFile<E::VirtualAddress>,
+    chk_callbacks: E::VirtualAddress,
+    section: &[u8; 4],
+) -> Option<E::VirtualAddress> {
+    let struct_size = if E::WORD_SIZE == MemAccessSize::Mem32 { 0xc } else { 0x10 };
     for i in 0.. {
-        let struct_size = if E::WORD_SIZE == MemAccessSize::Mem32 { 0xc } else { 0x10 };
         let section_id = binary.read_u32(chk_callbacks + i * struct_size).ok()?;
         if section_id == 0 {
             break;
         }
-        if section_id == 0x524e574f {
-            let callback = binary.read_address(chk_callbacks + i * struct_size + 4).ok()?;
-            ownr_callback = Some(callback);
-            break;
+        if section_id == u32::from_le_bytes(*section) {
+            return binary.read_address(chk_callbacks + i * struct_size + 4).ok();
         }
     }
-    let ownr_callback = ownr_callback?;
+    None
+}
+
+/// Finds the STR section callback, and the global that it sets to the loaded
+/// string section data.
+pub(crate) fn load_map_strings<'e, E: ExecutionState<'e>>(
+    analysis: &AnalysisCtx<'e, E>,
+    chk_callbacks: E::VirtualAddress,
+) -> LoadMapStrings<'e, E::VirtualAddress> {
+    let mut result = LoadMapStrings {
+        load_map_strings: None,
+        map_string_table: None,
+    };
+    let binary = analysis.binary;
+    let ctx = analysis.ctx;
+    let str_callback = match chk_section_callback::<E>(binary, chk_callbacks, b"STR\x20") {
+        Some(s) => s,
+        None => return result,
+    };
+    result.load_map_strings = Some(str_callback);
+
+    let mut analyzer = FindMapStringTable::<E> {
+        result: None,
+        phantom: Default::default(),
+        inlining: false,
+    };
+    let mut analysis = FuncAnalysis::new(binary, ctx, str_callback);
+    analysis.analyze(&mut analyzer);
+    result.map_string_table = analyzer.result;
+    result
+}
+
+struct FindMapStringTable<'e, E: ExecutionState<'e>> {
+    result: Option<Operand<'e>>,
+    phantom: std::marker::PhantomData<(*const E, &'e ())>,
+    inlining: bool,
+}
+
+impl<'e, E: ExecutionState<'e>> analysis::Analyzer<'e> for FindMapStringTable<'e, E> {
+    type State = analysis::DefaultState;
+    type Exec = E;
+    fn operation(&mut self, ctrl: &mut Control<'e, '_, '_, Self>, op: &Operation<'e>) {
+        // Check for first non-constant store of word-sized global,
+        // map_string_table = SMemAlloc(section_size) or similar.
+        // (The old table may be freed and cleared before that)
+        match *op {
+            Operation::Move(DestOperand::Memory(ref dest), val) => {
+                if dest.size == E::WORD_SIZE {
+                    let mem = ctrl.resolve_mem(dest);
+                    if mem.if_constant_address().is_some() {
+                        let val = ctrl.resolve(val);
+                        if val.if_constant().is_none() {
+                            let ctx = ctrl.ctx();
+                            self.result = Some(ctx.memory(&mem));
+                            ctrl.end_analysis();
+                        }
+                    }
+                }
+            }
+            Operation::Call(dest) => {
+                if !self.inlining {
+                    if let Some(dest) = ctrl.resolve_va(dest) {
+                        self.inlining = true;
+                        ctrl.inline(self, dest);
+                        self.inlining = false;
+                        if self.result.is_some() {
+                            ctrl.end_analysis();
+                        } else {
+                            ctrl.skip_operation();
+                        }
+                    }
+                }
+            }
+            _ => (),
+        }
+    }
+}
+
+pub(crate) fn chk_init_players<'e, E: ExecutionState<'e>>(
+    analysis: &AnalysisCtx<'e, E>,
+    chk_callbacks: E::VirtualAddress,
+) -> Option<Operand<'e>> {
+    let binary = analysis.binary;
+    let ctx = analysis.ctx;
+    let ownr_callback = chk_section_callback::<E>(binary, chk_callbacks, b"OWNR")?;
 
     let mut analyzer = FindChkInitPlayer::<E> {
         result: None,
@@ -2634,6 +2980,96 @@ impl<'e, E: ExecutionState<'e>> analysis::Analyzer<'e> for FindChkInitPlayer<'e,
     }
 }
 
+pub(crate) fn place_map_sprite<'e, E: ExecutionState<'e>>(
+    analysis: &AnalysisCtx<'e, E>,
+    chk_callbacks: E::VirtualAddress,
+) -> Option<E::VirtualAddress> {
+    let binary = analysis.binary;
+    let ctx = analysis.ctx;
+    let thg2_callback = chk_section_callback::<E>(binary, chk_callbacks, b"THG2")?;
+
+    let mut analyzer = FindPlaceMapSprite::<E> {
+        result: None,
+        phantom: Default::default(),
+        inlining: false,
+    };
+    let mut analysis = FuncAnalysis::new(binary, ctx, thg2_callback);
+    analysis.analyze(&mut analyzer);
+    analyzer.result
+}
+
+struct FindPlaceMapSprite<'e, E: ExecutionState<'e>> {
+    result: Option<E::VirtualAddress>,
+    phantom: std::marker::PhantomData<(*const E, &'e ())>,
+    inlining: bool,
+}
+
+impl<'e, E: ExecutionState<'e>> analysis::Analyzer<'e> for FindPlaceMapSprite<'e, E> {
+    type State = analysis::DefaultState;
+    type Exec = E;
+    fn operation(&mut self, ctrl: &mut Control<'e, '_, '_, Self>, op: &Operation<'e>) {
+        // THG2 entries are { u16 id, u16 x, u16 y, u8 player, u8 unk, u16 flags },
+        // with flags & 0x1000 meaning that the entry is a sprite instead of an unit.
+        // Follow only the sprite branch, and find
+        // place_map_sprite(Mem16[entry], Mem16[entry + 2], Mem16[entry + 4], ...)
+        // (The callback may also do the loop in a child function)
+        match *op {
+            Operation::Jump { condition, to } => {
+                let ctx = ctrl.ctx();
+                let condition = ctrl.resolve(condition);
+                let sprite_flag_check = condition.if_arithmetic_eq_neq_zero(ctx)
+                    .filter(|x| {
+                        let mem16_ok = x.0.if_arithmetic_and_const(0x1000)
+                            .and_then(|x| x.if_mem16())
+                            .is_some();
+                        let mem8_ok = x.0.if_arithmetic_and_const(0x10)
+                            .and_then(|x| x.if_mem8())
+                            .is_some();
+                        mem16_ok || mem8_ok
+                    })
+                    .map(|x| x.1);
+                if let Some(jump_if_not_sprite) = sprite_flag_check {
+                    let sprite_branch = match jump_if_not_sprite {
+                        true => ctrl.current_instruction_end(),
+                        false => match ctrl.resolve_va(to) {
+                            Some(s) => s,
+                            None => return,
+                        },
+                    };
+                    ctrl.end_branch();
+                    ctrl.add_branch_with_current_state(sprite_branch);
+                }
+            }
+            Operation::Call(dest) => {
+                if let Some(dest) = ctrl.resolve_va(dest) {
+                    let x = ctrl.resolve_arg(1).unwrap_and_mask().if_mem16();
+                    let y = ctrl.resolve_arg(2).unwrap_and_mask().if_mem16();
+                    if let (Some(x), Some(y)) = (x, y) {
+                        let (x_base, x_offset) = x.address();
+                        let (y_base, y_offset) = y.address();
+                        if x_base == y_base && x_offset.wrapping_add(2) == y_offset {
+                            self.result = Some(dest);
+                            ctrl.end_analysis();
+                            return;
+                        }
+                    }
+                    if !self.inlining {
+                        self.inlining = true;
+                        ctrl.inline(self, dest);
+                        self.inlining = false;
+                        if self.result.is_some() {
+                            ctrl.end_analysis();
+                        } else {
+                            ctrl.skip_operation();
+                        }
+                    }
+                }
+            }
+            _ => (),
+        }
+    }
+}
+
 pub(crate) fn original_chk_player_types<'e, E: ExecutionState<'e>>(
     analysis: &AnalysisCtx<'e, E>,
     chk_init_players: Operand<'e>,
@@ -4485,6 +4921,78 @@ pub(crate) fn single_player_map_end<'e, E: ExecutionState<'e>>(
     result
 }
 
+pub(crate) fn check_game_over<'e, E: ExecutionState<'e>>(
+    actx: &AnalysisCtx<'e, E>,
+    step_game_logic: E::VirtualAddress,
+    local_game_result: Operand<'e>,
+) -> Option<E::VirtualAddress> {
+    let binary = actx.binary;
+    let ctx = actx.ctx;
+    let bump = &actx.bump;
+    let local_game_result = local_game_result.if_memory()?;
+
+    // step_game_logic calls check_game_over() after triggers have been run,
+    // which goes through players' victory / defeat state and ends the game by
+    // writing local_game_result once the local player has won or lost.
+    // Take the innermost function (up to depth 3) that writes to local_game_result.
+    let mut analyzer = FindCheckGameOver::<E> {
+        result: None,
+        found_write: false,
+        local_game_result,
+        inline_depth: 0,
+        checked_calls: bumpvec_with_capacity(0x20, bump),
+    };
+    let mut analysis = FuncAnalysis::new(binary, ctx, step_game_logic);
+    analysis.analyze(&mut analyzer);
+    analyzer.result
+}
+
+struct FindCheckGameOver<'acx, 'e, E: ExecutionState<'e>> {
+    result: Option<E::VirtualAddress>,
+    /// Set once the local_game_result write has been seen; result is then the innermost
+    /// function that contained it.
+    found_write: bool,
+    local_game_result: &'e MemAccess<'e>,
+    inline_depth: u8,
+    checked_calls: BumpVec<'acx, E::VirtualAddress>,
+}
+
+impl<'acx, 'e: 'acx, E: ExecutionState<'e>> analysis::Analyzer<'e> for
+    FindCheckGameOver<'acx, 'e, E>
+{
+    type State = analysis::DefaultState;
+    type Exec = E;
+    fn operation(&mut self, ctrl: &mut Control<'e, '_, '_, Self>, op: &Operation<'e>) {
+        match *op {
+            Operation::Call(dest) if self.inline_depth < 3 => {
+                if let Some(dest) = ctrl.resolve_va(dest) {
+                    if self.checked_calls.iter().any(|&x| x == dest) {
+                        return;
+                    }
+                    self.checked_calls.push(dest);
+                    self.inline_depth += 1;
+                    ctrl.analyze_with_current_state(self, dest);
+                    self.inline_depth -= 1;
+                    if self.found_write {
+                        if self.result.is_none() {
+                            self.result = Some(dest);
+                        }
+                        ctrl.end_analysis();
+                    }
+                }
+            }
+            Operation::Move(DestOperand::Memory(ref mem), _) if self.inline_depth != 0 => {
+                let mem = ctrl.resolve_mem(mem);
+                if mem.address() == self.local_game_result.address() {
+                    self.found_write = true;
+                    ctrl.end_analysis();
+                }
+            }
+            _ => (),
+        }
+    }
+}
+
 struct FindSpMapEnd<'a, 'e, E: ExecutionState<'e>> {
     result: &'a mut SinglePlayerMapEnd<'e, E::VirtualAddress>,
     is_multiplayer: Operand<'e>,
@@ -5198,6 +5706,267 @@ impl<'a, 'e, E: ExecutionState<'e>> analysis::Analyzer<'e> for CheckReadWholeMpq
     }
 }
 
+pub(crate) fn mount_archive<'e, E: ExecutionState<'e>>(
+    actx: &AnalysisCtx<'e, E>,
+    open_map_mpq: E::VirtualAddress,
+) -> Option<E::VirtualAddress> {
+    let binary = actx.binary;
+    let ctx = actx.ctx;
+    let arg_cache = &actx.arg_cache;
+    // open_map_mpq(path, _, _, is_campaign, _, _, &map_mpq) ends up calling
+    //      SFileOpenArchive(path, priority, flags, &map_mpq)
+    // possibly through a wrapper; find the first call passing a7 as its a4.
+    let mut analyzer = FindMountArchive::<E> {
+        result: None,
+        out_handle: arg_cache.on_entry(6),
+        arg_cache,
+        inline_depth: 0,
+    };
+    let mut analysis = FuncAnalysis::new(binary, ctx, open_map_mpq);
+    analysis.analyze(&mut analyzer);
+    analyzer.result
+}
+
+struct FindMountArchive<'a, 'e, E: ExecutionState<'e>> {
+    result: Option<E::VirtualAddress>,
+    out_handle: Operand<'e>,
+    arg_cache: &'a ArgCache<'e, E>,
+    inline_depth: u8,
+}
+
+impl<'a, 'e, E: ExecutionState<'e>> analysis::Analyzer<'e> for FindMountArchive<'a, 'e, E> {
+    type State = analysis::DefaultState;
+    type Exec = E;
+    fn operation(&mut self, ctrl: &mut Control<'e, '_, '_, Self>, op: &Operation<'e>) {
+        if let Operation::Call(dest) = *op {
+            let dest = match ctrl.resolve_va(dest) {
+                Some(s) => s,
+                None => return,
+            };
+            if ctrl.resolve_arg(3) == self.out_handle {
+                self.result = Some(dest);
+                ctrl.end_analysis();
+                return;
+            }
+            if self.inline_depth >= 2 {
+                return;
+            }
+            // Wrapper taking the handle pointer at another position,
+            // follow it with the handle's position in the wrapper.
+            if let Some(pos) = (0..8).find(|&i| ctrl.resolve_arg(i) == self.out_handle) {
+                let old_handle = self.out_handle;
+                self.out_handle = self.arg_cache.on_entry(pos);
+                self.inline_depth += 1;
+                let binary = ctrl.binary();
+                let ctx = ctrl.ctx();
+                let mut analysis = FuncAnalysis::new(binary, ctx, dest);
+                analysis.analyze(self);
+                self.inline_depth -= 1;
+                self.out_handle = old_handle;
+                if self.result.is_some() {
+                    ctrl.end_analysis();
+                }
+            }
+        }
+    }
+}
+
+pub(crate) fn blit_pcx_to_surface<'e, E: ExecutionState<'e>>(
+    actx: &AnalysisCtx<'e, E>,
+    load_pcx: E::VirtualAddress,
+    functions: &FunctionFinder<'_, 'e, E>,
+) -> BlitPcxToSurface<'e, E::VirtualAddress> {
+    let mut result = BlitPcxToSurface {
+        blit_pcx_to_surface: None,
+        pcx_target_surface: None,
+    };
+    let binary = actx.binary;
+    let ctx = actx.ctx;
+    let arg_cache = &actx.arg_cache;
+    let funcs = functions.functions();
+    // blit_pcx_to_surface(surface, path) loads the pcx directly to surface pixels:
+    //      load_pcx(path, _, surface.data, surface.w * surface.h, ..)
+    // so find a load_pcx caller whose arg3 is read from one of the caller's arguments,
+    // then a caller of that which passes a global as the surface argument.
+    let mut surface_arg = None;
+    let callers = functions.find_callers(actx, load_pcx);
+    for caller in callers {
+        let new = entry_of_until(binary, &funcs, caller, |entry| {
+            let mut analyzer = IsBlitPcxToSurface::<E> {
+                result: EntryOf::Retry,
+                load_pcx,
+                arg_cache,
+            };
+            let mut analysis = FuncAnalysis::new(binary, ctx, entry);
+            analysis.analyze(&mut analyzer);
+            analyzer.result
+        }).into_option_with_entry();
+        if let Some((func, arg)) = new {
+            result.blit_pcx_to_surface = Some(func);
+            surface_arg = Some(arg);
+            break;
+        }
+    }
+    let (blit, surface_arg) = match (result.blit_pcx_to_surface, surface_arg) {
+        (Some(a), Some(b)) => (a, b),
+        _ => return result,
+    };
+    let callers = functions.find_callers(actx, blit);
+    for caller in callers {
+        let new = entry_of_until(binary, &funcs, caller, |entry| {
+            let mut analyzer = FindPcxTargetSurface::<E> {
+                result: EntryOf::Retry,
+                blit,
+                surface_arg,
+            };
+            let mut analysis = FuncAnalysis::new(binary, ctx, entry);
+            analysis.analyze(&mut analyzer);
+            analyzer.result
+        }).into_option_with_entry().map(|x| x.1);
+        if new.is_some() {
+            result.pcx_target_surface = new;
+            break;
+        }
+    }
+    result
+}
+
+struct IsBlitPcxToSurface<'a, 'e, E: ExecutionState<'e>> {
+    result: EntryOf<u8>,
+    load_pcx: E::VirtualAddress,
+    arg_cache: &'a ArgCache<'e, E>,
+}
+
+impl<'a, 'e, E: ExecutionState<'e>> analysis::Analyzer<'e> for IsBlitPcxToSurface<'a, 'e, E> {
+    type State = analysis::DefaultState;
+    type Exec = E;
+    fn operation(&mut self, ctrl: &mut Control<'e, '_, '_, Self>, op: &Operation<'e>) {
+        if let Operation::Call(dest) = *op {
+            if ctrl.resolve_va(dest) != Some(self.load_pcx) {
+                return;
+            }
+            let out = ctrl.resolve_arg(2);
+            let base = out.if_memory()
+                .filter(|mem| mem.size == E::WORD_SIZE)
+                .map(|mem| mem.address().0);
+            let arg_cache = self.arg_cache;
+            let surface_arg = base.and_then(|base| {
+                (0..4).find(|&i| base == arg_cache.on_entry(i))
+            });
+            self.result = match surface_arg {
+                Some(i) => EntryOf::Ok(i),
+                None => EntryOf::Stop,
+            };
+            ctrl.end_analysis();
+        }
+    }
+}
+
+struct FindPcxTargetSurface<'e, E: ExecutionState<'e>> {
+    result: EntryOf<Operand<'e>>,
+    blit: E::VirtualAddress,
+    surface_arg: u8,
+}
+
+impl<'e, E: ExecutionState<'e>> analysis::Analyzer<'e> for FindPcxTargetSurface<'e, E> {
+    type State = analysis::DefaultState;
+    type Exec = E;
+    fn operation(&mut self, ctrl: &mut Control<'e, '_, '_, Self>, op: &Operation<'e>) {
+        if let Operation::Call(dest) = *op {
+            if ctrl.resolve_va(dest) != Some(self.blit) {
+                return;
+            }
+            let surface = ctrl.resolve_arg(self.surface_arg);
+            self.result = match is_global(surface) {
+                true => EntryOf::Ok(surface),
+                false => EntryOf::Stop,
+            };
+            ctrl.end_analysis();
+        }
+    }
+}
+
+pub(crate) fn compute_sync_checksum<'e, E: ExecutionState<'e>>(
+    actx: &AnalysisCtx<'e, E>,
+    step_game_loop: E::VirtualAddress,
+    sync_data: Operand<'e>,
+) -> Option<E::VirtualAddress> {
+    let binary = actx.binary;
+    let ctx = actx.ctx;
+    let bump = &actx.bump;
+    let sync_data = sync_data.if_constant()?;
+    // Find the function called (inlined depth <= 2) from step_game_loop that writes
+    // a non-constant value to sync_data (0x10c0 bytes).
+    // The sync data is also cleared with memset during game loop init, but that won't
+    // be reached from step_game_loop.
+    let mut analyzer = FindComputeSyncChecksum::<E> {
+        result: None,
+        sync_data: sync_data..sync_data.wrapping_add(0x10c0),
+        current_entry: step_game_loop,
+        inline_depth: 0,
+        checked_calls: bumpvec_with_capacity(0x20, bump),
+    };
+    let mut analysis = FuncAnalysis::new(binary, ctx, step_game_loop);
+    analysis.analyze(&mut analyzer);
+    analyzer.result
+}
+
+struct FindComputeSyncChecksum<'acx, 'e, E: ExecutionState<'e>> {
+    result: Option<E::VirtualAddress>,
+    sync_data: std::ops::Range<u64>,
+    current_entry: E::VirtualAddress,
+    inline_depth: u8,
+    checked_calls: BumpVec<'acx, E::VirtualAddress>,
+}
+
+impl<'acx, 'e: 'acx, E: ExecutionState<'e>> analysis::Analyzer<'e> for
+    FindComputeSyncChecksum<'acx, 'e, E>
+{
+    type State = analysis::DefaultState;
+    type Exec = E;
+    fn operation(&mut self, ctrl: &mut Control<'e, '_, '_, Self>, op: &Operation<'e>) {
+        match *op {
+            Operation::Call(dest) => {
+                if self.inline_depth >= 2 {
+                    return;
+                }
+                let dest = match ctrl.resolve_va(dest) {
+                    Some(s) => s,
+                    None => return,
+                };
+                if self.checked_calls.iter().any(|&x| x == dest) {
+                    return;
+                }
+                self.checked_calls.push(dest);
+                let old_entry = self.current_entry;
+                self.current_entry = dest;
+                self.inline_depth += 1;
+                ctrl.analyze_with_current_state(self, dest);
+                self.inline_depth -= 1;
+                self.current_entry = old_entry;
+                if self.result.is_some() {
+                    ctrl.end_analysis();
+                }
+            }
+            Operation::Move(DestOperand::Memory(ref mem), value) => {
+                if self.inline_depth == 0 {
+                    return;
+                }
+                let mem = ctrl.resolve_mem(mem);
+                let (_, offset) = mem.address();
+                if !self.sync_data.contains(&offset) {
+                    return;
+                }
+                if ctrl.resolve(value).if_constant().is_none() {
+                    self.result = Some(self.current_entry);
+                    ctrl.end_analysis();
+                }
+            }
+            _ => (),
+        }
+    }
+}
+
 pub(crate) fn join_custom_game<'e, E: ExecutionState<'e>>(
     actx: &AnalysisCtx<'e, E>,
     join_game: E::VirtualAddress,
@@ -5373,3 +6142,69 @@ impl<'a, 'e, E: ExecutionState<'e>> analysis::Analyzer<'e> for FindFileWithCrcAn
         }
     }
 }
+
+/// Checks that `func` (or a function it calls directly) reads `game_data` field at
+/// `offset` with `size`, to confirm that operands derived from `game_data` layout are
+/// actually used.
+pub(crate) fn reads_game_data_field<'e, E: ExecutionState<'e>>(
+    actx: &AnalysisCtx<'e, E>,
+    func: E::VirtualAddress,
+    game_data: Operand<'e>,
+    offset: u64,
+    size: MemAccessSize,
+) -> bool {
+    let binary = actx.binary;
+    let ctx = actx.ctx;
+    let bump = &actx.bump;
+    let mut analyzer = FindGameDataFieldRead::<E> {
+        field: ctx.mem_any(size, game_data, offset),
+        found: false,
+        inline_depth: 0,
+        checked_calls: bumpvec_with_capacity(0x20, bump),
+    };
+    let mut analysis = FuncAnalysis::new(binary, ctx, func);
+    analysis.analyze(&mut analyzer);
+    analyzer.found
+}
+
+struct FindGameDataFieldRead<'acx, 'e, E: ExecutionState<'e>> {
+    field: Operand<'e>,
+    found: bool,
+    inline_depth: u8,
+    checked_calls: BumpVec<'acx, E::VirtualAddress>,
+}
+
+impl<'acx, 'e: 'acx, E: ExecutionState<'e>> analysis::Analyzer<'e> for
+    FindGameDataFieldRead<'acx, 'e, E>
+{
+    type State = analysis::DefaultState;
+    type Exec = E;
+    fn operation(&mut self, ctrl: &mut Control<'e, '_, '_, Self>, op: &Operation<'e>) {
+        let value = match *op {
+            Operation::Call(dest) => {
+                if self.inline_depth == 0 {
+                    if let Some(dest) = ctrl.resolve_va(dest) {
+                        if !self.checked_calls.iter().any(|&x| x == dest) {
+                            self.checked_calls.push(dest);
+                            self.inline_depth += 1;
+                            ctrl.analyze_with_current_state(self, dest);
+                            self.inline_depth -= 1;
+                            if self.found {
+                                ctrl.end_analysis();
+                            }
+                        }
+                    }
+                }
+                return;
+            }
+            Operation::Move(_, value) => value,
+            Operation::Jump { condition, .. } => condition,
+            _ => return,
+        };
+        let value = ctrl.resolve(value);
+        if value.iter_no_mem_addr().any(|x| x == self.field) {
+            self.found = true;
+            ctrl.end_analysis();
+        }
+    }
+}
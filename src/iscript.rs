@@ -6,6 +6,7 @@ use scarf::exec_state::ExecutionState;
 use scarf::exec_state::VirtualAddress;
 
 use crate::analysis::{AnalysisCtx, ArgCache};
+use crate::analysis_find::{EntryOf, FunctionFinder, entry_of_until};
 use crate::linked_list::DetectListAdd;
 use crate::switch;
 use crate::util::{ControlExt, ExecStateExt, OperandExt, single_result_assign};
@@ -393,6 +394,82 @@ impl<'e, E: ExecutionState<'e>> scarf::Analyzer<'e> for AddOverlayAnalyzer<'e, E
     }
 }
 
+pub(crate) fn add_status_overlay<'e, E: ExecutionState<'e>>(
+    actx: &AnalysisCtx<'e, E>,
+    add_overlay_iscript: E::VirtualAddress,
+    functions: &FunctionFinder<'_, 'e, E>,
+) -> Option<E::VirtualAddress> {
+    let ctx = actx.ctx;
+    let binary = actx.binary;
+    let arg_cache = &actx.arg_cache;
+    // add_status_overlay(unit, first_image_id) is used to create ensnare / plague / etc
+    // overlays; it selects small/medium/large variant based on unit's size, and calls
+    // add_overlay_iscript(this = image, image_id = first_image_id + size, x, y, 1).
+    // Check callers of add_overlay_iscript for one where image_id is
+    // first_image_id argument + something.
+    let callers = functions.find_callers(actx, add_overlay_iscript);
+    let funcs = functions.functions();
+    let mut result = None;
+    for caller in callers {
+        let new = entry_of_until(binary, &funcs, caller, |entry| {
+            let mut analyzer = FindAddStatusOverlay::<E> {
+                result: EntryOf::Retry,
+                add_overlay_iscript,
+                caller,
+                arg_cache,
+            };
+            let mut analysis = FuncAnalysis::new(binary, ctx, entry);
+            analysis.analyze(&mut analyzer);
+            analyzer.result
+        }).into_option_with_entry().map(|x| x.0);
+        if single_result_assign(new, &mut result) {
+            break;
+        }
+    }
+    result
+}
+
+struct FindAddStatusOverlay<'a, 'e, E: ExecutionState<'e>> {
+    result: EntryOf<()>,
+    add_overlay_iscript: E::VirtualAddress,
+    caller: E::VirtualAddress,
+    arg_cache: &'a ArgCache<'e, E>,
+}
+
+impl<'a, 'e, E: ExecutionState<'e>> scarf::Analyzer<'e> for FindAddStatusOverlay<'a, 'e, E> {
+    type State = analysis::DefaultState;
+    type Exec = E;
+    fn operation(&mut self, ctrl: &mut Control<'e, '_, '_, Self>, op: &Operation<'e>) {
+        if let Operation::Call(dest) = *op {
+            if ctrl.resolve_va(dest) != Some(self.add_overlay_iscript) {
+                return;
+            }
+            let image_id = ctrl.resolve_arg_thiscall_u32(0);
+            // Unit may be either `this` or a1, so accept image id from either of
+            // first two arguments.
+            let arg1 = self.arg_cache.on_entry(0);
+            let arg2 = self.arg_cache.on_entry(1);
+            let ok = image_id.if_arithmetic_add().is_some() &&
+                image_id.iter_no_mem_addr().any(|x| x == arg1 || x == arg2);
+            if ok {
+                self.result = EntryOf::Ok(());
+                ctrl.end_analysis();
+            } else if ctrl.address() == self.caller {
+                self.result = EntryOf::Stop;
+            }
+        }
+    }
+}
+
+pub(crate) fn iscript_use_weapon_branch<'e, E: ExecutionState<'e>>(
+    analysis: &AnalysisCtx<'e, E>,
+    iscript_switch_table: E::VirtualAddress,
+) -> Option<E::VirtualAddress> {
+    // useweapon is iscript opcode 0x28. The switch is inside step_iscript,
+    // so this is a branch address and not a function entry.
+    switch::simple_switch_branch(analysis.binary, iscript_switch_table, 0x28)
+}
+
 pub(crate) fn draw_cursor_marker<'e, E: ExecutionState<'e>>(
     analysis: &AnalysisCtx<'e, E>,
     iscript_switch_table: E::VirtualAddress,
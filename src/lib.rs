@@ -73,8 +73,8 @@ pub use scarf;
 pub use scarf::{BinarySection};
 
 pub use crate::analysis::{
-    AddressAnalysis, Analysis, DatType, DatPatchesDebug, FiregraftAddresses, OperandAnalysis,
-    Patch,
+    AddressAnalysis, Analysis, DatType, DatPatchesDebug, FailureReason, FiregraftAddresses,
+    OperandAnalysis, Patch, SwitchKind,
 };
 
 pub use crate::ai::AiScriptHook;
@@ -83,7 +83,9 @@ pub use crate::dat::{
 };
 pub use crate::eud::{Eud, EudTable};
 pub use crate::firegraft::{RequirementTables, RequirementTable};
-pub use crate::game::{Limits};
+pub use crate::game::{Limits, UnitLimits};
+pub use crate::game_init::GameDataOffsets;
+pub use crate::units::{FlingySpeedOffsets, OrderTargetOffsets, UnitStatusTimers};
 pub use crate::inline_hook::InlineHookState;
 pub use crate::iscript::StepIscriptHook;
 pub use crate::network::{SnpDefinitions};
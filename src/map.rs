@@ -2,6 +2,7 @@ use scarf::{DestOperand, FlagUpdate, FlagArith, Operand, Operation};
 use scarf::analysis::{self, Control, FuncAnalysis};
 use scarf::exec_state::{ExecutionState, VirtualAddress};
 
+use crate::add_terms::collect_arith_add_terms;
 use crate::analysis::{AnalysisCtx, ArgCache};
 use crate::analysis_find::{EntryOf, FunctionFinder, entry_of_until_with_limit};
 use crate::call_tracker::{CallTracker};
@@ -29,6 +30,12 @@ impl<Va: VirtualAddress> Default for RunTriggers<Va> {
     }
 }
 
+/// Array inside `game` that is indexed by trigger conditions.
+pub(crate) struct TriggerGameArray<'e> {
+    pub array: Option<Operand<'e>>,
+    pub stride: u32,
+}
+
 #[derive(Clone, Copy, Default)]
 pub struct TriggerUnitCountCaches<'e> {
     pub completed_units: Option<Operand<'e>>,
@@ -401,6 +408,152 @@ impl<'a, 'e, E: ExecutionState<'e>> analysis::Analyzer<'e> for RunTriggersAnalyz
     }
 }
 
+/// Finds the trigger location array from condition #3 Bring, which reads
+/// `game.locations[condition.location - 1]`.
+pub(crate) fn trigger_locations<'e, E: ExecutionState<'e>>(
+    analysis: &AnalysisCtx<'e, E>,
+    conditions: E::VirtualAddress,
+    game: Operand<'e>,
+) -> TriggerGameArray<'e> {
+    // Location ids are 1-based, the index isn't decremented before multiplying,
+    // so the found offset is stride less than the array start.
+    trigger_game_array(analysis, conditions, 3, game, 0x10..0x20, 0, true)
+}
+
+/// Finds the death counters from condition #15 Deaths, which reads
+/// `game.deaths[condition.unit_id][player]`.
+pub(crate) fn trigger_death_counters<'e, E: ExecutionState<'e>>(
+    analysis: &AnalysisCtx<'e, E>,
+    conditions: E::VirtualAddress,
+    game: Operand<'e>,
+) -> TriggerGameArray<'e> {
+    // Row stride is player_count * 4, player * 4 is the one extra term.
+    trigger_game_array(analysis, conditions, 0xf, game, 0x20..0x100, 1, false)
+}
+
+fn trigger_game_array<'e, E: ExecutionState<'e>>(
+    analysis: &AnalysisCtx<'e, E>,
+    conditions: E::VirtualAddress,
+    condition_id: u32,
+    game: Operand<'e>,
+    stride_range: std::ops::Range<u64>,
+    extra_terms: usize,
+    one_based_index: bool,
+) -> TriggerGameArray<'e> {
+    let mut result = TriggerGameArray {
+        array: None,
+        stride: 0,
+    };
+    let binary = analysis.binary;
+    let ctx = analysis.ctx;
+    let condition_addr = conditions + condition_id * E::VirtualAddress::SIZE;
+    let condition = match binary.read_address(condition_addr) {
+        Ok(o) => o,
+        Err(_) => return result,
+    };
+    let mut analyzer = TriggerGameArrayAnalyzer::<E> {
+        result: None,
+        inline_depth: 0,
+        game,
+        stride_range,
+        extra_terms,
+        actx: analysis,
+    };
+    let mut analysis = FuncAnalysis::new(binary, ctx, condition);
+    analysis.analyze(&mut analyzer);
+    if let Some((offset, stride)) = analyzer.result {
+        let offset = match one_based_index {
+            true => offset.wrapping_add(stride),
+            false => offset,
+        };
+        result.array = Some(ctx.add_const(game, offset));
+        result.stride = stride as u32;
+    }
+    result
+}
+
+struct TriggerGameArrayAnalyzer<'a, 'e, E: ExecutionState<'e>> {
+    /// (offset from game, stride)
+    result: Option<(u64, u64)>,
+    inline_depth: u8,
+    game: Operand<'e>,
+    stride_range: std::ops::Range<u64>,
+    /// How many terms in addition to `game` and `index * stride` the address
+    /// is expected to have (e.g. `player * 4` for 2D arrays)
+    extra_terms: usize,
+    actx: &'a AnalysisCtx<'e, E>,
+}
+
+impl<'a, 'e, E: ExecutionState<'e>> analysis::Analyzer<'e> for
+    TriggerGameArrayAnalyzer<'a, 'e, E>
+{
+    type State = analysis::DefaultState;
+    type Exec = E;
+    fn operation(&mut self, ctrl: &mut Control<'e, '_, '_, Self>, op: &Operation<'e>) {
+        match *op {
+            Operation::Call(dest) => {
+                // The array entry may be passed to a function instead of being read here.
+                for i in 0..3 {
+                    let arg = ctrl.resolve_arg(i);
+                    if self.check_address(arg) {
+                        ctrl.end_analysis();
+                        return;
+                    }
+                }
+                if self.inline_depth < 2 {
+                    if let Some(dest) = ctrl.resolve_va(dest) {
+                        self.inline_depth += 1;
+                        ctrl.analyze_with_current_state(self, dest);
+                        self.inline_depth -= 1;
+                        if self.result.is_some() {
+                            ctrl.end_analysis();
+                        }
+                    }
+                }
+            }
+            Operation::Move(_, value) => {
+                let value = ctrl.resolve(value);
+                if let Some(mem) = value.if_memory() {
+                    let ctx = ctrl.ctx();
+                    if self.check_address(mem.address_op(ctx)) {
+                        ctrl.end_analysis();
+                    }
+                }
+            }
+            _ => (),
+        }
+    }
+}
+
+impl<'a, 'e, E: ExecutionState<'e>> TriggerGameArrayAnalyzer<'a, 'e, E> {
+    /// Checks for `game + index * stride + offset` (+ `extra_terms`)
+    fn check_address(&mut self, address: Operand<'e>) -> bool {
+        let mut terms = collect_arith_add_terms(address, &self.actx.bump);
+        let game = self.game;
+        if !terms.remove_one(|op, neg| !neg && op == game) {
+            return false;
+        }
+        let stride_range = &self.stride_range;
+        let index = terms.remove_get(|op, neg| {
+            !neg && op.if_arithmetic_mul()
+                .and_then(|(_, r)| r.if_constant())
+                .filter(|c| stride_range.contains(c))
+                .is_some()
+        });
+        let stride = index.and_then(|x| x.if_arithmetic_mul())
+            .and_then(|(_, r)| r.if_constant());
+        let stride = match stride {
+            Some(s) => s,
+            None => return false,
+        };
+        if terms.terms.len() != self.extra_terms {
+            return false;
+        }
+        self.result = Some((terms.constant, stride));
+        true
+    }
+}
+
 pub(crate) fn trigger_unit_count_caches<'e, E: ExecutionState<'e>>(
     analysis: &AnalysisCtx<'e, E>,
     conditions: E::VirtualAddress,
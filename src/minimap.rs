@@ -1,11 +1,15 @@
+use bumpalo::collections::Vec as BumpVec;
+
 use scarf::analysis::{self, Control, FuncAnalysis};
 use scarf::exec_state::{ExecutionState, VirtualAddress};
-use scarf::{Operand, Operation};
+use scarf::{DestOperand, MemAccess, Operand, Operation};
 
 use crate::analysis::{AnalysisCtx, Patch};
 use crate::analysis_find::{EntryOf, FunctionFinder, entry_of_until};
 use crate::analysis_state::{AnalysisState, StateEnum, ReplayVisionsState};
-use crate::util::{ControlExt, ExecStateExt, OperandExt, OptionExt, bumpvec_with_capacity};
+use crate::util::{
+    ControlExt, ExecStateExt, OperandExt, OptionExt, bumpvec_with_capacity, single_result_assign,
+};
 use crate::struct_layouts::StructLayouts;
 
 pub(crate) fn unexplored_fog_minimap_patch<'e, E: ExecutionState<'e>>(
@@ -416,3 +420,75 @@ impl<'a, 'acx, 'e: 'acx, E: ExecutionState<'e>> scarf::Analyzer<'e> for
     }
 }
 
+pub(crate) fn set_replay_vision<'e, E: ExecutionState<'e>>(
+    actx: &AnalysisCtx<'e, E>,
+    replay_visions: Operand<'e>,
+    functions: &FunctionFinder<'_, 'e, E>,
+) -> Option<E::VirtualAddress> {
+    let binary = actx.binary;
+    let ctx = actx.ctx;
+    let bump = &actx.bump;
+    let arg_cache = &actx.arg_cache;
+    // set_replay_vision(player_or_mask) is a small function which
+    // writes a value derived from arg1 to replay_visions
+    // (Either `replay_visions = arg1` or toggle `replay_visions ^= 1 << arg1`)
+    let replay_visions_mem = replay_visions.if_memory()?;
+    let global = E::VirtualAddress::from_u64(replay_visions_mem.if_constant_address()?);
+    let funcs = functions.functions();
+    let global_refs = functions.find_functions_using_global(actx, global);
+    let global_refs = BumpVec::from_iter_in(global_refs.iter().map(|x| x.use_address), bump);
+    let mut result = None;
+    for &use_address in &global_refs {
+        let new = entry_of_until(binary, &funcs, use_address, |entry| {
+            let mut analyzer = IsSetReplayVision::<E> {
+                result: EntryOf::Retry,
+                use_address,
+                replay_visions: replay_visions_mem,
+                arg1: arg_cache.on_entry(0),
+            };
+            let mut analysis = FuncAnalysis::new(binary, ctx, entry);
+            analysis.analyze(&mut analyzer);
+            analyzer.result
+        }).into_option_with_entry().map(|x| x.0);
+        if single_result_assign(new, &mut result) {
+            break;
+        }
+    }
+    result
+}
+
+struct IsSetReplayVision<'a, 'e, E: ExecutionState<'e>> {
+    result: EntryOf<()>,
+    use_address: E::VirtualAddress,
+    replay_visions: &'a MemAccess<'e>,
+    arg1: Operand<'e>,
+}
+
+impl<'a, 'e, E: ExecutionState<'e>> analysis::Analyzer<'e> for IsSetReplayVision<'a, 'e, E> {
+    type State = analysis::DefaultState;
+    type Exec = E;
+    fn operation(&mut self, ctrl: &mut Control<'e, '_, '_, Self>, op: &Operation<'e>) {
+        let use_address = self.use_address;
+        let at_use_address = use_address >= ctrl.address() &&
+            use_address < ctrl.current_instruction_end();
+        if let Operation::Move(DestOperand::Memory(ref mem), value) = *op {
+            let mem = ctrl.resolve_mem(mem);
+            if mem.address() == self.replay_visions.address() {
+                let value = ctrl.resolve(value);
+                let arg1 = self.arg1;
+                let from_arg = value.iter_no_mem_addr().any(|x| x == arg1);
+                if from_arg {
+                    self.result = EntryOf::Ok(());
+                    ctrl.end_analysis();
+                    return;
+                }
+            }
+        }
+        if at_use_address {
+            // Found the entry, but not a write from arg1 at the use
+            if let EntryOf::Retry = self.result {
+                self.result = EntryOf::Stop;
+            }
+        }
+    }
+}
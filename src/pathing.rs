@@ -17,6 +17,7 @@ pub struct RegionRelated<'e, Va: VirtualAddress> {
     pub get_region: Option<Va>,
     pub ai_regions: Option<Operand<'e>>,
     pub change_ai_region_state: Option<Va>,
+    pub ai_region_size: u32,
 }
 
 pub(crate) struct StepUnitMovement<Va: VirtualAddress> {
@@ -35,6 +36,7 @@ pub(crate) fn regions<'e, E: ExecutionState<'e>>(
         get_region: None,
         ai_regions: None,
         change_ai_region_state: None,
+        ai_region_size: 0,
     };
 
     // Find things through aiscript value_area
@@ -125,16 +127,19 @@ impl<'a, 'e, E: ExecutionState<'e>> RegionsAnalyzer<'a, 'e, E> {
             }
             // SetAiRegionState call?
             Some(5) => {
+                // a1 = ai_regions[player] + region_id * ai_region_size
+                let region_size = E::struct_layouts().ai_region_size();
                 let arg1 =  ctrl.resolve_arg(0);
                 let ai_regions = arg1.if_arithmetic_add()
                     .and_either_other(|x| {
-                        x.if_arithmetic_mul_const(E::struct_layouts().ai_region_size())
+                        x.if_arithmetic_mul_const(region_size)
                             .filter(|x| x.contains_undefined())
                     })
                     .and_then(|x| ctrl.if_mem_word(x));
                 if let Some(ai_regions_mem) = ai_regions {
                     self.result.ai_regions = Some(ai_regions_mem.address_op(ctx));
                     self.result.change_ai_region_state = Some(dest);
+                    self.result.ai_region_size = region_size as u32;
                 }
             }
             _ => (),
@@ -143,6 +148,55 @@ impl<'a, 'e, E: ExecutionState<'e>> RegionsAnalyzer<'a, 'e, E> {
     }
 }
 
+/// Finds offset of region.state from change_ai_region_state(a1 = region, a2 = u8 state),
+/// which writes a2 there.
+pub(crate) fn ai_region_state_offset<'e, E: ExecutionState<'e>>(
+    actx: &AnalysisCtx<'e, E>,
+    change_ai_region_state: E::VirtualAddress,
+) -> Option<u16> {
+    let binary = actx.binary;
+    let ctx = actx.ctx;
+
+    let mut analysis = FuncAnalysis::new(binary, ctx, change_ai_region_state);
+    let mut analyzer = RegionStateOffsetAnalyzer::<E> {
+        result: None,
+        arg_cache: &actx.arg_cache,
+    };
+    analysis.analyze(&mut analyzer);
+    analyzer.result
+}
+
+struct RegionStateOffsetAnalyzer<'a, 'e, E: ExecutionState<'e>> {
+    result: Option<u16>,
+    arg_cache: &'a ArgCache<'e, E>,
+}
+
+impl<'a, 'e, E: ExecutionState<'e>> analysis::Analyzer<'e> for
+    RegionStateOffsetAnalyzer<'a, 'e, E>
+{
+    type State = analysis::DefaultState;
+    type Exec = E;
+    fn operation(&mut self, ctrl: &mut Control<'e, '_, '_, Self>, op: &Operation<'e>) {
+        let ctx = ctrl.ctx();
+        if let Operation::Move(DestOperand::Memory(ref mem), value) = *op {
+            if mem.size == MemAccessSize::Mem8 {
+                let value = ctx.and_const(ctrl.resolve(value), 0xff);
+                let state = ctx.and_const(self.arg_cache.on_entry(1), 0xff);
+                if value == state {
+                    let (base, offset) = ctrl.resolve_mem(mem).address();
+                    if base == self.arg_cache.on_entry(0) && offset < 0x40 {
+                        self.result = Some(offset as u16);
+                        ctrl.end_analysis();
+                    }
+                }
+            }
+        } else if let Operation::Call(..) = *op {
+            // State is written before any calls
+            ctrl.end_analysis();
+        }
+    }
+}
+
 pub(crate) fn pathing<'e, E: ExecutionState<'e>>(
     analysis: &AnalysisCtx<'e, E>,
     get_region: E::VirtualAddress,
@@ -281,6 +335,102 @@ impl<'a, 'e, E: ExecutionState<'e>> analysis::Analyzer<'e> for
     }
 }
 
+pub(crate) fn update_unit_facing<'e, E: ExecutionState<'e>>(
+    actx: &AnalysisCtx<'e, E>,
+    step_unit_movement: E::VirtualAddress,
+) -> Option<E::VirtualAddress> {
+    let binary = actx.binary;
+    let ctx = actx.ctx;
+    let bump = &actx.bump;
+
+    let mut analysis = FuncAnalysis::new(binary, ctx, step_unit_movement);
+    let mut analyzer = UpdateUnitFacingAnalyzer::<E> {
+        result: None,
+        inline_depth: 0,
+        func_entry: step_unit_movement,
+        checked_functions: bumpvec_with_capacity(0x20, bump),
+    };
+    analysis.analyze(&mut analyzer);
+    analyzer.result
+}
+
+struct UpdateUnitFacingAnalyzer<'acx, 'e, E: ExecutionState<'e>> {
+    result: Option<E::VirtualAddress>,
+    inline_depth: u8,
+    func_entry: E::VirtualAddress,
+    checked_functions: BumpVec<'acx, E::VirtualAddress>,
+}
+
+impl<'acx, 'e: 'acx, E: ExecutionState<'e>> analysis::Analyzer<'e> for
+    UpdateUnitFacingAnalyzer<'acx, 'e, E>
+{
+    type State = analysis::DefaultState;
+    type Exec = E;
+    fn operation(&mut self, ctrl: &mut Control<'e, '_, '_, Self>, op: &Operation<'e>) {
+        // Follow every branch of the switch on this.movement_state, and inline
+        // to depth 2 for calls with this = this.
+        // update_unit_facing is the function that writes to this.facing_direction
+        // a value depending on this.turn_speed.
+        let ctx = ctrl.ctx();
+        match *op {
+            Operation::Jump { condition, to } if self.inline_depth == 0 => {
+                if condition == ctx.const_1() {
+                    let to = ctrl.resolve(to);
+                    let exec = ctrl.exec_state();
+                    if let Some(switch) = CompleteSwitch::new(to, ctx, exec) {
+                        let binary = ctrl.binary();
+                        let (low, high) = switch.limits();
+                        for case in low..=high {
+                            if let Some(branch) = switch.branch(binary, ctx, case) {
+                                ctrl.add_branch_with_current_state(branch);
+                            }
+                        }
+                        ctrl.end_branch();
+                    }
+                }
+            }
+            Operation::Call(dest) if self.inline_depth < 2 => {
+                if let Some(dest) = ctrl.resolve_va(dest) {
+                    if ctrl.resolve_register(1) != ctx.register(1) {
+                        return;
+                    }
+                    if self.checked_functions.iter().any(|&x| x == dest) {
+                        return;
+                    }
+                    self.checked_functions.push(dest);
+                    let old_entry = self.func_entry;
+                    self.func_entry = dest;
+                    self.inline_depth += 1;
+                    ctrl.analyze_with_current_state(self, dest);
+                    self.inline_depth -= 1;
+                    self.func_entry = old_entry;
+                    if self.result.is_some() {
+                        ctrl.end_analysis();
+                    }
+                }
+            }
+            Operation::Move(DestOperand::Memory(ref mem), value)
+                if mem.size == MemAccessSize::Mem8 && self.inline_depth != 0 =>
+            {
+                let mem = ctrl.resolve_mem(mem);
+                let facing_offset = E::struct_layouts().flingy_facing_direction();
+                if mem.address() != (ctx.register(1), facing_offset) {
+                    return;
+                }
+                let turn_speed_offset = E::struct_layouts().flingy_turn_speed();
+                let value = ctrl.resolve(value);
+                let uses_turn_speed = value.iter_no_mem_addr()
+                    .any(|x| x.if_mem8_offset(turn_speed_offset) == Some(ctx.register(1)));
+                if uses_turn_speed {
+                    self.result = Some(self.func_entry);
+                    ctrl.end_analysis();
+                }
+            }
+            _ => (),
+        }
+    }
+}
+
 pub(crate) fn analyze_make_path<'e, E: ExecutionState<'e>>(
     actx: &AnalysisCtx<'e, E>,
     make_path: E::VirtualAddress,
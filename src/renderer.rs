@@ -22,6 +22,11 @@ use crate::util::{
 };
 use crate::vtables::Vtables;
 
+/// Renderer vtable index of Renderer_Draw(this, draw_commands, width, height)
+const RENDERER_DRAW_INDEX: u32 = 7;
+/// Renderer vtable index of Renderer_UploadVerticesIndices
+const RENDERER_UPLOAD_VERTICES_INDEX: u32 = 0xa;
+
 #[derive(Clone)]
 pub struct PrismShaders<Va: VirtualAddress> {
     pub vertex_shaders: Rc<Vec<Va>>,
@@ -37,6 +42,27 @@ impl<Va: VirtualAddress> Default for PrismShaders<Va> {
     }
 }
 
+/// Reads function pointers of `vtable` until one that doesn't point to code section.
+pub(crate) fn vtable_methods<Va: VirtualAddress>(
+    binary: &BinaryFile<Va>,
+    vtable: Va,
+) -> Vec<Va> {
+    let code_section = binary.code_section();
+    let code_end = code_section.virtual_address + code_section.virtual_size;
+    let mut result = Vec::with_capacity(0x40);
+    for i in 0.. {
+        let func = match binary.read_address(vtable + Va::SIZE * i) {
+            Ok(o) => o,
+            Err(_) => break,
+        };
+        if func < code_section.virtual_address || func >= code_end {
+            break;
+        }
+        result.push(func);
+    }
+    result
+}
+
 pub(crate) fn prism_shaders<'e, E: ExecutionState<'e>>(
     analysis: &AnalysisCtx<'e, E>,
     vtables: &Vtables<'e, E::VirtualAddress>,
@@ -320,8 +346,8 @@ pub(crate) fn vertex_buffer<'e, E: ExecutionState<'e>>(
     analysis: &AnalysisCtx<'e, E>,
     vtables: &Vtables<'e, E::VirtualAddress>,
 ) -> Option<Operand<'e>> {
-    // Renderer_Draw (vtable + 0x1c) calls a function that uploads vertex
-    // buffer (vtable + 0x28)
+    // Renderer_Draw (vtable[7]) calls a function that uploads vertex
+    // buffer (vtable[0xa])
     // Renderer_Draw(this, draw_commands, width, height)
     //    upload_vertices_indices_and_sort_order(draw_commands)
     //      upload_vertices_indices_and_sort_order2(draw_commands)
@@ -339,7 +365,7 @@ pub(crate) fn vertex_buffer<'e, E: ExecutionState<'e>>(
         .flat_map(|name| vtables.vtables_starting_with(name))
         .map(|x| x.address)
     {
-        let draw = match binary.read_address(vtable + 7 * word_size).ok() {
+        let draw = match binary.read_address(vtable + RENDERER_DRAW_INDEX * word_size).ok() {
             Some(s) => s,
             None => continue,
         };
@@ -436,10 +462,11 @@ impl<'a, 'e, E: ExecutionState<'e>> scarf::Analyzer<'e> for FindVertexBuffer<'a,
                     if self.inline_depth != 0 {
                         // Check for the actual renderer.upload_vertices_indices virtual call
                         let word_size = E::VirtualAddress::SIZE;
-                        let is_vtable_fn_28 = ctrl
-                            .if_mem_word_offset(dest, 0xa * word_size as u64)
+                        let upload_offset = RENDERER_UPLOAD_VERTICES_INDEX * word_size;
+                        let is_upload_vertices = ctrl
+                            .if_mem_word_offset(dest, upload_offset as u64)
                             .is_some();
-                        if is_vtable_fn_28 {
+                        if is_upload_vertices {
                             let arg3 = ctrl.resolve_arg_thiscall(2);
                             // Arg3 is Mem32[vertex_buf + 4] (Mem32 even on 64bit too)
                             let vertex_buf = arg3.if_mem32()
@@ -471,6 +498,177 @@ impl<'a, 'e, E: ExecutionState<'e>> FindVertexBuffer<'a, 'e, E> {
     }
 }
 
+pub(crate) fn bind_texture<'e, E: ExecutionState<'e>>(
+    analysis: &AnalysisCtx<'e, E>,
+    vtables: &Vtables<'e, E::VirtualAddress>,
+) -> Option<E::VirtualAddress> {
+    // Renderer_Draw (vtable[7]) binds texture of each draw command with
+    // this.vtable.bind_texture(command.texture, ...) before drawing it.
+    // Find the first virtual call on this with arg1 loaded from draw command
+    // (Not stack argument / global / member of this), and use same vtable
+    // index to read the function.
+    let binary = analysis.binary;
+    let ctx = analysis.ctx;
+    let arg_cache = &analysis.arg_cache;
+    let word_size = E::VirtualAddress::SIZE;
+
+    for vtable in [&b".?AVGLRenderer"[..], b".?AVPrismRenderer"].iter()
+        .flat_map(|name| vtables.vtables_starting_with(name))
+        .map(|x| x.address)
+    {
+        let draw = match binary.read_address(vtable + RENDERER_DRAW_INDEX * word_size).ok() {
+            Some(s) => s,
+            None => continue,
+        };
+        let mut analyzer = FindBindTexture::<E> {
+            arg_cache,
+            result: None,
+            inline_depth: 0,
+        };
+        let mut analysis = FuncAnalysis::new(binary, ctx, draw);
+        analysis.analyze(&mut analyzer);
+        if let Some(index) = analyzer.result {
+            if let Ok(func) = binary.read_address(vtable + index * word_size) {
+                return Some(func);
+            }
+        }
+    }
+    None
+}
+
+struct FindBindTexture<'a, 'e, E: ExecutionState<'e>> {
+    arg_cache: &'a ArgCache<'e, E>,
+    /// Vtable index
+    result: Option<u32>,
+    inline_depth: u8,
+}
+
+impl<'a, 'e, E: ExecutionState<'e>> scarf::Analyzer<'e> for FindBindTexture<'a, 'e, E> {
+    type State = analysis::DefaultState;
+    type Exec = E;
+    fn operation(&mut self, ctrl: &mut Control<'e, '_, '_, Self>, op: &Operation<'e>) {
+        let ctx = ctrl.ctx();
+        if let Operation::Call(dest) = *op {
+            let dest = ctrl.resolve(dest);
+            let this = ctrl.resolve_register(1);
+            if this != ctx.register(1) {
+                return;
+            }
+            if let Some(dest) = dest.if_constant().map(|x| E::VirtualAddress::from_u64(x)) {
+                // Inline to functions taking draw_commands
+                if self.inline_depth < 2 &&
+                    ctrl.resolve_arg_thiscall(0) == self.arg_cache.on_thiscall_entry(0)
+                {
+                    self.inline_depth += 1;
+                    ctrl.analyze_with_current_state(self, dest);
+                    self.inline_depth -= 1;
+                    if self.result.is_some() {
+                        ctrl.end_analysis();
+                    }
+                }
+                return;
+            }
+            let word_size = E::VirtualAddress::SIZE;
+            let index = ctrl.if_mem_word(dest)
+                .map(|mem| mem.address())
+                .filter(|&(base, _)| ctrl.if_mem_word_offset(base, 0) == Some(ctx.register(1)))
+                .map(|(_, offset)| offset / word_size as u64)
+                .filter(|&index| {
+                    index != RENDERER_DRAW_INDEX as u64 &&
+                        index != RENDERER_UPLOAD_VERTICES_INDEX as u64 &&
+                        index < 0x80
+                });
+            let index = match index {
+                Some(s) => s,
+                None => return,
+            };
+            let arg1 = ctrl.resolve_arg_thiscall(0);
+            let from_command = ctrl.if_mem_word(arg1)
+                .map(|mem| mem.address().0)
+                .filter(|&base| {
+                    base != ctx.register(4) && base != ctx.register(1) && !is_global(base)
+                })
+                .is_some();
+            if from_command {
+                self.result = Some(index as u32);
+                ctrl.end_analysis();
+            }
+        }
+    }
+}
+
+pub(crate) fn draw_quad<'e, E: ExecutionState<'e>>(
+    actx: &AnalysisCtx<'e, E>,
+    draw_image: E::VirtualAddress,
+    vertex_buffer: Operand<'e>,
+) -> Option<E::VirtualAddress> {
+    // draw_image ends up calling draw_quad for the image's frame, which reserves
+    // space for the vertices by increasing Mem32[vertex_buf + 4] (word size on 64bit),
+    // same field that is passed to Renderer_UploadVerticesIndices.
+    let binary = actx.binary;
+    let ctx = actx.ctx;
+    let word_size = E::VirtualAddress::SIZE;
+    let mut analyzer = FindDrawQuad::<E> {
+        result: None,
+        vertex_count: ctx.mem32(vertex_buffer, word_size as u64),
+        inline_depth: 0,
+    };
+    let mut analysis = FuncAnalysis::new(binary, ctx, draw_image);
+    analysis.analyze(&mut analyzer);
+    analyzer.result
+}
+
+struct FindDrawQuad<'e, E: ExecutionState<'e>> {
+    result: Option<E::VirtualAddress>,
+    vertex_count: Operand<'e>,
+    inline_depth: u8,
+}
+
+impl<'e, E: ExecutionState<'e>> scarf::Analyzer<'e> for FindDrawQuad<'e, E> {
+    type State = analysis::DefaultState;
+    type Exec = E;
+    fn operation(&mut self, ctrl: &mut Control<'e, '_, '_, Self>, op: &Operation<'e>) {
+        match *op {
+            Operation::Call(dest) => {
+                if self.inline_depth < 3 {
+                    if let Some(dest) = ctrl.resolve_va(dest) {
+                        self.inline_depth += 1;
+                        ctrl.analyze_with_current_state(self, dest);
+                        self.inline_depth -= 1;
+                        if let Some(result) = self.result {
+                            if result == E::VirtualAddress::from_u64(0) {
+                                self.result = Some(dest);
+                            }
+                            ctrl.end_analysis();
+                        }
+                    }
+                }
+            }
+            Operation::Move(DestOperand::Memory(ref mem), value) if self.inline_depth != 0 => {
+                if mem.size != MemAccessSize::Mem32 {
+                    return;
+                }
+                let mem = ctrl.resolve_mem(mem);
+                let ctx = ctrl.ctx();
+                if ctx.memory(&mem) != self.vertex_count {
+                    return;
+                }
+                let value = ctrl.resolve(value);
+                let vertex_count = self.vertex_count;
+                let ok = value.if_arithmetic_add()
+                    .and_either_other(|x| x.if_constant())
+                    .filter(|&x| x == vertex_count)
+                    .is_some();
+                if ok {
+                    self.result = Some(E::VirtualAddress::from_u64(0));
+                    ctrl.end_analysis();
+                }
+            }
+            _ => (),
+        }
+    }
+}
+
 pub(crate) fn draw_game_layer<'e, E: ExecutionState<'e>>(
     actx: &AnalysisCtx<'e, E>,
     draw_layers: Operand<'e>,
@@ -1,7 +1,8 @@
 use scarf::analysis::{self, Control, FuncAnalysis};
-use scarf::exec_state::{ExecutionState};
+use scarf::exec_state::{ExecutionState, VirtualAddress};
 use scarf::{DestOperand, MemAccessSize, Operand, Operation};
 
+use crate::add_terms::collect_arith_add_terms;
 use crate::analysis::{AnalysisCtx, ArgCache};
 use crate::switch;
 use crate::util::{ControlExt, OptionExt};
@@ -11,6 +12,11 @@ pub(crate) struct PlaySound<'e> {
     pub sound_channels: Option<Operand<'e>>,
 }
 
+pub(crate) struct SoundVolume<'e, Va: VirtualAddress> {
+    pub set_sound_volume: Option<Va>,
+    pub sound_bank_handle: Option<Operand<'e>>,
+}
+
 pub(crate) fn play_sound<'e, E: ExecutionState<'e>>(
     analysis: &AnalysisCtx<'e, E>,
     iscript_switch: E::VirtualAddress,
@@ -176,3 +182,92 @@ impl<'a, 'e, E: ExecutionState<'e>> scarf::Analyzer<'e> for PlaySoundFnAnalyzer<
         }
     }
 }
+
+pub(crate) fn analyze_sound_volume<'e, E: ExecutionState<'e>>(
+    actx: &AnalysisCtx<'e, E>,
+    play_sound: E::VirtualAddress,
+    sound_channels: Operand<'e>,
+) -> SoundVolume<'e, E::VirtualAddress> {
+    let ctx = actx.ctx;
+    let binary = actx.binary;
+    let mut result = SoundVolume {
+        set_sound_volume: None,
+        sound_bank_handle: None,
+    };
+    let mut analyzer = SoundVolumeAnalyzer::<E> {
+        result: &mut result,
+        actx,
+        sound_channels,
+        inline_depth: 0,
+    };
+    let mut analysis = FuncAnalysis::new(binary, ctx, play_sound);
+    analysis.analyze(&mut analyzer);
+    result
+}
+
+struct SoundVolumeAnalyzer<'a, 'e, E: ExecutionState<'e>> {
+    result: &'a mut SoundVolume<'e, E::VirtualAddress>,
+    actx: &'a AnalysisCtx<'e, E>,
+    sound_channels: Operand<'e>,
+    inline_depth: u8,
+}
+
+impl<'a, 'e, E: ExecutionState<'e>> scarf::Analyzer<'e> for SoundVolumeAnalyzer<'a, 'e, E> {
+    type State = analysis::DefaultState;
+    type Exec = E;
+    fn operation(&mut self, ctrl: &mut Control<'e, '_, '_, Self>, op: &Operation<'e>) {
+        // Sounds are played through a global sound bank/device object, with
+        // virtual calls `sound_bank.vtable.fn(...)`.
+        // Once the buffer has been created, play_sound sets volume of the channel with
+        // set_sound_volume(channel, volume) (Or channel as this), where volume isn't constant.
+        if let Operation::Call(dest) = *op {
+            let dest_op = ctrl.resolve(dest);
+            if self.result.sound_bank_handle.is_none() {
+                let this = ctrl.resolve_register(1);
+                let is_virtual_on_this = ctrl.if_mem_word(dest_op)
+                    .and_then(|mem| ctrl.if_mem_word_offset(mem.address().0, 0))
+                    .filter(|&x| x == this)
+                    .is_some();
+                if is_virtual_on_this {
+                    let bank = ctrl.if_mem_word(this)
+                        .filter(|mem| mem.if_constant_address().is_some());
+                    if bank.is_some() {
+                        self.result.sound_bank_handle = Some(this);
+                    }
+                }
+            }
+            if let Some(dest) = dest_op.if_constant().map(|x| E::VirtualAddress::from_u64(x)) {
+                let this = ctrl.resolve_register(1);
+                let arg1 = ctrl.resolve_arg(0);
+                let arg2 = ctrl.resolve_arg(1);
+                let arg1_thiscall = ctrl.resolve_arg_thiscall(0);
+                let is_set_volume = (self.is_channel(arg1) && arg2.if_constant().is_none()) ||
+                    (self.is_channel(this) && arg1_thiscall.if_constant().is_none());
+                if is_set_volume {
+                    self.result.set_sound_volume = Some(dest);
+                } else if self.inline_depth == 0 {
+                    self.inline_depth += 1;
+                    ctrl.analyze_with_current_state(self, dest);
+                    self.inline_depth -= 1;
+                }
+                if self.result.set_sound_volume.is_some() &&
+                    self.result.sound_bank_handle.is_some()
+                {
+                    ctrl.end_analysis();
+                }
+            }
+        }
+    }
+}
+
+impl<'a, 'e, E: ExecutionState<'e>> SoundVolumeAnalyzer<'a, 'e, E> {
+    /// Checks for `sound_channels + index * channel_size`
+    fn is_channel(&self, op: Operand<'e>) -> bool {
+        let mut terms = collect_arith_add_terms(op, &self.actx.bump);
+        let channels = self.sound_channels;
+        if !terms.remove_one(|x, neg| !neg && x == channels) {
+            return false;
+        }
+        terms.terms.len() == 1 && terms.terms[0].0.if_mul_with_const().is_some()
+    }
+}
@@ -13,8 +13,8 @@ use crate::inline_hook::{EspOffsetRegs, InlineHookState, inline_hook_state};
 use crate::struct_layouts::StructLayouts;
 use crate::switch::CompleteSwitch;
 use crate::util::{
-    ControlExt, ExecStateExt, MemAccessExt, OperandExt, bumpvec_with_capacity,
-    single_result_assign,
+    ControlExt, ExecStateExt, MemAccessExt, OperandExt, OptionExt, bumpvec_with_capacity,
+    single_result_assign, is_stack_address,
 };
 
 #[derive(Clone, Debug)]
@@ -74,6 +74,23 @@ pub(crate) struct OrderMatrix<Va: VirtualAddress> {
     pub get_sight_range: Option<Va>,
 }
 
+pub(crate) struct OrderSpell<Va: VirtualAddress> {
+    pub cast_spell: Option<Va>,
+}
+
+pub(crate) struct OrderHallucination<Va: VirtualAddress> {
+    pub create_hallucination: Option<Va>,
+}
+
+pub(crate) struct OrderReturnCargo<Va: VirtualAddress> {
+    pub return_cargo: Option<Va>,
+}
+
+pub(crate) struct OrderPlaceAddon<Va: VirtualAddress> {
+    pub attach_addon: Option<Va>,
+    pub addon_offset: u16,
+}
+
 pub(crate) struct OrderPlayerGuard<Va: VirtualAddress> {
     pub get_target_acquisition_range: Option<Va>,
     pub pick_auto_target: Option<Va>,
@@ -300,6 +317,60 @@ pub(crate) fn find_order_function<'e, E: ExecutionState<'e>>(
     find_order_function_any(analysis, step_order, this, offset, order)
 }
 
+pub(crate) fn iscript_death_call<'e, E: ExecutionState<'e>>(
+    analysis: &AnalysisCtx<'e, E>,
+    order_die: E::VirtualAddress,
+) -> Option<E::VirtualAddress> {
+    let binary = analysis.binary;
+    let ctx = analysis.ctx;
+    // Order 0x0 (Die) first runs
+    //      set_iscript_animation(unit.sprite, 1 (Death))
+    // and on later frames waits until the death animation's iscript ends, and
+    // only then deletes the unit. Returns address of the call instruction that
+    // starts the animation.
+    let mut analyzer = FindIscriptDeath::<E> {
+        result: None,
+        phantom: Default::default(),
+    };
+    let mut analysis = FuncAnalysis::new(binary, ctx, order_die);
+    analysis.analyze(&mut analyzer);
+    analyzer.result
+}
+
+struct FindIscriptDeath<'e, E: ExecutionState<'e>> {
+    result: Option<E::VirtualAddress>,
+    phantom: std::marker::PhantomData<(*const E, &'e ())>,
+}
+
+impl<'e, E: ExecutionState<'e>> analysis::Analyzer<'e> for FindIscriptDeath<'e, E> {
+    type State = analysis::DefaultState;
+    type Exec = E;
+    fn operation(&mut self, ctrl: &mut Control<'e, '_, '_, Self>, op: &Operation<'e>) {
+        if let Operation::Call(_) = *op {
+            let ctx = ctrl.ctx();
+            let unit_sprite = E::struct_layouts().unit_sprite();
+            let unit = ctx.register(1);
+            let this = ctrl.resolve_register(1);
+            let arg1 = ctrl.resolve_arg(0);
+            let arg2 = ctrl.resolve_arg(1);
+            let anim_is_death = |op: Operand<'e>| ctx.and_const(op, 0xff) == ctx.const_1();
+            let ok = if ctrl.if_mem_word_offset(this, unit_sprite) == Some(unit) {
+                anim_is_death(arg1)
+            } else if ctrl.if_mem_word_offset(arg1, unit_sprite) == Some(unit) {
+                anim_is_death(arg2)
+            } else {
+                false
+            };
+            if ok {
+                let address = ctrl.address();
+                if single_result_assign(Some(address), &mut self.result) {
+                    ctrl.end_analysis();
+                }
+            }
+        }
+    }
+}
+
 pub(crate) fn find_order_function_secondary<'e, E: ExecutionState<'e>>(
     analysis: &AnalysisCtx<'e, E>,
     step_secondary_order: &SecondaryOrderHook<'e, E::VirtualAddress>,
@@ -1475,6 +1546,368 @@ impl<'a, 'e, E: ExecutionState<'e>> analysis::Analyzer<'e> for AnalyzeOrderMatri
     }
 }
 
+pub(crate) fn analyze_order_spell<'e, E: ExecutionState<'e>>(
+    actx: &AnalysisCtx<'e, E>,
+    order_spell: E::VirtualAddress,
+) -> OrderSpell<E::VirtualAddress> {
+    let mut result = OrderSpell {
+        cast_spell: None,
+    };
+
+    let binary = actx.binary;
+    let ctx = actx.ctx;
+
+    let mut analyzer = AnalyzeOrderSpell::<E> {
+        result: &mut result,
+        state: OrderSpellState::CastSpell,
+    };
+    let mut analysis = FuncAnalysis::new(binary, ctx, order_spell);
+    analysis.analyze(&mut analyzer);
+    result
+}
+
+struct AnalyzeOrderSpell<'a, 'e, E: ExecutionState<'e>> {
+    result: &'a mut OrderSpell<E::VirtualAddress>,
+    state: OrderSpellState,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+enum OrderSpellState {
+    /// Generic spell order (Psionic storm, irradiate, etc.) moves to range and
+    /// checks energy, then calls cast_spell(this = this).
+    /// Check calls with this = this.
+    CastSpell,
+    /// cast_spell should spend energy: Mem16[this + x] = Mem16[this + x] - cost
+    VerifyCastSpell,
+}
+
+impl<'a, 'e, E: ExecutionState<'e>> analysis::Analyzer<'e> for AnalyzeOrderSpell<'a, 'e, E> {
+    type State = analysis::DefaultState;
+    type Exec = E;
+    fn operation(&mut self, ctrl: &mut Control<'e, '_, '_, Self>, op: &Operation<'e>) {
+        let ctx = ctrl.ctx();
+        match self.state {
+            OrderSpellState::CastSpell => {
+                if let Operation::Call(dest) = *op {
+                    if let Some(dest) = ctrl.resolve_va(dest) {
+                        let this = ctrl.resolve_register(1);
+                        if this == ctx.register(1) {
+                            self.state = OrderSpellState::VerifyCastSpell;
+                            ctrl.analyze_with_current_state(self, dest);
+                            if self.result.cast_spell.is_some() {
+                                self.result.cast_spell = Some(dest);
+                                ctrl.end_analysis();
+                            } else {
+                                self.state = OrderSpellState::CastSpell;
+                            }
+                        }
+                    }
+                }
+            }
+            OrderSpellState::VerifyCastSpell => {
+                if let Operation::Move(DestOperand::Memory(ref mem), value) = *op {
+                    if mem.size != MemAccessSize::Mem16 {
+                        return;
+                    }
+                    let mem = ctrl.resolve_mem(mem);
+                    let (base, offset) = mem.address();
+                    if base != ctx.register(1) {
+                        return;
+                    }
+                    let value = ctrl.resolve(value);
+                    let ok = value.unwrap_and_mask()
+                        .if_arithmetic_sub()
+                        .filter(|&(l, r)| {
+                            r.if_constant().is_none() &&
+                                l.unwrap_and_mask().if_mem16_offset(offset) == Some(base)
+                        })
+                        .is_some();
+                    if ok {
+                        self.result.cast_spell = Some(E::VirtualAddress::from_u64(0));
+                        ctrl.end_analysis();
+                    }
+                }
+            }
+        }
+    }
+}
+
+pub(crate) fn analyze_order_hallucination<'e, E: ExecutionState<'e>>(
+    actx: &AnalysisCtx<'e, E>,
+    order_hallucination: E::VirtualAddress,
+    create_unit: E::VirtualAddress,
+) -> OrderHallucination<E::VirtualAddress> {
+    let mut result = OrderHallucination {
+        create_hallucination: None,
+    };
+
+    let binary = actx.binary;
+    let ctx = actx.ctx;
+
+    let mut analyzer = AnalyzeOrderHallucination::<E> {
+        result: &mut result,
+        state: OrderHallucinationState::CreateHallucination,
+        create_unit,
+        verify_limit: 0,
+    };
+    let mut analysis = FuncAnalysis::new(binary, ctx, order_hallucination);
+    analysis.analyze(&mut analyzer);
+    result
+}
+
+struct AnalyzeOrderHallucination<'a, 'e, E: ExecutionState<'e>> {
+    result: &'a mut OrderHallucination<E::VirtualAddress>,
+    state: OrderHallucinationState,
+    create_unit: E::VirtualAddress,
+    verify_limit: u8,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+enum OrderHallucinationState {
+    /// Once the order has spent energy, create_hallucination(this.target) gets
+    /// called twice. Check every call.
+    CreateHallucination,
+    /// create_hallucination should call
+    /// create_unit(target.unit_id, target.x, target.y, this.player)
+    /// and then set the hallucination flag on the new unit.
+    VerifyCreateHallucination,
+}
+
+impl<'a, 'e, E: ExecutionState<'e>> analysis::Analyzer<'e> for
+    AnalyzeOrderHallucination<'a, 'e, E>
+{
+    type State = analysis::DefaultState;
+    type Exec = E;
+    fn operation(&mut self, ctrl: &mut Control<'e, '_, '_, Self>, op: &Operation<'e>) {
+        let ctx = ctrl.ctx();
+        match self.state {
+            OrderHallucinationState::CreateHallucination => {
+                if let Operation::Call(dest) = *op {
+                    if let Some(dest) = ctrl.resolve_va(dest) {
+                        if dest == self.create_unit {
+                            return;
+                        }
+                        self.state = OrderHallucinationState::VerifyCreateHallucination;
+                        self.verify_limit = 8;
+                        ctrl.analyze_with_current_state(self, dest);
+                        if self.result.create_hallucination.is_some() {
+                            self.result.create_hallucination = Some(dest);
+                            ctrl.end_analysis();
+                        } else {
+                            self.state = OrderHallucinationState::CreateHallucination;
+                        }
+                    }
+                }
+            }
+            OrderHallucinationState::VerifyCreateHallucination => {
+                if let Operation::Call(dest) = *op {
+                    if self.verify_limit == 0 {
+                        ctrl.end_analysis();
+                        return;
+                    }
+                    self.verify_limit -= 1;
+                    if ctrl.resolve_va(dest) == Some(self.create_unit) {
+                        let target_offset = E::struct_layouts().unit_target();
+                        let unit_id_offset = E::struct_layouts().unit_id();
+                        let arg1 = ctrl.resolve_arg(0);
+                        let ok = arg1.unwrap_and_mask()
+                            .if_mem16_offset(unit_id_offset)
+                            .and_then(|x| ctrl.if_mem_word_offset(x, target_offset))
+                            .filter(|&x| x == ctx.register(1))
+                            .is_some();
+                        if ok {
+                            self.result.create_hallucination =
+                                Some(E::VirtualAddress::from_u64(0));
+                        }
+                        ctrl.end_analysis();
+                    }
+                }
+            }
+        }
+    }
+}
+
+pub(crate) fn analyze_order_place_addon<'e, E: ExecutionState<'e>>(
+    actx: &AnalysisCtx<'e, E>,
+    order_place_addon: E::VirtualAddress,
+) -> OrderPlaceAddon<E::VirtualAddress> {
+    let mut result = OrderPlaceAddon {
+        attach_addon: None,
+        addon_offset: 0,
+    };
+
+    let binary = actx.binary;
+    let ctx = actx.ctx;
+
+    // Once the building has been placed, order_place_addon creates the addon unit
+    // and calls attach_addon, which sets building.addon = addon.
+    // Check calls up to 2 deep for the function that does the write.
+    let mut analyzer = AnalyzeOrderPlaceAddon::<E> {
+        result: &mut result,
+        inline_depth: 0,
+        found_write: false,
+    };
+    let mut analysis = FuncAnalysis::new(binary, ctx, order_place_addon);
+    analysis.analyze(&mut analyzer);
+    result
+}
+
+struct AnalyzeOrderPlaceAddon<'a, 'e, E: ExecutionState<'e>> {
+    result: &'a mut OrderPlaceAddon<E::VirtualAddress>,
+    inline_depth: u8,
+    /// Set once the building.addon write has been seen; attach_addon is then the
+    /// innermost function that contained it.
+    found_write: bool,
+}
+
+impl<'a, 'e, E: ExecutionState<'e>> analysis::Analyzer<'e> for
+    AnalyzeOrderPlaceAddon<'a, 'e, E>
+{
+    type State = analysis::DefaultState;
+    type Exec = E;
+    fn operation(&mut self, ctrl: &mut Control<'e, '_, '_, Self>, op: &Operation<'e>) {
+        let ctx = ctrl.ctx();
+        match *op {
+            Operation::Call(dest) if self.inline_depth < 2 => {
+                if let Some(dest) = ctrl.resolve_va(dest) {
+                    self.inline_depth += 1;
+                    ctrl.analyze_with_current_state(self, dest);
+                    self.inline_depth -= 1;
+                    if self.found_write {
+                        if self.result.attach_addon.is_none() {
+                            self.result.attach_addon = Some(dest);
+                        }
+                        ctrl.end_analysis();
+                    }
+                }
+            }
+            Operation::Move(DestOperand::Memory(ref mem), value) if self.inline_depth != 0 => {
+                if mem.size != E::WORD_SIZE {
+                    return;
+                }
+                let mem = ctrl.resolve_mem(mem);
+                let (base, offset) = mem.address();
+                let unit_specific = E::struct_layouts().unit_specific();
+                if base != ctx.register(1) ||
+                    offset < unit_specific ||
+                    offset >= unit_specific + 0x10
+                {
+                    return;
+                }
+                // Should be the new addon; detach_addon writes 0 to same field.
+                let value = ctrl.resolve(value);
+                if value.if_constant().is_none() && !is_stack_address(value) {
+                    self.result.addon_offset = offset as u16;
+                    self.found_write = true;
+                    ctrl.end_analysis();
+                }
+            }
+            _ => (),
+        }
+    }
+}
+
+pub(crate) fn analyze_order_return_cargo<'e, E: ExecutionState<'e>>(
+    actx: &AnalysisCtx<'e, E>,
+    order_return_minerals: E::VirtualAddress,
+    game: Operand<'e>,
+) -> OrderReturnCargo<E::VirtualAddress> {
+    let mut result = OrderReturnCargo {
+        return_cargo: None,
+    };
+
+    let binary = actx.binary;
+    let ctx = actx.ctx;
+
+    let mut analyzer = AnalyzeOrderReturnCargo::<E> {
+        result: &mut result,
+        state: OrderReturnCargoState::ReturnCargo,
+        game,
+        verify_limit: 0,
+    };
+    let mut analysis = FuncAnalysis::new(binary, ctx, order_return_minerals);
+    analysis.analyze(&mut analyzer);
+    result
+}
+
+struct AnalyzeOrderReturnCargo<'a, 'e, E: ExecutionState<'e>> {
+    result: &'a mut OrderReturnCargo<E::VirtualAddress>,
+    state: OrderReturnCargoState,
+    game: Operand<'e>,
+    verify_limit: u8,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+enum OrderReturnCargoState {
+    /// Once the worker has reached the resource depot, return_cargo gets called.
+    /// Check every call.
+    ReturnCargo,
+    /// return_cargo should add the carried resources to
+    /// game.minerals[player] or game.gas[player]
+    VerifyReturnCargo,
+}
+
+impl<'a, 'e, E: ExecutionState<'e>> analysis::Analyzer<'e> for
+    AnalyzeOrderReturnCargo<'a, 'e, E>
+{
+    type State = analysis::DefaultState;
+    type Exec = E;
+    fn operation(&mut self, ctrl: &mut Control<'e, '_, '_, Self>, op: &Operation<'e>) {
+        match self.state {
+            OrderReturnCargoState::ReturnCargo => {
+                if let Operation::Call(dest) = *op {
+                    if let Some(dest) = ctrl.resolve_va(dest) {
+                        self.state = OrderReturnCargoState::VerifyReturnCargo;
+                        self.verify_limit = 12;
+                        ctrl.analyze_with_current_state(self, dest);
+                        if self.result.return_cargo.is_some() {
+                            self.result.return_cargo = Some(dest);
+                            ctrl.end_analysis();
+                        } else {
+                            self.state = OrderReturnCargoState::ReturnCargo;
+                        }
+                    }
+                }
+            }
+            OrderReturnCargoState::VerifyReturnCargo => {
+                match *op {
+                    Operation::Call(..) | Operation::Jump { .. } => {
+                        if self.verify_limit == 0 {
+                            ctrl.end_analysis();
+                            return;
+                        }
+                        self.verify_limit -= 1;
+                    }
+                    Operation::Move(DestOperand::Memory(ref mem), value) => {
+                        if mem.size != MemAccessSize::Mem32 {
+                            return;
+                        }
+                        let mem = ctrl.resolve_mem(mem);
+                        let game = self.game;
+                        let (base, _) = mem.address();
+                        let is_game_array = base.if_arithmetic_add()
+                            .and_if_either_other(|x| x == game)
+                            .filter(|index| index.if_arithmetic_mul_const(4).is_some())
+                            .is_some();
+                        if !is_game_array {
+                            return;
+                        }
+                        let value = ctrl.resolve(value);
+                        let ok = value.if_arithmetic_add()
+                            .and_either(|x| x.if_memory().filter(|&x| *x == mem))
+                            .is_some();
+                        if ok {
+                            self.result.return_cargo = Some(E::VirtualAddress::from_u64(0));
+                            ctrl.end_analysis();
+                        }
+                    }
+                    _ => (),
+                }
+            }
+        }
+    }
+}
+
 pub(crate) fn analyze_order_player_guard<'e, E: ExecutionState<'e>>(
     actx: &AnalysisCtx<'e, E>,
     order_player_guard: E::VirtualAddress,
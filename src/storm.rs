@@ -1,11 +1,13 @@
-use scarf::{Operand, Operation};
+use bumpalo::collections::Vec as BumpVec;
+
+use scarf::{DestOperand, Operand, Operation};
 use scarf::analysis::{self, Control, FuncAnalysis};
 use scarf::exec_state::{ExecutionState, VirtualAddress};
 use scarf::operand::{OperandHashByAddress};
 
 use crate::analysis::{AnalysisCtx, ArgCache};
 use crate::hash_map::HashSet;
-use crate::util::{ControlExt, OperandExt, is_global};
+use crate::util::{ControlExt, MemAccessExt, OperandExt, bumpvec_with_capacity, is_global};
 
 pub(crate) struct ReadWholeMpqFile<'e, Va: VirtualAddress> {
     pub mpq_locale: Option<Operand<'e>>,
@@ -185,3 +187,95 @@ impl<'a, 'e, E: ExecutionState<'e>> AnalyzeReadWholeMpqFile<'a, 'e, E> {
             value_base == ctrl.resolve_register(4).add_sub_offset().0
     }
 }
+
+pub(crate) fn archive_chain_head<'e, E: ExecutionState<'e>>(
+    actx: &AnalysisCtx<'e, E>,
+    mount_archive: E::VirtualAddress,
+) -> Option<Operand<'e>> {
+    let binary = actx.binary;
+    let ctx = actx.ctx;
+    let bump = &actx.bump;
+    // SFileOpenArchive allocates the archive struct and links it to the list of
+    // mounted archives that file opening searches through:
+    //      archive = alloc(..);
+    //      ...
+    //      archive.next = archive_chain_head;
+    //      archive_chain_head = archive;
+    // (The head may not be written if it's inserted later in the priority order,
+    // but some branch will write it)
+    // Function return values are made Custom so that the allocation can be recognized
+    // without knowing the allocation function.
+    // Require both the store of Custom(n) to a global and the store of that global's
+    // old value to a field of Custom(n), in either order.
+    let mut analyzer = FindArchiveChainHead::<E> {
+        result: None,
+        next_custom: 0,
+        head_stores: bumpvec_with_capacity(8, bump),
+        next_stores: bumpvec_with_capacity(8, bump),
+    };
+    let mut analysis = FuncAnalysis::new(binary, ctx, mount_archive);
+    analysis.analyze(&mut analyzer);
+    analyzer.result
+}
+
+struct FindArchiveChainHead<'acx, 'e, E: ExecutionState<'e>> {
+    result: Option<Operand<'e>>,
+    next_custom: u32,
+    /// (Custom id, global) for `global = Custom(id)`
+    head_stores: BumpVec<'acx, (u32, Operand<'e>)>,
+    /// (Custom id, global) for `Custom(id).field = global`
+    next_stores: BumpVec<'acx, (u32, Operand<'e>)>,
+}
+
+impl<'acx, 'e: 'acx, E: ExecutionState<'e>> analysis::Analyzer<'e> for
+    FindArchiveChainHead<'acx, 'e, E>
+{
+    type State = analysis::DefaultState;
+    type Exec = E;
+    fn operation(&mut self, ctrl: &mut Control<'e, '_, '_, Self>, op: &Operation<'e>) {
+        match *op {
+            Operation::Call(..) => {
+                let ctx = ctrl.ctx();
+                ctrl.do_call_with_result(ctx.custom(self.next_custom));
+                self.next_custom += 1;
+            }
+            Operation::Move(DestOperand::Memory(ref mem), value) => {
+                if mem.size != E::WORD_SIZE {
+                    return;
+                }
+                let ctx = ctrl.ctx();
+                let mem = ctrl.resolve_mem(mem);
+                let value = ctrl.resolve(value);
+                let (base, offset) = mem.address();
+                let pair = if mem.is_global() {
+                    match value.if_custom() {
+                        Some(id) => {
+                            let head = ctx.memory(&mem);
+                            self.head_stores.push((id, head));
+                            self.next_stores.iter()
+                                .any(|&x| x == (id, head))
+                                .then_some(head)
+                        }
+                        None => None,
+                    }
+                } else {
+                    match (base.if_custom(), ctrl.if_mem_word(value)) {
+                        (Some(id), Some(old)) if offset < 0x100 && old.is_global() => {
+                            let head = value;
+                            self.next_stores.push((id, head));
+                            self.head_stores.iter()
+                                .any(|&x| x == (id, head))
+                                .then_some(head)
+                        }
+                        _ => None,
+                    }
+                };
+                if let Some(head) = pair {
+                    self.result = Some(head);
+                    ctrl.end_analysis();
+                }
+            }
+            _ => (),
+        }
+    }
+}
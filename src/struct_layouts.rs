@@ -69,6 +69,10 @@ impl StructLayouts {
         self.pair(0x21, 0x39)
     }
 
+    pub const fn flingy_turn_speed(self) -> u64 {
+        self.pair(0x22, 0x3a)
+    }
+
     pub const fn flingy_movement_type(self) -> u64 {
         self.pair(0x27, 0x3f)
     }
@@ -121,6 +125,10 @@ impl StructLayouts {
         self.pair(0x80, 0xc0)
     }
 
+    pub const fn unit_kills(self) -> u64 {
+        self.pair(0x8f, 0xd3)
+    }
+
     pub const fn unit_invisibility_effects(self) -> u64 {
         self.pair(0x96, 0xda)
     }
@@ -181,14 +189,30 @@ impl StructLayouts {
         self.pair(0x100, 0x188)
     }
 
+    pub const fn unit_stim_timer(self) -> u64 {
+        self.pair(0x115, 0x1a1)
+    }
+
+    pub const fn unit_ensnare_timer(self) -> u64 {
+        self.pair(0x116, 0x1a2)
+    }
+
     pub const fn unit_lockdown_timer(self) -> u64 {
         self.pair(0x117, 0x1a3)
     }
 
+    pub const fn unit_irradiate_timer(self) -> u64 {
+        self.pair(0x118, 0x1a4)
+    }
+
     pub const fn unit_stasis_timer(self) -> u64 {
         self.pair(0x119, 0x1a5)
     }
 
+    pub const fn unit_plague_timer(self) -> u64 {
+        self.pair(0x11a, 0x1a6)
+    }
+
     pub const fn unit_maelstrom_timer(self) -> u64 {
         self.pair(0x124, 0x1b4)
     }
@@ -272,10 +296,18 @@ impl StructLayouts {
         self.pair(0x24, 0x2c)
     }
 
+    pub const fn ai_script_town(self) -> u64 {
+        self.pair(0x2c, 0x38)
+    }
+
     pub const fn ai_script_flags(self) -> u64 {
         self.pair(0x30, 0x40)
     }
 
+    pub const fn guard_ai_size(self) -> u64 {
+        self.pair(0x20, 0x30)
+    }
+
     pub const fn ai_town_player(self) -> u64 {
         self.pair(0x18, 0x30)
     }
@@ -320,6 +352,10 @@ impl StructLayouts {
         self.pair(0x8, 0x10) as u32
     }
 
+    pub const fn bullet_target(self) -> u64 {
+        self.pair(0x5c, 0x80)
+    }
+
     pub const fn bullet_weapon_id(self) -> u64 {
         self.pair(0x60, 0x88)
     }
@@ -420,8 +456,62 @@ impl StructLayouts {
     pub const fn local_skin_unit_skins(self) -> u64 {
         self.pair(0x14, 0x28)
     }
+
+    // GameData is a packed struct, same layout in 32- and 64-bit.
+    pub const fn game_data_size(self) -> u64 {
+        0x8d
+    }
+
+    pub const fn game_data_game_name(self) -> u64 {
+        0x4
+    }
+
+    pub const fn game_data_active_human_players(self) -> u64 {
+        0x24
+    }
+
+    pub const fn game_data_max_players(self) -> u64 {
+        0x25
+    }
+
+    pub const fn game_data_game_speed(self) -> u64 {
+        0x26
+    }
+
+    pub const fn game_data_host_name(self) -> u64 {
+        0x34
+    }
+
+    pub const fn game_data_map_title(self) -> u64 {
+        0x4d
+    }
+
+    /// 0x20 byte game template, starts with u16 game type.
+    pub const fn game_data_game_template(self) -> u64 {
+        0x6d
+    }
+
+    pub const fn game_data_victory_condition(self) -> u64 {
+        self.game_data_game_template() + 0x8
+    }
+
+    pub const fn game_data_resource_amount(self) -> u64 {
+        self.game_data_game_template() + 0x17
+    }
 }
 
+// Check that the fixed-size GameData fields line up with each other and
+// with the size that single_player_start copies.
+const _: () = {
+    let layouts = StructLayouts { is_64bit: false };
+    assert!(layouts.game_data_game_name() + 0x18 <= layouts.game_data_active_human_players());
+    assert!(layouts.game_data_game_speed() < layouts.game_data_host_name());
+    assert!(layouts.game_data_host_name() + 0x19 == layouts.game_data_map_title());
+    assert!(layouts.game_data_map_title() + 0x20 == layouts.game_data_game_template());
+    assert!(layouts.game_data_game_template() + 0x20 == layouts.game_data_size());
+    assert!(layouts.game_data_resource_amount() + 4 <= layouts.game_data_size());
+};
+
 pub fn button_set_index_to_action<Va: VirtualAddress>(
     binary: &BinaryFile<Va>,
     button_sets: Va,
@@ -204,6 +204,11 @@ impl<'e> CompleteSwitch<'e> {
         self.base
     }
 
+    /// Minimum and maximum (inclusive) values that `branch` accepts.
+    pub fn limits(&self) -> (u32, u32) {
+        (self.low, self.high)
+    }
+
     pub fn switch_table(&self) -> u64 {
         self.table.address().1
     }
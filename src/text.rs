@@ -20,6 +20,9 @@ pub struct FontRender<Va: VirtualAddress> {
     pub ttf_render_sdf: Option<Va>,
 }
 
+/// Font names in order of their index in `fonts` array.
+pub(crate) static FONT_NAMES: [&[u8]; 4] = [b"font8", b"font10", b"font16", b"font16x"];
+
 pub(crate) fn fonts<'e, E: ExecutionState<'e>>(
     analysis: &AnalysisCtx<'e, E>,
     functions: &FunctionFinder<'_, 'e, E>,
@@ -96,15 +99,8 @@ impl<'a, 'e, E: ExecutionState<'e>> scarf::Analyzer<'e> for FontsAnalyzer<'a, 'e
                                 let slice_len = slice.iter().take(8).position(|&x| x == 0)?;
                                 Some(&slice[..slice_len])
                             })
-                            .and_then(|string| {
-                                match string {
-                                    b"font8" => Some(0),
-                                    b"font10" => Some(1),
-                                    b"font16" => Some(2),
-                                    b"font16x" => Some(3),
-                                    _ => None,
-                                }
-                            });
+                            .and_then(|string| FONT_NAMES.iter().position(|&x| x == string))
+                            .map(|x| x as u32);
                         if let Some(offset) = offset {
                             let ctx = ctrl.ctx();
                             ctrl.skip_operation();
@@ -134,7 +130,7 @@ impl<'a, 'e, E: ExecutionState<'e>> scarf::Analyzer<'e> for FontsAnalyzer<'a, 'e
                     };
                     let cand = &mut self.candidates[index];
                     cand.seen_bits |= 1 << offset;
-                    if cand.seen_bits == 0xf {
+                    if cand.seen_bits == (1 << FONT_NAMES.len()) - 1 {
                         self.result = EntryOf::Ok(cand.base);
                         ctrl.end_analysis();
                     }
@@ -53,6 +53,16 @@ pub(crate) struct UnitSpeed<Va: VirtualAddress> {
     pub buffed_turn_speed: Option<Va>,
 }
 
+pub(crate) struct UnitStepEnergy<Va: VirtualAddress> {
+    pub step_energy: Option<Va>,
+    pub energy_offset: u16,
+}
+
+pub(crate) struct StepConstruction<Va: VirtualAddress> {
+    pub step_construction: Option<Va>,
+    pub remaining_build_time_offset: u16,
+}
+
 pub(crate) struct StepActiveUnitAnalysis<'e, Va: VirtualAddress> {
     pub step_unit_movement: Option<Va>,
     pub should_vision_update: Option<Operand<'e>>,
@@ -118,6 +128,53 @@ pub(crate) struct KillUnit<Va: VirtualAddress> {
     pub remove_unit_ai: Option<Va>,
 }
 
+/// Offsets of the order target fields in unit struct.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct OrderTargetOffsets {
+    /// `unit.order_target.unit`, pointer to the target unit.
+    pub target_unit: u16,
+    /// `unit.order_target.pos.x`, u16.
+    pub target_x: u16,
+    /// `unit.order_target.pos.y`, u16.
+    pub target_y: u16,
+}
+
+/// Offsets of the speed fields in flingy part of unit struct.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct FlingySpeedOffsets {
+    /// `flingy.current_speed`, u32.
+    pub current_speed: u16,
+    /// `flingy.top_speed`, u32.
+    pub top_speed: u16,
+    /// `flingy.acceleration`, u16.
+    pub acceleration: u16,
+    /// `flingy.turn_speed`, u8.
+    pub turn_speed: u16,
+}
+
+/// Offsets of status effect timers in unit struct.
+/// Only the ones that were seen being decremented in `step_unit_status_effects` are `Some`.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct UnitStatusTimers {
+    pub stim: Option<u16>,
+    pub ensnare: Option<u16>,
+    pub lockdown: Option<u16>,
+    pub irradiate: Option<u16>,
+    pub stasis: Option<u16>,
+    pub plague: Option<u16>,
+    pub maelstrom: Option<u16>,
+}
+
+pub(crate) struct StepUnitStatusEffects<Va: VirtualAddress> {
+    pub step_unit_status_effects: Option<Va>,
+    pub timers: UnitStatusTimers,
+}
+
+pub(crate) struct UnitRank<Va: VirtualAddress> {
+    pub update_unit_rank: Option<Va>,
+    pub rank_offset: u16,
+}
+
 pub(crate) struct OrderUnitMorph<Va: VirtualAddress> {
     pub transform_unit: Option<Va>,
     pub add_ai_to_trained_unit: Option<Va>,
@@ -1414,6 +1471,49 @@ impl<'a, 'e, E: ExecutionState<'e>> analysis::Analyzer<'e> for FindPlayerGainedU
     }
 }
 
+pub(crate) fn unit_build_queue_offset<'e, E: ExecutionState<'e>>(
+    actx: &AnalysisCtx<'e, E>,
+    clear_build_queue: E::VirtualAddress,
+) -> Option<u16> {
+    let ctx = actx.ctx;
+    let binary = actx.binary;
+
+    // clear_build_queue(this = unit) writes 0xe4 to every queue entry,
+    // the lowest offset written is start of the queue.
+    let mut analysis = FuncAnalysis::new(binary, ctx, clear_build_queue);
+    let mut analyzer = BuildQueueOffsetAnalyzer::<E> {
+        result: None,
+        phantom: Default::default(),
+    };
+    analysis.analyze(&mut analyzer);
+    analyzer.result
+}
+
+struct BuildQueueOffsetAnalyzer<'e, E: ExecutionState<'e>> {
+    result: Option<u16>,
+    phantom: std::marker::PhantomData<(*const E, &'e ())>,
+}
+
+impl<'e, E: ExecutionState<'e>> analysis::Analyzer<'e> for BuildQueueOffsetAnalyzer<'e, E> {
+    type State = analysis::DefaultState;
+    type Exec = E;
+    fn operation(&mut self, ctrl: &mut Control<'e, '_, '_, Self>, op: &Operation<'e>) {
+        let ctx = ctrl.ctx();
+        if let Operation::Move(DestOperand::Memory(ref mem), val) = *op {
+            let (base, offset) = ctrl.resolve_mem(mem).address();
+            if base == ctx.register(1) && offset < 0x400 {
+                let val_u16 = ctx.and_const(ctrl.resolve(val), 0xffff);
+                if val_u16.if_constant() == Some(0xe4) {
+                    let offset = offset as u16;
+                    if self.result.map(|x| offset < x).unwrap_or(true) {
+                        self.result = Some(offset);
+                    }
+                }
+            }
+        }
+    }
+}
+
 pub(crate) fn unit_apply_speed_upgrades<'e, E: ExecutionState<'e>>(
     actx: &AnalysisCtx<'e, E>,
     units_dat: (E::VirtualAddress, u32),
@@ -1700,6 +1800,86 @@ impl<'a, 'e, E: ExecutionState<'e>> UnitSpeedAnalyzer<'a, 'e, E> {
     }
 }
 
+pub(crate) fn flingy_speed_offsets<'e, E: ExecutionState<'e>>(
+    actx: &AnalysisCtx<'e, E>,
+    update_speed: E::VirtualAddress,
+    buffed_flingy_speed: E::VirtualAddress,
+    buffed_acceleration: E::VirtualAddress,
+    buffed_turn_speed: E::VirtualAddress,
+) -> Option<FlingySpeedOffsets> {
+    let ctx = actx.ctx;
+    let binary = actx.binary;
+    // update_speed does
+    //  this.top_speed = buffed_flingy_speed(this, flingy_dat_speed[..])
+    //  this.acceleration = buffed_acceleration(this, flingy_dat_acceleration[..])
+    //  this.turn_speed = buffed_turn_speed(this, flingy_dat_turn_speed[..])
+    // Replace the call results with Custom(0..3) and take the stores of them.
+    let mut analysis = FuncAnalysis::new(binary, ctx, update_speed);
+    let mut analyzer = FlingySpeedOffsetsAnalyzer::<E> {
+        funcs: [buffed_flingy_speed, buffed_acceleration, buffed_turn_speed],
+        offsets: [None; 3],
+    };
+    analysis.analyze(&mut analyzer);
+    let [top_speed, acceleration, turn_speed] = analyzer.offsets;
+    let top_speed = top_speed?;
+    Some(FlingySpeedOffsets {
+        // current_speed isn't written by update_speed, it directly follows top_speed.
+        current_speed: top_speed.checked_add(4)?,
+        top_speed,
+        acceleration: acceleration?,
+        turn_speed: turn_speed?,
+    })
+}
+
+struct FlingySpeedOffsetsAnalyzer<'e, E: ExecutionState<'e>> {
+    funcs: [E::VirtualAddress; 3],
+    offsets: [Option<u16>; 3],
+}
+
+impl<'e, E: ExecutionState<'e>> analysis::Analyzer<'e> for FlingySpeedOffsetsAnalyzer<'e, E> {
+    type State = analysis::DefaultState;
+    type Exec = E;
+    fn operation(&mut self, ctrl: &mut Control<'e, '_, '_, Self>, op: &Operation<'e>) {
+        let ctx = ctrl.ctx();
+        match *op {
+            Operation::Call(dest) => {
+                if let Some(dest) = ctrl.resolve_va(dest) {
+                    if let Some(idx) = self.funcs.iter().position(|&x| x == dest) {
+                        ctrl.do_call_with_result(ctx.custom(idx as u32));
+                    }
+                }
+            }
+            Operation::Move(DestOperand::Memory(ref mem), value) => {
+                let value = ctrl.resolve(value);
+                let idx = match value.unwrap_and_mask().if_custom() {
+                    Some(s) if (s as usize) < self.offsets.len() => s as usize,
+                    _ => return,
+                };
+                let expected_size = match idx {
+                    0 => MemAccessSize::Mem32,
+                    1 => MemAccessSize::Mem16,
+                    _ => MemAccessSize::Mem8,
+                };
+                if mem.size != expected_size {
+                    return;
+                }
+                let mem = ctrl.resolve_mem(mem);
+                let (base, offset) = mem.address();
+                if base != ctx.register(1) {
+                    return;
+                }
+                if let Ok(offset) = u16::try_from(offset) {
+                    single_result_assign(Some(offset), &mut self.offsets[idx]);
+                    if self.offsets.iter().all(|x| x.is_some()) {
+                        ctrl.end_analysis();
+                    }
+                }
+            }
+            _ => (),
+        }
+    }
+}
+
 pub(crate) fn analyze_step_active_unit<'e, E: ExecutionState<'e>>(
     actx: &AnalysisCtx<'e, E>,
     step_active_unit: E::VirtualAddress,
@@ -1819,6 +1999,495 @@ impl<'a, 'acx, 'e, E: ExecutionState<'e>> StepActiveAnalyzer<'a, 'acx, 'e, E> {
     }
 }
 
+pub(crate) fn analyze_unit_step_energy<'e, E: ExecutionState<'e>>(
+    actx: &AnalysisCtx<'e, E>,
+    step_active_unit: E::VirtualAddress,
+) -> UnitStepEnergy<E::VirtualAddress> {
+    let mut result = UnitStepEnergy {
+        step_energy: None,
+        energy_offset: 0,
+    };
+    let ctx = actx.ctx;
+    let binary = actx.binary;
+    // Find function with this = unit (possibly called from another this = unit function)
+    // which does unit.energy = unit.energy + 8 (and clamps to max energy)
+    let mut analysis = FuncAnalysis::new(binary, ctx, step_active_unit);
+    let mut analyzer = StepEnergyAnalyzer::<E> {
+        result: &mut result,
+        inline_depth: 0,
+        inline_limit: 0,
+    };
+    analysis.analyze(&mut analyzer);
+    result
+}
+
+pub(crate) fn cloak_energy_drain<'e, E: ExecutionState<'e>>(
+    actx: &AnalysisCtx<'e, E>,
+    update_cloak_state: E::VirtualAddress,
+    energy_offset: u16,
+) -> Option<E::VirtualAddress> {
+    let ctx = actx.ctx;
+    let binary = actx.binary;
+    // Find function with this = unit (update_cloak_state itself, or called from it)
+    // which does unit.energy = unit.energy - x
+    let mut analysis = FuncAnalysis::new(binary, ctx, update_cloak_state);
+    let mut analyzer = CloakEnergyDrainAnalyzer::<E> {
+        result: None,
+        found_drain: false,
+        energy_offset: energy_offset as u64,
+        inline_depth: 0,
+    };
+    analysis.analyze(&mut analyzer);
+    if analyzer.found_drain && analyzer.result.is_none() {
+        // Energy was drained directly in update_cloak_state
+        Some(update_cloak_state)
+    } else {
+        analyzer.result
+    }
+}
+
+struct CloakEnergyDrainAnalyzer<'e, E: ExecutionState<'e>> {
+    result: Option<E::VirtualAddress>,
+    /// Set once the energy write has been seen; result is then the innermost
+    /// function that contained it.
+    found_drain: bool,
+    energy_offset: u64,
+    inline_depth: u8,
+}
+
+impl<'e, E: ExecutionState<'e>> analysis::Analyzer<'e> for CloakEnergyDrainAnalyzer<'e, E> {
+    type State = analysis::DefaultState;
+    type Exec = E;
+    fn operation(&mut self, ctrl: &mut Control<'e, '_, '_, Self>, op: &Operation<'e>) {
+        let ctx = ctrl.ctx();
+        match *op {
+            Operation::Call(dest) if self.inline_depth < 2 => {
+                if ctrl.resolve_register(1) != ctx.register(1) {
+                    return;
+                }
+                if let Some(dest) = ctrl.resolve_va(dest) {
+                    self.inline_depth += 1;
+                    ctrl.analyze_with_current_state(self, dest);
+                    self.inline_depth -= 1;
+                    if self.found_drain {
+                        if self.result.is_none() {
+                            self.result = Some(dest);
+                        }
+                        ctrl.end_analysis();
+                    }
+                }
+            }
+            Operation::Move(DestOperand::Memory(ref mem), value) => {
+                if mem.size != MemAccessSize::Mem16 {
+                    return;
+                }
+                let mem = ctrl.resolve_mem(mem);
+                let (base, offset) = mem.address();
+                if base != ctx.register(1) || offset != self.energy_offset {
+                    return;
+                }
+                let value = ctrl.resolve(value);
+                let ok = value.unwrap_and_mask()
+                    .if_arithmetic_sub()
+                    .and_then(|(l, _)| l.unwrap_and_mask().if_mem16_offset(offset))
+                    .filter(|&x| x == base)
+                    .is_some();
+                if ok {
+                    self.found_drain = true;
+                    ctrl.end_analysis();
+                }
+            }
+            _ => (),
+        }
+    }
+}
+
+struct StepEnergyAnalyzer<'a, 'e, E: ExecutionState<'e>> {
+    result: &'a mut UnitStepEnergy<E::VirtualAddress>,
+    inline_depth: u8,
+    inline_limit: u8,
+}
+
+impl<'a, 'e, E: ExecutionState<'e>> analysis::Analyzer<'e> for StepEnergyAnalyzer<'a, 'e, E> {
+    type State = analysis::DefaultState;
+    type Exec = E;
+    fn operation(&mut self, ctrl: &mut Control<'e, '_, '_, Self>, op: &Operation<'e>) {
+        let ctx = ctrl.ctx();
+        if self.inline_depth != 0 {
+            if matches!(*op, Operation::Call(..) | Operation::Jump { .. }) {
+                if self.inline_limit == 0 {
+                    ctrl.end_analysis();
+                    return;
+                }
+                self.inline_limit -= 1;
+            }
+        }
+        match *op {
+            Operation::Call(dest) if self.inline_depth < 2 => {
+                if ctrl.resolve_register(1) != ctx.register(1) {
+                    return;
+                }
+                if let Some(dest) = ctrl.resolve_va(dest) {
+                    let old_limit = self.inline_limit;
+                    self.inline_depth += 1;
+                    self.inline_limit = 24;
+                    ctrl.analyze_with_current_state(self, dest);
+                    self.inline_depth -= 1;
+                    self.inline_limit = old_limit;
+                    if let Some(result) = self.result.step_energy {
+                        if result == E::VirtualAddress::from_u64(0) {
+                            self.result.step_energy = Some(dest);
+                        }
+                        ctrl.end_analysis();
+                    }
+                }
+            }
+            Operation::Move(DestOperand::Memory(ref mem), value) if self.inline_depth != 0 => {
+                if mem.size != MemAccessSize::Mem16 {
+                    return;
+                }
+                let mem = ctrl.resolve_mem(mem);
+                let (base, offset) = mem.address();
+                if base != ctx.register(1) || offset > 0x400 {
+                    return;
+                }
+                let value = ctrl.resolve(value);
+                let ok = value.unwrap_and_mask()
+                    .if_arithmetic_add_const(8)
+                    .and_then(|x| x.unwrap_and_mask().if_mem16_offset(offset))
+                    .filter(|&x| x == base)
+                    .is_some();
+                if ok {
+                    self.result.energy_offset = offset as u16;
+                    self.result.step_energy = Some(E::VirtualAddress::from_u64(0));
+                    ctrl.end_analysis();
+                }
+            }
+            _ => (),
+        }
+    }
+}
+
+pub(crate) fn analyze_step_construction<'e, E: ExecutionState<'e>>(
+    actx: &AnalysisCtx<'e, E>,
+    step_active_unit: E::VirtualAddress,
+) -> StepConstruction<E::VirtualAddress> {
+    let mut result = StepConstruction {
+        step_construction: None,
+        remaining_build_time_offset: 0,
+    };
+    let ctx = actx.ctx;
+    let binary = actx.binary;
+    // Find function with this = unit (possibly called from another this = unit function)
+    // which does both
+    //      unit.remaining_build_time = unit.remaining_build_time - x
+    //      unit.hitpoints = unit.hitpoints + y
+    let mut analysis = FuncAnalysis::new(binary, ctx, step_active_unit);
+    let mut analyzer = StepConstructionAnalyzer::<E> {
+        result: &mut result,
+        inline_depth: 0,
+        inline_limit: 0,
+        build_time_offset: None,
+        hp_changed: false,
+    };
+    analysis.analyze(&mut analyzer);
+    result
+}
+
+pub(crate) fn spawn_larva<'e, E: ExecutionState<'e>>(
+    actx: &AnalysisCtx<'e, E>,
+    step_active_unit: E::VirtualAddress,
+    create_unit: E::VirtualAddress,
+) -> Option<E::VirtualAddress> {
+    let ctx = actx.ctx;
+    let binary = actx.binary;
+    let bump = &actx.bump;
+    // Hatcheries periodically call spawn_larva(this = hatchery),
+    // which creates a larva with create_unit(0x23, x, y, player).
+    // Find function with this = unit (possibly few calls deep) that calls create_unit
+    // with unit id 0x23.
+    let mut analysis = FuncAnalysis::new(binary, ctx, step_active_unit);
+    let mut analyzer = SpawnLarvaAnalyzer::<E> {
+        result: None,
+        create_unit,
+        found_create_larva: false,
+        inline_depth: 0,
+        checked_calls: bumpvec_with_capacity(0x20, bump),
+    };
+    analysis.analyze(&mut analyzer);
+    analyzer.result
+}
+
+struct SpawnLarvaAnalyzer<'acx, 'e, E: ExecutionState<'e>> {
+    result: Option<E::VirtualAddress>,
+    create_unit: E::VirtualAddress,
+    /// Set once create_unit(0x23, ...) has been seen; result is then the
+    /// innermost inlined function that contained the call.
+    found_create_larva: bool,
+    inline_depth: u8,
+    checked_calls: BumpVec<'acx, E::VirtualAddress>,
+}
+
+impl<'acx, 'e: 'acx, E: ExecutionState<'e>> analysis::Analyzer<'e> for
+    SpawnLarvaAnalyzer<'acx, 'e, E>
+{
+    type State = analysis::DefaultState;
+    type Exec = E;
+    fn operation(&mut self, ctrl: &mut Control<'e, '_, '_, Self>, op: &Operation<'e>) {
+        let ctx = ctrl.ctx();
+        if let Operation::Call(dest) = *op {
+            let dest = match ctrl.resolve_va(dest) {
+                Some(s) => s,
+                None => return,
+            };
+            if dest == self.create_unit {
+                let is_larva = ctrl.resolve_arg(0).unwrap_and_mask().if_constant() == Some(0x23);
+                if is_larva && self.inline_depth != 0 {
+                    self.found_create_larva = true;
+                    ctrl.end_analysis();
+                }
+                return;
+            }
+            if self.inline_depth >= 3 || ctrl.resolve_register(1) != ctx.register(1) {
+                return;
+            }
+            if self.checked_calls.iter().any(|&x| x == dest) {
+                return;
+            }
+            self.checked_calls.push(dest);
+            self.inline_depth += 1;
+            ctrl.analyze_with_current_state(self, dest);
+            self.inline_depth -= 1;
+            if self.found_create_larva {
+                if self.result.is_none() {
+                    self.result = Some(dest);
+                }
+                ctrl.end_analysis();
+            }
+        }
+    }
+}
+
+pub(crate) fn analyze_step_unit_status_effects<'e, E: ExecutionState<'e>>(
+    actx: &AnalysisCtx<'e, E>,
+    step_active_unit: E::VirtualAddress,
+) -> StepUnitStatusEffects<E::VirtualAddress> {
+    let mut result = StepUnitStatusEffects {
+        step_unit_status_effects: None,
+        timers: UnitStatusTimers::default(),
+    };
+    let ctx = actx.ctx;
+    let binary = actx.binary;
+    let bump = &actx.bump;
+    // Find function with this = unit (possibly called from another this = unit function)
+    // which decrements at least two of the status effect timers
+    //      unit.stim_timer = unit.stim_timer - 1
+    //      unit.irradiate_timer = unit.irradiate_timer - 1
+    //      etc.
+    // All branches of the function have to be checked before deciding, so the timers
+    // are collected to a bitmask per function.
+    let mut analysis = FuncAnalysis::new(binary, ctx, step_active_unit);
+    let mut analyzer = StepStatusEffectsAnalyzer::<E> {
+        result: &mut result,
+        inline_depth: 0,
+        decremented_timers: 0,
+        checked_calls: bumpvec_with_capacity(0x20, bump),
+    };
+    analysis.analyze(&mut analyzer);
+    result
+}
+
+struct StepStatusEffectsAnalyzer<'a, 'acx, 'e, E: ExecutionState<'e>> {
+    result: &'a mut StepUnitStatusEffects<E::VirtualAddress>,
+    inline_depth: u8,
+    /// Bits are indices to `status_timer_offsets()`
+    decremented_timers: u8,
+    checked_calls: BumpVec<'acx, E::VirtualAddress>,
+}
+
+impl<'a, 'acx, 'e: 'acx, E: ExecutionState<'e>> StepStatusEffectsAnalyzer<'a, 'acx, 'e, E> {
+    fn status_timer_offsets() -> [u64; 7] {
+        let layouts = E::struct_layouts();
+        [
+            layouts.unit_stim_timer(),
+            layouts.unit_ensnare_timer(),
+            layouts.unit_lockdown_timer(),
+            layouts.unit_irradiate_timer(),
+            layouts.unit_stasis_timer(),
+            layouts.unit_plague_timer(),
+            layouts.unit_maelstrom_timer(),
+        ]
+    }
+}
+
+impl<'a, 'acx, 'e: 'acx, E: ExecutionState<'e>> analysis::Analyzer<'e> for
+    StepStatusEffectsAnalyzer<'a, 'acx, 'e, E>
+{
+    type State = analysis::DefaultState;
+    type Exec = E;
+    fn operation(&mut self, ctrl: &mut Control<'e, '_, '_, Self>, op: &Operation<'e>) {
+        let ctx = ctrl.ctx();
+        match *op {
+            Operation::Call(dest) if self.inline_depth < 3 => {
+                if ctrl.resolve_register(1) != ctx.register(1) {
+                    return;
+                }
+                if let Some(dest) = ctrl.resolve_va(dest) {
+                    if self.checked_calls.iter().any(|&x| x == dest) {
+                        return;
+                    }
+                    self.checked_calls.push(dest);
+                    let old_timers = self.decremented_timers;
+                    self.decremented_timers = 0;
+                    self.inline_depth += 1;
+                    ctrl.analyze_with_current_state(self, dest);
+                    self.inline_depth -= 1;
+                    if self.result.step_unit_status_effects.is_some() {
+                        ctrl.end_analysis();
+                        return;
+                    }
+                    let timers = self.decremented_timers;
+                    if timers.count_ones() >= 2 {
+                        let offsets = Self::status_timer_offsets();
+                        let get = |i: usize| {
+                            Some(offsets[i] as u16).filter(|_| timers & (1 << i) != 0)
+                        };
+                        self.result.timers = UnitStatusTimers {
+                            stim: get(0),
+                            ensnare: get(1),
+                            lockdown: get(2),
+                            irradiate: get(3),
+                            stasis: get(4),
+                            plague: get(5),
+                            maelstrom: get(6),
+                        };
+                        self.result.step_unit_status_effects = Some(dest);
+                        ctrl.end_analysis();
+                        return;
+                    }
+                    self.decremented_timers = old_timers;
+                }
+            }
+            Operation::Move(DestOperand::Memory(ref mem), value) if self.inline_depth != 0 => {
+                if mem.size != MemAccessSize::Mem8 {
+                    return;
+                }
+                let mem = ctrl.resolve_mem(mem);
+                let (base, offset) = mem.address();
+                if base != ctx.register(1) {
+                    return;
+                }
+                let index = match Self::status_timer_offsets().iter().position(|&x| x == offset) {
+                    Some(s) => s,
+                    None => return,
+                };
+                let value = ctrl.resolve(value).unwrap_and_mask();
+                let is_decrement = value.if_arithmetic_sub_const(1)
+                    .or_else(|| value.if_arithmetic_add_const(0xff))
+                    .and_then(|x| x.unwrap_and_mask().if_mem8_offset(offset))
+                    .filter(|&x| x == base)
+                    .is_some();
+                if is_decrement {
+                    self.decremented_timers |= 1 << index;
+                }
+            }
+            _ => (),
+        }
+    }
+}
+
+struct StepConstructionAnalyzer<'a, 'e, E: ExecutionState<'e>> {
+    result: &'a mut StepConstruction<E::VirtualAddress>,
+    inline_depth: u8,
+    inline_limit: u8,
+    build_time_offset: Option<u16>,
+    hp_changed: bool,
+}
+
+impl<'a, 'e, E: ExecutionState<'e>> analysis::Analyzer<'e> for
+    StepConstructionAnalyzer<'a, 'e, E>
+{
+    type State = analysis::DefaultState;
+    type Exec = E;
+    fn operation(&mut self, ctrl: &mut Control<'e, '_, '_, Self>, op: &Operation<'e>) {
+        let ctx = ctrl.ctx();
+        if self.inline_depth != 0 {
+            if matches!(*op, Operation::Call(..) | Operation::Jump { .. }) {
+                if self.inline_limit == 0 {
+                    ctrl.end_analysis();
+                    return;
+                }
+                self.inline_limit -= 1;
+            }
+        }
+        match *op {
+            Operation::Call(dest) if self.inline_depth < 2 => {
+                if ctrl.resolve_register(1) != ctx.register(1) {
+                    return;
+                }
+                if let Some(dest) = ctrl.resolve_va(dest) {
+                    let old_limit = self.inline_limit;
+                    self.inline_depth += 1;
+                    self.inline_limit = 32;
+                    self.build_time_offset = None;
+                    self.hp_changed = false;
+                    ctrl.analyze_with_current_state(self, dest);
+                    self.inline_depth -= 1;
+                    self.inline_limit = old_limit;
+                    if let Some(result) = self.result.step_construction {
+                        if result == E::VirtualAddress::from_u64(0) {
+                            self.result.step_construction = Some(dest);
+                        }
+                        ctrl.end_analysis();
+                    }
+                }
+            }
+            Operation::Move(DestOperand::Memory(ref mem), value) if self.inline_depth != 0 => {
+                let hp_offset = E::VirtualAddress::SIZE as u64 * 2;
+                let is_hp = match mem.size {
+                    MemAccessSize::Mem16 => false,
+                    MemAccessSize::Mem32 => true,
+                    _ => return,
+                };
+                let mem = ctrl.resolve_mem(mem);
+                let (base, offset) = mem.address();
+                if base != ctx.register(1) || offset > 0x400 {
+                    return;
+                }
+                let value = ctrl.resolve(value);
+                if is_hp {
+                    if offset != hp_offset {
+                        return;
+                    }
+                    let ok = value.if_arithmetic_add()
+                        .and_either(|x| x.if_mem32_offset(offset).filter(|&x| x == base))
+                        .is_some();
+                    if ok {
+                        self.hp_changed = true;
+                    }
+                } else {
+                    let ok = value.unwrap_and_mask()
+                        .if_arithmetic_sub()
+                        .and_then(|(l, _)| l.unwrap_and_mask().if_mem16_offset(offset))
+                        .filter(|&x| x == base)
+                        .is_some();
+                    if ok {
+                        self.build_time_offset = Some(offset as u16);
+                    }
+                }
+                if self.hp_changed {
+                    if let Some(offset) = self.build_time_offset {
+                        self.result.remaining_build_time_offset = offset;
+                        self.result.step_construction = Some(E::VirtualAddress::from_u64(0));
+                        ctrl.end_analysis();
+                    }
+                }
+            }
+            _ => (),
+        }
+    }
+}
+
 pub(crate) fn analyze_step_hidden_unit<'e, E: ExecutionState<'e>>(
     actx: &AnalysisCtx<'e, E>,
     step_hidden_unit: E::VirtualAddress,
@@ -3159,6 +3828,90 @@ pub(crate) fn analyze_kill_unit<'e, E: ExecutionState<'e>>(
     result
 }
 
+/// Vanilla BW doesn't have unit ranks; this is for mods which add a rank
+/// (veterancy) byte that gets incremented when the unit kills something,
+/// followed by update_unit_rank(this = killer) call.
+pub(crate) fn analyze_unit_rank<'e, E: ExecutionState<'e>>(
+    actx: &AnalysisCtx<'e, E>,
+    kill_unit: E::VirtualAddress,
+) -> UnitRank<E::VirtualAddress> {
+    let mut result = UnitRank {
+        update_unit_rank: None,
+        rank_offset: u16::MAX,
+    };
+    let ctx = actx.ctx;
+    let binary = actx.binary;
+    let mut analysis = FuncAnalysis::new(binary, ctx, kill_unit);
+    let mut analyzer = UnitRankAnalyzer::<E> {
+        result: &mut result,
+        incremented: None,
+        inline_depth: 0,
+    };
+    analysis.analyze(&mut analyzer);
+    result
+}
+
+struct UnitRankAnalyzer<'a, 'e, E: ExecutionState<'e>> {
+    result: &'a mut UnitRank<E::VirtualAddress>,
+    /// Unit and offset of `Mem8[unit + offset] += 1`
+    incremented: Option<(Operand<'e>, u16)>,
+    inline_depth: u8,
+}
+
+impl<'a, 'e, E: ExecutionState<'e>> analysis::Analyzer<'e> for UnitRankAnalyzer<'a, 'e, E> {
+    type State = analysis::DefaultState;
+    type Exec = E;
+    fn operation(&mut self, ctrl: &mut Control<'e, '_, '_, Self>, op: &Operation<'e>) {
+        let ctx = ctrl.ctx();
+        match *op {
+            Operation::Call(dest) => {
+                let dest = match ctrl.resolve_va(dest) {
+                    Some(s) => s,
+                    None => return,
+                };
+                if let Some((unit, offset)) = self.incremented {
+                    if ctrl.resolve_register(1) == unit {
+                        self.result.update_unit_rank = Some(dest);
+                        self.result.rank_offset = offset;
+                        ctrl.end_analysis();
+                    }
+                } else if self.inline_depth == 0 {
+                    self.inline_depth += 1;
+                    ctrl.analyze_with_current_state(self, dest);
+                    self.inline_depth -= 1;
+                    if self.result.update_unit_rank.is_some() {
+                        ctrl.end_analysis();
+                    }
+                }
+            }
+            Operation::Move(DestOperand::Memory(ref mem), value) => {
+                if mem.size != MemAccessSize::Mem8 || self.incremented.is_some() {
+                    return;
+                }
+                let mem = ctrl.resolve_mem(mem);
+                let (base, offset) = mem.address();
+                // The killed unit is `this`, rank would be incremented for the killer.
+                // Vanilla increments killer's kill count in the same way, so skip that.
+                if base == ctx.register(1) || offset > 0x400 || is_global(base) ||
+                    offset == E::struct_layouts().unit_kills()
+                {
+                    return;
+                }
+                let value = ctrl.resolve(value);
+                let is_increment = Operand::and_masked(value).0
+                    .if_arithmetic_add_const(1)
+                    .and_then(|x| x.if_mem8())
+                    .filter(|x| x.address() == (base, offset))
+                    .is_some();
+                if is_increment {
+                    self.incremented = Some((base, offset as u16));
+                }
+            }
+            _ => (),
+        }
+    }
+}
+
 struct KillUnitAnalyzer<'a, 'acx, 'e, E: ExecutionState<'e>> {
     result: &'a mut KillUnit<E::VirtualAddress>,
     arg_cache: &'a ArgCache<'e, E>,
@@ -3609,3 +4362,99 @@ fn seems_int_div_by_32<'e>(op: Operand<'e>) -> bool {
         .and_either_other(|x| x.if_arithmetic_and_const(0x1f))
         .is_some()
 }
+
+/// Finds `is_building(unit_id)`, a function that only returns
+/// `units_dat_flags[unit_id] & 1`.
+///
+/// Most code reads units.dat flags inline, so this may not exist in every build,
+/// in which case the result is None.
+pub(crate) fn is_building_fn<'e, E: ExecutionState<'e>>(
+    actx: &AnalysisCtx<'e, E>,
+    units_dat: (E::VirtualAddress, u32),
+    functions: &FunctionFinder<'_, 'e, E>,
+) -> Option<E::VirtualAddress> {
+    let binary = actx.binary;
+    let ctx = actx.ctx;
+    let units_dat_flags = binary.read_address(units_dat.0 + 0x16 * units_dat.1).ok()?;
+    let mut global_refs = functions.find_functions_using_global(actx, units_dat_flags);
+    global_refs.sort_unstable_by_key(|x| x.func_entry);
+    global_refs.dedup_by_key(|x| x.func_entry);
+    let mut result = None;
+    for global in &global_refs {
+        let mut analyzer = IsBuildingFn::<E> {
+            arg_cache: &actx.arg_cache,
+            units_dat_flags: units_dat_flags.as_u64(),
+            flag_checked: false,
+            is_ok: false,
+            failed: false,
+        };
+        let mut analysis = FuncAnalysis::new(binary, ctx, global.func_entry);
+        analysis.analyze(&mut analyzer);
+        let new = Some(global.func_entry).filter(|_| analyzer.is_ok && !analyzer.failed);
+        if single_result_assign(new, &mut result) {
+            break;
+        }
+    }
+    result
+}
+
+struct IsBuildingFn<'a, 'e, E: ExecutionState<'e>> {
+    arg_cache: &'a ArgCache<'e, E>,
+    units_dat_flags: u64,
+    /// Set when a branch depends on the building flag, the function is then
+    /// expected to return constant 0 / 1.
+    flag_checked: bool,
+    is_ok: bool,
+    failed: bool,
+}
+
+impl<'a, 'e, E: ExecutionState<'e>> analysis::Analyzer<'e> for IsBuildingFn<'a, 'e, E> {
+    type State = analysis::DefaultState;
+    type Exec = E;
+    fn operation(&mut self, ctrl: &mut Control<'e, '_, '_, Self>, op: &Operation<'e>) {
+        match *op {
+            Operation::Call(..) => {
+                self.failed = true;
+                ctrl.end_analysis();
+            }
+            Operation::Jump { condition, .. } => {
+                let ctx = ctrl.ctx();
+                let condition = ctrl.resolve(condition);
+                let is_flag_check = condition.if_arithmetic_eq_neq_zero(ctx)
+                    .filter(|x| self.is_building_flag(x.0))
+                    .is_some();
+                if is_flag_check {
+                    self.flag_checked = true;
+                }
+            }
+            Operation::Return(..) => {
+                let ret = ctrl.resolve_register(0);
+                let ok = self.is_building_flag(ret) ||
+                    (self.flag_checked && matches!(ret.if_constant(), Some(0) | Some(1)));
+                if ok {
+                    self.is_ok = true;
+                } else {
+                    self.failed = true;
+                    ctrl.end_analysis();
+                }
+            }
+            _ => (),
+        }
+    }
+}
+
+impl<'a, 'e, E: ExecutionState<'e>> IsBuildingFn<'a, 'e, E> {
+    /// Checks for `units_dat_flags[a1] & 1`, either as a u32 or u8 read.
+    fn is_building_flag(&self, op: Operand<'e>) -> bool {
+        let arg1 = self.arg_cache.on_entry(0);
+        op.if_arithmetic_and_const(1)
+            .and_then(|x| x.unwrap_and_mask().if_memory())
+            .filter(|mem| matches!(mem.size, MemAccessSize::Mem32 | MemAccessSize::Mem8))
+            .map(|mem| mem.address())
+            .filter(|&(index, offset)| {
+                offset == self.units_dat_flags &&
+                    index.iter().any(|x| x == arg1 || x.if_register() == Some(1))
+            })
+            .is_some()
+    }
+}
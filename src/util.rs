@@ -538,6 +538,106 @@ fn is_global_rec(op: Operand<'_>) -> bool {
     }
 }
 
+/// Writes a textual form of `op` that doesn't depend on `Debug`/`Display` formatting
+/// or interning order of the `OperandContext`.
+///
+/// Registers are named by `word_size`, constants are written in hex, memory as
+/// `MemN[base + offset]` (or `- offset` when negative) and arithmetic as `(left op right)`,
+/// with float arithmetic using `fN.op` tokens. Undefined operands are all written as `undef`,
+/// as their ids depend on analysis order.
+pub fn write_operand_signature(out: &mut String, op: Operand<'_>, word_size: MemAccessSize) {
+    use std::fmt::Write;
+
+    static REGISTERS_32: [&str; 8] = ["eax", "ecx", "edx", "ebx", "esp", "ebp", "esi", "edi"];
+    static REGISTERS_64: [&str; 16] = [
+        "rax", "rcx", "rdx", "rbx", "rsp", "rbp", "rsi", "rdi",
+        "r8", "r9", "r10", "r11", "r12", "r13", "r14", "r15",
+    ];
+    let registers: &[&str] = match word_size {
+        MemAccessSize::Mem64 => &REGISTERS_64,
+        _ => &REGISTERS_32,
+    };
+    match *op.ty() {
+        OperandType::Arch(arch) => {
+            match arch.if_register().and_then(|reg| registers.get(reg as usize)) {
+                Some(name) => out.push_str(name),
+                None => {
+                    let _ = write!(out, "arch_{:x}", arch.value());
+                }
+            }
+        }
+        OperandType::Constant(c) => {
+            let _ = write!(out, "0x{:x}", c);
+        }
+        OperandType::Custom(c) => {
+            let _ = write!(out, "Custom_{:x}", c);
+        }
+        OperandType::Memory(ref mem) => {
+            let (base, offset) = mem.address();
+            let _ = write!(out, "Mem{}[", mem.size.bytes() * 8);
+            if base.if_constant() == Some(0) {
+                let _ = write!(out, "0x{:x}", offset);
+            } else {
+                write_operand_signature(out, base, word_size);
+                // Offsets wrap at 32 bits on 32-bit executables
+                let signed_offset = if word_size == MemAccessSize::Mem64 || offset > 0xffff_ffff {
+                    offset as i64
+                } else {
+                    offset as u32 as i32 as i64
+                };
+                if signed_offset < 0 {
+                    let _ = write!(out, " - 0x{:x}", signed_offset.unsigned_abs());
+                } else if signed_offset != 0 {
+                    let _ = write!(out, " + 0x{:x}", signed_offset);
+                }
+            }
+            out.push(']');
+        }
+        OperandType::Arithmetic(ref arith) => {
+            out.push('(');
+            write_operand_signature(out, arith.left, word_size);
+            let _ = write!(out, " {} ", arith_op_signature(arith.ty));
+            write_operand_signature(out, arith.right, word_size);
+            out.push(')');
+        }
+        OperandType::ArithmeticFloat(ref arith, size) => {
+            out.push('(');
+            write_operand_signature(out, arith.left, word_size);
+            let _ = write!(out, " f{}.{} ", size.bytes() * 8, arith_op_signature(arith.ty));
+            write_operand_signature(out, arith.right, word_size);
+            out.push(')');
+        }
+        OperandType::SignExtend(val, from, to) => {
+            let _ = write!(out, "signext{}_{}(", from.bytes() * 8, to.bytes() * 8);
+            write_operand_signature(out, val, word_size);
+            out.push(')');
+        }
+        _ if op.is_undefined() => out.push_str("undef"),
+        _ => out.push_str("unknown"),
+    }
+}
+
+fn arith_op_signature(ty: ArithOpType) -> &'static str {
+    match ty {
+        ArithOpType::Add => "+",
+        ArithOpType::Sub => "-",
+        ArithOpType::Mul => "*",
+        ArithOpType::MulHigh => "*hi",
+        ArithOpType::Div => "/",
+        ArithOpType::Modulo => "%",
+        ArithOpType::And => "&",
+        ArithOpType::Or => "|",
+        ArithOpType::Xor => "^",
+        ArithOpType::Lsh => "<<",
+        ArithOpType::Rsh => ">>",
+        ArithOpType::Equal => "==",
+        ArithOpType::GreaterThan => ">",
+        ArithOpType::ToFloat => "to_float",
+        ArithOpType::ToInt => "to_int",
+        _ => "unknown_op",
+    }
+}
+
 pub fn is_stack_address(addr: Operand<'_>) -> bool {
     if let Some((l, r)) = addr.if_arithmetic_sub() {
         r.if_constant().is_some() && l.if_register() == Some(4)
@@ -378,6 +378,7 @@ fn everything_1215f() {
     test_with_extra_checks(Path::new("1215f.exe"), |ctx, analysis| {
         let guard_ai = analysis.first_guard_ai().unwrap();
         assert_eq!(guard_ai, ctx.constant(0x0D258A8));
+        assert_eq!(analysis.guard_ai_size(), 0x20);
 
         let ais = analysis.aiscript_hook().unwrap();
         assert_eq!(ais.return_address.0, 0x005BCB3C);
@@ -919,6 +920,12 @@ fn everything_1232e() {
         assert_eq!(game_data.unwrap(), ctx.constant(0x1070ce0));
         assert_eq!(skins.unwrap(), ctx.constant(0x00E003A0));
         assert_eq!(player_skins.unwrap(), ctx.constant(0x0106F6E0));
+        assert_eq!(analysis.game_mode().unwrap(), ctx.mem16c(0x1070ce0 + 0x6d));
+        assert_eq!(analysis.victory_conditions().unwrap(), ctx.mem8c(0x1070ce0 + 0x75));
+        assert_eq!(analysis.starting_resources().unwrap(), ctx.mem32c(0x1070ce0 + 0x84));
+        let game_data_offsets = analysis.game_data_offsets().unwrap();
+        assert_eq!(game_data_offsets.game_type, 0x6d);
+        assert_eq!(game_data_offsets.map_title, 0x4d);
 
         let select_map_entry = analysis.select_map_entry();
         let is_multiplayer = analysis.is_multiplayer();
@@ -1316,6 +1323,11 @@ fn everything_1236b() {
         let patch = analysis.replay_minimap_unexplored_fog_patch().unwrap();
         assert_eq!(patch.address.0, 0x007423C7);
         assert_eq!(patch.data, &[0x90, 0x90]);
+        let mut image = vec![0u8; 0x10];
+        let image_base = VirtualAddress(0x007423C0);
+        assert!(patch.apply_to_memory_image(&mut image, image_base));
+        assert_eq!(&image[7..9], &[0x90, 0x90]);
+        assert!(!patch.apply_to_memory_image(&mut image[..8], image_base));
     })
 }
 
@@ -1625,7 +1637,19 @@ fn test_nongeneric<'e, E: ExecutionState<'e>>(
                 LookupSoundId | SFileOpenFileEx | SFileReadFileEx | SFileCloseFile |
                 LoadConsoles | InitConsoles | GetUiConsoles | GetStatResIconsDdsGrp |
                 GetUnitSkin | JoinCustomGame | FindFileWithCrc | ForFilesInDir |
-                SimpleFileMatchCallback | GetLocales | InitGameMap | SaveReplay => continue,
+                SimpleFileMatchCallback | GetLocales | InitGameMap | SaveReplay |
+                CloseGameMenu | InitObserver | ChatInputHandler |
+                MountArchive | BlitPcxToSurface | PlaceMapSprite |
+                IsBuildingFn | ComputeSyncChecksum | AiRequestUnit |
+                AiReturnToGuard | AddStatusOverlay | GameToScreenCoords |
+                FinalizeDragSelect | CenterOnTownhall | IscriptDeathCall |
+                UpdateUnitFacing | GetTerrainHeight | ComputeMissChance => continue,
+            // Only exists if a mod has added unit ranks; none of the test executables do.
+            UpdateUnitRank => {
+                assert!(result.is_none(), "Found update_unit_rank on vanilla");
+                assert!(analysis.unit_rank_offset().is_none());
+                continue;
+            }
             _ => (),
         }
         assert!(result.is_some(), "Missing {}", addr.name());
@@ -1646,7 +1670,11 @@ fn test_nongeneric<'e, E: ExecutionState<'e>>(
                 RgbColors | DisableColorChoice | UseMapSetRgbColor | SfxData | SoundChannels |
                 Images | TilesetCv5 | TilesetData | TilesetVx4Ex | TileDefaultFlags |
                 MinitileGraphics | MinitileData | FoliageState | CreepOriginalTiles |
-                CreepTileBorders | CursorScaleFactor =>
+                CreepTileBorders | CursorScaleFactor | LocationArray | DeathCounters |
+                SoundBankHandle | IsObserver |
+                IsRecordingReplay | ChatInputBuffer | ArchiveChainHead |
+                PcxTargetSurface | BulletArray | UnitUnderCursor |
+                PlayerActionCounts | ElevationData =>
             {
                 continue;
             }
@@ -1693,7 +1721,8 @@ fn test_nongeneric<'e, E: ExecutionState<'e>>(
                 FirstFreePlacementImage | LastFreePlacementImage | FirstFreePlacementRect |
                 LastFreePlacementRect | TilesetIndexedMapTiles | Vx4MapTiles | RepulseState |
                 TerrainFramebuf | StatportVideos | StatportTalkingPortraitActive |
-                StatportVideoId | NgdpEnabled =>
+                StatportVideoId | NgdpEnabled | MapStringTable |
+                CurrentSmkContext =>
             {
                 check_global_opt(result, binary, op.name());
             }
@@ -2117,6 +2146,86 @@ fn test_nongeneric<'e, E: ExecutionState<'e>>(
         assert!(join_param_variant_type_offset.is_none());
     }
 
+    let unit_addon_offset = analysis.unit_addon_offset();
+    if E::VirtualAddress::SIZE == 4 {
+        assert_eq!(unit_addon_offset, Some(0xc0));
+    } else {
+        assert_eq!(unit_addon_offset, Some(0x108));
+    }
+    let unit_status_flags_offset = analysis.unit_status_flags_offset();
+    if E::VirtualAddress::SIZE == 4 {
+        assert_eq!(unit_status_flags_offset, 0xdc);
+    } else {
+        assert_eq!(unit_status_flags_offset, 0x140);
+    }
+
+    let timers = analysis.unit_status_timer_offsets().unwrap();
+    if let Some(stim) = timers.stim {
+        if E::VirtualAddress::SIZE == 4 {
+            assert_eq!(stim, 0x115);
+        } else {
+            assert_eq!(stim, 0x1a1);
+        }
+    }
+
+    let order_target = analysis.unit_order_target_offsets();
+    if E::VirtualAddress::SIZE == 4 {
+        assert_eq!(order_target.target_x, 0x58);
+        assert_eq!(order_target.target_y, 0x5a);
+        assert_eq!(order_target.target_unit, 0x5c);
+    } else {
+        assert_eq!(order_target.target_x, 0x78);
+        assert_eq!(order_target.target_y, 0x7a);
+        assert_eq!(order_target.target_unit, 0x80);
+    }
+
+    assert_eq!(analysis.storm_turn_count().unwrap(), ctx.constant(0xc));
+
+    let flingy_speed = analysis.flingy_speed_offsets().unwrap();
+    if E::VirtualAddress::SIZE == 4 {
+        assert_eq!(flingy_speed.top_speed, 0x34);
+        assert_eq!(flingy_speed.current_speed, 0x38);
+        assert_eq!(flingy_speed.acceleration, 0x48);
+        assert_eq!(flingy_speed.turn_speed, 0x22);
+    } else {
+        assert_eq!(flingy_speed.top_speed, 0x4c);
+        assert_eq!(flingy_speed.current_speed, 0x50);
+        assert_eq!(flingy_speed.acceleration, 0x60);
+        assert_eq!(flingy_speed.turn_speed, 0x3a);
+    }
+
+    let step_objects = analysis.step_objects().unwrap();
+    let prologue = analysis.function_prologue(step_objects, 0x10).unwrap();
+    assert_eq!(prologue.len(), 0x10);
+    assert!(analysis.function_prologue(E::VirtualAddress::from_u64(0), 0x10).is_none());
+    let (addrs, ops) = analysis.resolve_subset(
+        &[samase_scarf::AddressAnalysis::StepObjects],
+        &[samase_scarf::OperandAnalysis::Game],
+    );
+    assert_eq!(addrs, vec![analysis.step_objects()]);
+    assert_eq!(ops, vec![analysis.game()]);
+    let explained = analysis.explain(samase_scarf::AddressAnalysis::StepObjects);
+    assert!(matches!(explained, samase_scarf::FailureReason::Found));
+    let game = analysis.game().unwrap();
+    let game_signature = analysis.operand_signature(game);
+    assert!(game_signature.starts_with("Mem"));
+    assert_eq!(analysis.operand_signature(game), game_signature);
+    let ecx = if E::VirtualAddress::SIZE == 4 { "ecx" } else { "rcx" };
+    let op = ctx.mem32(ctx.register(1), 0u64.wrapping_sub(8));
+    assert_eq!(analysis.operand_signature(op), format!("Mem32[{} - 0x8]", ecx));
+    let op = ctx.and_const(ctx.register(1), 0xff);
+    assert_eq!(analysis.operand_signature(op), format!("({} & 0xff)", ecx));
+    let iscript_cases = analysis.switch_cases(samase_scarf::SwitchKind::StepIscript);
+    assert_eq!(iscript_cases.len(), 0x45);
+    assert_eq!(iscript_cases[0x28].0, 0x28);
+    let command_cases = analysis.switch_cases(samase_scarf::SwitchKind::ProcessCommands);
+    assert!(!command_cases.is_empty());
+    assert!(analysis.timings().is_empty());
+    analysis.enable_timing();
+    analysis.step_objects();
+    // Cached results aren't timed again
+    assert!(analysis.timings().is_empty());
+
     let unit_update_speed = analysis.unit_update_speed();
     // Some versions inline unit_update_speed
     // The function has randomly trash added to that function which makes inlining fail,
@@ -2541,6 +2650,44 @@ fn test_nongeneric<'e, E: ExecutionState<'e>>(
     }
     assert!(analysis.cursor_dimension_patch().is_some());
 
+    // Heuristic analyses that aren't guaranteed to work on older builds, but should
+    // keep working on current ones.
+    if version >= (1, 23, 10, b'a') {
+        check_global_opt(analysis.archive_chain_head(), binary, "archive_chain_head");
+        assert!(analysis.chat_input_handler().is_some());
+        let chat_input_buffer = analysis.chat_input_buffer().and_then(|x| x.if_constant());
+        assert!(chat_input_buffer.is_some(), "chat_input_buffer not found");
+        assert!(analysis.location_array().is_some());
+        assert!(analysis.death_counters().is_some());
+        assert!(analysis.sound_bank_handle().is_some());
+        assert!(analysis.close_game_menu().is_some());
+        assert!(analysis.init_observer().is_some());
+        check_global_opt(analysis.is_observer(), binary, "is_observer");
+        check_global_opt(analysis.is_recording_replay(), binary, "is_recording_replay");
+        assert!(analysis.mount_archive().is_some());
+        assert!(analysis.blit_pcx_to_surface().is_some());
+        check_global_opt(analysis.pcx_target_surface(), binary, "pcx_target_surface");
+        assert!(analysis.place_map_sprite().is_some());
+        assert!(analysis.is_building_fn().is_some());
+        assert!(analysis.compute_sync_checksum().is_some());
+        assert!(analysis.bullet_array().is_some());
+        assert!(analysis.ai_request_unit().is_some());
+        assert!(analysis.ai_return_to_guard().is_some());
+        assert!(analysis.add_status_overlay().is_some());
+        assert!(analysis.game_to_screen_coords().is_some());
+        check_global_opt(analysis.unit_under_cursor(), binary, "unit_under_cursor");
+        assert!(analysis.finalize_drag_select().is_some());
+        assert!(analysis.center_on_townhall().is_some());
+        assert!(analysis.iscript_death_call().is_some());
+        assert!(analysis.update_unit_facing().is_some());
+        assert!(analysis.get_terrain_height().is_some());
+        assert!(analysis.elevation_data().is_some());
+        assert!(analysis.compute_miss_chance().is_some());
+        let player_action_counts = analysis.player_action_counts()
+            .and_then(|x| x.if_constant());
+        assert!(player_action_counts.is_some(), "player_action_counts not found");
+    }
+
     let dump_text = samase_scarf::dump::dump_all(analysis);
     let compare_path = if E::VirtualAddress::SIZE == 4 {
         format!("tests/compare/{}-32.txt", filename_str)